@@ -1,6 +1,7 @@
 #!/usr/bin/env python3
 import sys
 import os
+import struct
 
 
 def generate_random_value(i, j):
@@ -8,17 +9,35 @@ def generate_random_value(i, j):
     return hash_val / 10000.0
 
 
+def write_npy(size, output_path):
+    """Write the matrix as a little-endian float64 .npy file."""
+    header = f"{{'descr': '<f8', 'fortran_order': False, 'shape': ({size}, {size}), }}"
+    # Pad so that magic, version, length and header end on a 64-byte boundary
+    padding = -(10 + len(header) + 1) % 64
+    header = header + ' ' * padding + '\n'
+    with open(output_path, 'wb') as f:
+        f.write(b'\x93NUMPY\x01\x00')
+        f.write(struct.pack('<H', len(header)))
+        f.write(header.encode('ascii'))
+        for i in range(size):
+            row = [generate_random_value(i, j) for j in range(size)]
+            f.write(struct.pack(f'<{size}d', *row))
+
+
 def generate_matrix(size, output_path):
-    """Generate a matrix and save it to a file."""
+    """Generate a matrix and save it to a file (.npy, or text otherwise)."""
     print(f"Generating {size}x{size} matrix...")
     
-    with open(output_path, 'w') as f:
-        for i in range(size):
-            row = []
-            for j in range(size):
-                value = generate_random_value(i, j)
-                row.append(str(value))
-            f.write(' '.join(row) + '\n')
+    if output_path.endswith('.npy'):
+        write_npy(size, output_path)
+    else:
+        with open(output_path, 'w') as f:
+            for i in range(size):
+                row = []
+                for j in range(size):
+                    value = generate_random_value(i, j)
+                    row.append(str(value))
+                f.write(' '.join(row) + '\n')
     
     file_size = os.path.getsize(output_path)
     print(f"Matrix saved: {file_size} bytes ({file_size / (1024 * 1024):.2f} MB)")
@@ -43,6 +62,7 @@ def main():
         print("", file=sys.stderr)
         print(f"Example: {sys.argv[0]} matrix_a.txt matrix_b.txt", file=sys.stderr)
         print(f"Example: {sys.argv[0]} 1000 matrix_a.txt matrix_b.txt", file=sys.stderr)
+        print(f"Example: {sys.argv[0]} 1000 matrix_a.npy matrix_b.npy", file=sys.stderr)
         sys.exit(1)
     
     print(f"Generating two {size}x{size} matrices (~{(size * size * 4) / (1024 * 1024)}MB each)...")
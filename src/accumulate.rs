@@ -0,0 +1,360 @@
+//! Accumulation modes for dot products
+//!
+//! The blocked kernel sums each dot product term by term, so its rounding
+//! error grows with the inner dimension. The other modes trade speed for
+//! accuracy: pairwise summation bounds the error by `O(log k)` instead of
+//! `O(k)`, compensated summation carries the rounding error of each addition
+//! along in a separate sum, and double-double keeps every sum and product in
+//! roughly twice the working precision. Integer sums are exact, so integer
+//! matrices ignore the mode.
+//!
+//! # Reproducibility
+//!
+//! With [`GemmSpec::reproducible`](crate::kernel::GemmSpec::reproducible)
+//! set, every entry of the result is computed as
+//! `beta * c + alpha * dot(row of op(A), column of op(B))`, the dot product
+//! running over the inner dimension in ascending order in the selected mode
+//! with plain scalar arithmetic. Nothing in that depends on the number of
+//! workers or threads, on which rows a worker was given, on the multiplication
+//! algorithm or on the SIMD level of the machine, so the output is identical
+//! bit for bit across all of them. The dot-product kernel is also used for
+//! every mode except naive, which makes those modes reproducible as well.
+
+use crate::element::Element;
+use crate::error::{Error, Result};
+use crate::kernel::Strided;
+use num_complex::Complex;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// How the terms of each dot product in a product are summed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Accumulation {
+    /// One running sum, as the blocked kernel does
+    #[default]
+    Naive,
+    /// Recursive halving: sums of sums of blocks of terms
+    Pairwise,
+    /// Kahan-Babuska-Neumaier compensated summation, to second order
+    Compensated,
+    /// Double-double arithmetic with error-free products
+    DoubleDouble,
+}
+
+impl Accumulation {
+    pub const ALL: [Accumulation; 4] = [
+        Accumulation::Naive,
+        Accumulation::Pairwise,
+        Accumulation::Compensated,
+        Accumulation::DoubleDouble,
+    ];
+
+    /// Name as accepted by `--accumulation`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Accumulation::Naive => "naive",
+            Accumulation::Pairwise => "pairwise",
+            Accumulation::Compensated => "kahan",
+            Accumulation::DoubleDouble => "double-double",
+        }
+    }
+
+    /// Parse a mode name; `neumaier` and `dd` are accepted as aliases
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "neumaier" | "compensated" => Ok(Accumulation::Compensated),
+            "dd" => Ok(Accumulation::DoubleDouble),
+            _ => Accumulation::ALL
+                .into_iter()
+                .find(|mode| mode.name() == name)
+                .ok_or_else(|| Error::invalid_argument("accumulation mode", name)),
+        }
+    }
+}
+
+impl fmt::Display for Accumulation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Compute `C += alpha * A * B` with every dot product summed in `mode`,
+/// splitting the rows of C across `pool` if one is given
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn gemm<T: Element>(
+    mode: Accumulation,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: T,
+    a: Strided<'_, T>,
+    b: Strided<'_, T>,
+    c: &mut [T],
+    ldc: usize,
+    pool: Option<&ThreadPool>,
+) {
+    if m == 0 || n == 0 {
+        return;
+    }
+
+    // Columns of B, each contiguous
+    let mut b_cols = vec![T::zero(); n * k];
+    for j in 0..n {
+        for p in 0..k {
+            b_cols[j * k + p] = b.at(p, j);
+        }
+    }
+    let row = |i: usize, c_row: &mut [T], a_row: &mut Vec<T>| {
+        a_row.clear();
+        a_row.extend((0..k).map(|p| a.at(i, p)));
+        for (j, c) in c_row[..n].iter_mut().enumerate() {
+            *c += alpha * T::dot(mode, a_row, &b_cols[j * k..(j + 1) * k]);
+        }
+    };
+
+    // C may be a sub-block of a wider matrix: stop at the end of its last row
+    let c = &mut c[..(m - 1) * ldc + n];
+    match pool {
+        Some(pool) => pool.install(|| {
+            c.par_chunks_mut(ldc)
+                .enumerate()
+                .for_each_init(Vec::new, |a_row, (i, c_row)| row(i, c_row, a_row))
+        }),
+        None => {
+            let mut a_row = Vec::with_capacity(k);
+            for (i, c_row) in c.chunks_mut(ldc).enumerate() {
+                row(i, c_row, &mut a_row);
+            }
+        }
+    }
+}
+
+/// Real floating-point types the accumulation modes work on
+pub(crate) trait Float:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+
+    fn abs(self) -> Self;
+
+    /// `self * a + b` with a single rounding
+    fn mul_add(self, a: Self, b: Self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($ty:ty) => {
+        impl Float for $ty {
+            const ZERO: $ty = 0.0;
+
+            fn abs(self) -> $ty {
+                <$ty>::abs(self)
+            }
+
+            fn mul_add(self, a: $ty, b: $ty) -> $ty {
+                <$ty>::mul_add(self, a, b)
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+/// Running sum of products
+trait Accumulator<F: Float> {
+    fn new() -> Self;
+
+    fn add_product(&mut self, x: F, y: F);
+
+    fn finish(self) -> F;
+}
+
+struct Naive<F>(F);
+
+impl<F: Float> Accumulator<F> for Naive<F> {
+    fn new() -> Self {
+        Naive(F::ZERO)
+    }
+
+    fn add_product(&mut self, x: F, y: F) {
+        self.0 = self.0 + x * y;
+    }
+
+    fn finish(self) -> F {
+        self.0
+    }
+}
+
+/// Terms summed naively before the block joins the pairwise tree
+const PAIRWISE_BLOCK: usize = 8;
+
+/// Pairwise summation of a stream: completed blocks are merged like a binary
+/// counter, so `levels[l]` holds the sum of `2^l` blocks
+struct Pairwise<F> {
+    block: F,
+    count: usize,
+    levels: [F; 64],
+    occupied: u64,
+}
+
+impl<F: Float> Accumulator<F> for Pairwise<F> {
+    fn new() -> Self {
+        Pairwise {
+            block: F::ZERO,
+            count: 0,
+            levels: [F::ZERO; 64],
+            occupied: 0,
+        }
+    }
+
+    fn add_product(&mut self, x: F, y: F) {
+        self.block = self.block + x * y;
+        self.count += 1;
+        if self.count == PAIRWISE_BLOCK {
+            let mut carry = std::mem::replace(&mut self.block, F::ZERO);
+            let mut level = 0;
+            while self.occupied >> level & 1 == 1 {
+                carry = self.levels[level] + carry;
+                self.occupied &= !(1 << level);
+                level += 1;
+            }
+            self.levels[level] = carry;
+            self.occupied |= 1 << level;
+            self.count = 0;
+        }
+    }
+
+    fn finish(self) -> F {
+        let mut sum = self.block;
+        for level in 0..64 {
+            if self.occupied >> level & 1 == 1 {
+                sum = self.levels[level] + sum;
+            }
+        }
+        sum
+    }
+}
+
+/// Neumaier's variant of Kahan summation, which also holds up when a term
+/// is larger than the running sum, with Klein's second-order correction: the
+/// compensation is itself summed the same way, so that it does not drift
+/// over very long sums
+struct Compensated<F> {
+    sum: F,
+    compensation: F,
+    second_order: F,
+}
+
+/// Sum `a + b` and the rounding error it commits (Fast2Sum on the larger)
+fn sum_with_error<F: Float>(a: F, b: F) -> (F, F) {
+    let sum = a + b;
+    let error = if a.abs() >= b.abs() {
+        (a - sum) + b
+    } else {
+        (b - sum) + a
+    };
+    (sum, error)
+}
+
+impl<F: Float> Accumulator<F> for Compensated<F> {
+    fn new() -> Self {
+        Compensated {
+            sum: F::ZERO,
+            compensation: F::ZERO,
+            second_order: F::ZERO,
+        }
+    }
+
+    fn add_product(&mut self, x: F, y: F) {
+        let (sum, error) = sum_with_error(self.sum, x * y);
+        let (compensation, error) = sum_with_error(self.compensation, error);
+        self.sum = sum;
+        self.compensation = compensation;
+        self.second_order = self.second_order + error;
+    }
+
+    fn finish(self) -> F {
+        self.sum + (self.compensation + self.second_order)
+    }
+}
+
+/// Unevaluated sum `hi + lo` with `|lo|` at most half an ulp of `hi`
+struct DoubleDouble<F> {
+    hi: F,
+    lo: F,
+}
+
+impl<F: Float> Accumulator<F> for DoubleDouble<F> {
+    fn new() -> Self {
+        DoubleDouble {
+            hi: F::ZERO,
+            lo: F::ZERO,
+        }
+    }
+
+    fn add_product(&mut self, x: F, y: F) {
+        // Exact product p + e, then an exact sum s + t (Knuth's TwoSum)
+        let p = x * y;
+        let e = x.mul_add(y, -p);
+        let s = self.hi + p;
+        let v = s - self.hi;
+        let t = (self.hi - (s - v)) + (p - v);
+        // Fold the error terms in and renormalize
+        let lo = self.lo + t + e;
+        self.hi = s + lo;
+        self.lo = lo - (self.hi - s);
+    }
+
+    fn finish(self) -> F {
+        self.hi + self.lo
+    }
+}
+
+fn dot_with<F: Float, A: Accumulator<F>>(products: impl Iterator<Item = (F, F)>) -> F {
+    let mut accumulator = A::new();
+    for (x, y) in products {
+        accumulator.add_product(x, y);
+    }
+    accumulator.finish()
+}
+
+/// Sum of the products in `mode`
+fn sum_products<F: Float>(mode: Accumulation, products: impl Iterator<Item = (F, F)>) -> F {
+    match mode {
+        Accumulation::Naive => dot_with::<F, Naive<F>>(products),
+        Accumulation::Pairwise => dot_with::<F, Pairwise<F>>(products),
+        Accumulation::Compensated => dot_with::<F, Compensated<F>>(products),
+        Accumulation::DoubleDouble => dot_with::<F, DoubleDouble<F>>(products),
+    }
+}
+
+/// Real dot product `sum x[i] * y[i]` accumulated in `mode`
+pub(crate) fn real_dot<F: Float>(mode: Accumulation, x: &[F], y: &[F]) -> F {
+    sum_products(mode, x.iter().copied().zip(y.iter().copied()))
+}
+
+/// Complex dot product (without conjugation), accumulating the real and
+/// imaginary parts as real sums of twice the length
+pub(crate) fn complex_dot<F: Float>(
+    mode: Accumulation,
+    x: &[Complex<F>],
+    y: &[Complex<F>],
+) -> Complex<F> {
+    let pairs = || x.iter().zip(y);
+    let re = sum_products(
+        mode,
+        pairs().flat_map(|(x, y)| [(x.re, y.re), (-x.im, y.im)]),
+    );
+    let im = sum_products(
+        mode,
+        pairs().flat_map(|(x, y)| [(x.re, y.im), (x.im, y.re)]),
+    );
+    Complex::new(re, im)
+}
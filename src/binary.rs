@@ -0,0 +1,440 @@
+//! Native binary matrix format
+//!
+//! A file is a 32-byte header, the elements in row-major order and, if the
+//! header says so, a CRC-32 of the element bytes:
+//!
+//! | offset | size | field                                              |
+//! |--------|------|----------------------------------------------------|
+//! | 0      | 8    | magic number `DMMATRIX`                            |
+//! | 8      | 1    | format version, currently 1                        |
+//! | 9      | 1    | byte order: 0 little-endian, 1 big-endian          |
+//! | 10     | 1    | element type: 1 f32, 2 f64, 3 i32, 4 i64, 5 c64, 6 c128 |
+//! | 11     | 1    | flags: bit 0 set if a checksum follows the data    |
+//! | 12     | 4    | reserved, zero                                     |
+//! | 16     | 8    | rows, unsigned                                     |
+//! | 24     | 8    | columns, unsigned                                  |
+//! | 32     |      | elements; complex ones as real then imaginary part |
+//! |        | 4    | CRC-32 (IEEE) of the element bytes, if flagged     |
+//!
+//! Multi-byte fields use the file's byte order. Writers use the native order
+//! so that saving needs no conversion; readers convert when it differs.
+
+use crate::element::{Dtype, Element};
+use crate::error::{Error, Result};
+use crate::matrix::Matrix;
+use crate::view::MatrixView;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// First bytes of every binary matrix file
+pub const MAGIC: [u8; 8] = *b"DMMATRIX";
+
+/// Format version written by this crate
+pub const VERSION: u8 = 1;
+
+/// File extension that [`Matrix::save`] writes in the binary format
+pub const EXTENSION: &str = "bin";
+
+const FLAG_CHECKSUM: u8 = 1;
+
+/// Decoded header of a binary matrix file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub version: u8,
+    pub big_endian: bool,
+    pub dtype: Dtype,
+    /// A CRC-32 of the element bytes follows them
+    pub checksum: bool,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl Header {
+    /// Size of the encoded header in bytes
+    pub const SIZE: usize = 32;
+
+    /// Header for a native-endian file written by this version
+    pub fn new(dtype: Dtype, rows: usize, cols: usize, checksum: bool) -> Self {
+        Header {
+            version: VERSION,
+            big_endian: cfg!(target_endian = "big"),
+            dtype,
+            checksum,
+            rows,
+            cols,
+        }
+    }
+
+    pub fn encode(&self) -> [u8; Header::SIZE] {
+        let mut bytes = [0u8; Header::SIZE];
+        bytes[..8].copy_from_slice(&MAGIC);
+        bytes[8] = self.version;
+        bytes[9] = self.big_endian as u8;
+        bytes[10] = dtype_code(self.dtype);
+        bytes[11] = if self.checksum { FLAG_CHECKSUM } else { 0 };
+        let (rows, cols) = (self.rows as u64, self.cols as u64);
+        let (rows, cols) = if self.big_endian {
+            (rows.to_be_bytes(), cols.to_be_bytes())
+        } else {
+            (rows.to_le_bytes(), cols.to_le_bytes())
+        };
+        bytes[16..24].copy_from_slice(&rows);
+        bytes[24..32].copy_from_slice(&cols);
+        bytes
+    }
+
+    /// Decode and validate a header
+    pub fn decode(bytes: &[u8; Header::SIZE]) -> Result<Self> {
+        if bytes[..8] != MAGIC {
+            return Err(format_error("Not a binary matrix file (bad magic number)"));
+        }
+        let version = bytes[8];
+        if version != VERSION {
+            return Err(format_error(format!(
+                "Unsupported format version {}",
+                version
+            )));
+        }
+        let big_endian = match bytes[9] {
+            0 => false,
+            1 => true,
+            order => return Err(format_error(format!("Invalid byte order {}", order))),
+        };
+        let dtype = Dtype::ALL
+            .into_iter()
+            .find(|&dtype| dtype_code(dtype) == bytes[10])
+            .ok_or_else(|| format_error(format!("Unknown element type code {}", bytes[10])))?;
+        let flags = bytes[11];
+        if flags & !FLAG_CHECKSUM != 0 {
+            return Err(format_error(format!("Unsupported flags {:#04x}", flags)));
+        }
+
+        let dim = |field: &[u8]| {
+            let field = field.try_into().expect("eight header bytes");
+            let value = if big_endian {
+                u64::from_be_bytes(field)
+            } else {
+                u64::from_le_bytes(field)
+            };
+            usize::try_from(value)
+                .map_err(|_| format_error(format!("Dimension {} is too large", value)))
+        };
+        let header = Header {
+            version,
+            big_endian,
+            dtype,
+            checksum: flags & FLAG_CHECKSUM != 0,
+            rows: dim(&bytes[16..24])?,
+            cols: dim(&bytes[24..32])?,
+        };
+        header.data_len()?;
+        Ok(header)
+    }
+
+    /// Number of bytes of element data following the header
+    pub fn data_len(&self) -> Result<usize> {
+        self.rows
+            .checked_mul(self.cols)
+            .and_then(|len| len.checked_mul(self.dtype.size()))
+            .ok_or_else(|| {
+                format_error(format!(
+                    "Dimensions {}x{} are too large",
+                    self.rows, self.cols
+                ))
+            })
+    }
+
+    /// Read the header at the start of a binary matrix file
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut file = File::open(path).map_err(|e| Error::io(Some(path), e))?;
+        read_header(&mut file).map_err(|e| in_file(e, path))
+    }
+}
+
+/// Whether the file at `path` starts with the binary format's magic number
+pub fn is_binary_file<P: AsRef<Path>>(path: P) -> Result<bool> {
+    file_starts_with(path.as_ref(), &MAGIC)
+}
+
+/// Whether the file at `path` starts with `magic`
+pub(crate) fn file_starts_with(path: &Path, magic: &[u8]) -> Result<bool> {
+    Ok(file_start(path, magic.len())? == magic)
+}
+
+/// Up to the first `len` bytes of the file at `path`
+pub(crate) fn file_start(path: &Path, len: usize) -> Result<Vec<u8>> {
+    let file = File::open(path).map_err(|e| Error::io(Some(path), e))?;
+    let mut start = Vec::with_capacity(len);
+    file.take(len as u64)
+        .read_to_end(&mut start)
+        .map_err(|e| Error::io(Some(path), e))?;
+    Ok(start)
+}
+
+impl<T: Element> Matrix<T> {
+    /// Load a matrix from a file in the binary format
+    pub fn load_binary<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| Error::io(Some(path), e))?;
+        Matrix::read_binary(BufReader::new(file)).map_err(|e| in_file(e, path))
+    }
+
+    /// Read a matrix in the binary format, checking the checksum if present
+    pub fn read_binary<R: Read>(mut reader: R) -> Result<Self> {
+        let header = read_header(&mut reader)?;
+        if header.dtype != T::DTYPE {
+            return Err(format_error(format!(
+                "File holds {} elements, but {} was requested",
+                header.dtype,
+                T::DTYPE
+            )));
+        }
+
+        // Read through `take` rather than allocating whatever the header
+        // claims up front, so a corrupt header fails as a short read
+        let len = header.data_len()?;
+        let mut bytes = Vec::new();
+        (&mut reader)
+            .take(len as u64)
+            .read_to_end(&mut bytes)
+            .map_err(|e| Error::io(None, e))?;
+        if bytes.len() != len {
+            return Err(format_error("File is truncated"));
+        }
+        if header.checksum {
+            let mut stored = [0u8; 4];
+            read_exact(&mut reader, &mut stored)?;
+            let stored = if header.big_endian {
+                u32::from_be_bytes(stored)
+            } else {
+                u32::from_le_bytes(stored)
+            };
+            let mut crc = Crc32::new();
+            crc.update(&bytes);
+            if crc.finish() != stored {
+                return Err(format_error(
+                    "Checksum mismatch: the element data is corrupt",
+                ));
+            }
+        }
+        let mut rest = [0u8; 1];
+        if reader.read(&mut rest).map_err(|e| Error::io(None, e))? != 0 {
+            return Err(format_error("Unexpected data after the matrix"));
+        }
+
+        let data = bytes
+            .chunks_exact(T::DTYPE.size())
+            .map(|element| T::read(element, header.big_endian))
+            .collect();
+        Matrix::from_vec(data, header.rows, header.cols)
+    }
+
+    /// Save a matrix to a file in the binary format, optionally followed by
+    /// a checksum of the element data
+    pub fn save_binary<P: AsRef<Path>>(&self, path: P, checksum: bool) -> Result<()> {
+        self.view().save_binary(path, checksum)
+    }
+}
+
+impl<T: Element> MatrixView<'_, T> {
+    /// Save the viewed elements to a file in the binary format
+    pub fn save_binary<P: AsRef<Path>>(&self, path: P, checksum: bool) -> Result<()> {
+        let path = path.as_ref();
+        let write = || -> io::Result<()> {
+            let mut writer = BufWriter::new(File::create(path)?);
+            self.write_binary(&mut writer, checksum)?;
+            writer.flush()
+        };
+        write().map_err(|e| Error::io(Some(path), e))
+    }
+
+    /// Write the viewed elements in the binary format
+    pub fn write_binary<W: Write>(&self, writer: &mut W, checksum: bool) -> io::Result<()> {
+        let header = Header::new(T::DTYPE, self.rows(), self.cols(), checksum);
+        writer.write_all(&header.encode())?;
+
+        let mut crc = Crc32::new();
+        write_rows(writer, &mut crc, self)?;
+        if checksum {
+            writer.write_all(&crc.finish().to_ne_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a matrix in the binary format a block of rows at a time, so that
+/// the whole matrix never has to be in memory
+pub struct BinaryWriter<W: Write, T = f64> {
+    writer: W,
+    header: Header,
+    rows_written: usize,
+    crc: Crc32,
+    element: PhantomData<T>,
+}
+
+impl<W: Write, T: Element> BinaryWriter<W, T> {
+    /// Start a `rows x cols` matrix by writing its header
+    pub fn new(mut writer: W, rows: usize, cols: usize, checksum: bool) -> Result<Self> {
+        let header = Header::new(T::DTYPE, rows, cols, checksum);
+        header.data_len()?;
+        writer
+            .write_all(&header.encode())
+            .map_err(|e| Error::io(None, e))?;
+        Ok(BinaryWriter {
+            writer,
+            header,
+            rows_written: 0,
+            crc: Crc32::new(),
+            element: PhantomData,
+        })
+    }
+
+    /// Rows and columns of the whole matrix
+    pub fn dims(&self) -> (usize, usize) {
+        (self.header.rows, self.header.cols)
+    }
+
+    /// Number of rows written so far
+    pub fn rows_written(&self) -> usize {
+        self.rows_written
+    }
+
+    /// Append the viewed rows after those already written
+    pub fn write_rows(&mut self, rows: MatrixView<'_, T>) -> Result<()> {
+        let remaining = self.header.rows - self.rows_written;
+        if rows.cols() != self.header.cols || rows.rows() > remaining {
+            return Err(Error::DimensionMismatch {
+                operation: "append rows",
+                left: (remaining, self.header.cols),
+                right: (rows.rows(), rows.cols()),
+            });
+        }
+        write_rows(&mut self.writer, &mut self.crc, &rows).map_err(|e| Error::io(None, e))?;
+        self.rows_written += rows.rows();
+        Ok(())
+    }
+
+    /// Write the checksum, if any, once every row is written and hand back
+    /// the underlying writer
+    pub fn finish(mut self) -> Result<W> {
+        if self.rows_written != self.header.rows {
+            return Err(format_error(format!(
+                "Only {} of {} rows were written",
+                self.rows_written, self.header.rows
+            )));
+        }
+        if self.header.checksum {
+            self.writer
+                .write_all(&self.crc.finish().to_ne_bytes())
+                .map_err(|e| Error::io(None, e))?;
+        }
+        Ok(self.writer)
+    }
+}
+
+/// Write the viewed elements in native byte order, adding them to `crc`
+fn write_rows<W: Write, T: Element>(
+    writer: &mut W,
+    crc: &mut Crc32,
+    view: &MatrixView<'_, T>,
+) -> io::Result<()> {
+    let mut row = Vec::with_capacity(view.cols() * T::DTYPE.size());
+    for i in 0..view.rows() {
+        row.clear();
+        for j in 0..view.cols() {
+            view.at(i, j).write_ne(&mut row);
+        }
+        crc.update(&row);
+        writer.write_all(&row)?;
+    }
+    Ok(())
+}
+
+/// Stable on-disk code of an element type
+fn dtype_code(dtype: Dtype) -> u8 {
+    match dtype {
+        Dtype::F32 => 1,
+        Dtype::F64 => 2,
+        Dtype::I32 => 3,
+        Dtype::I64 => 4,
+        Dtype::C64 => 5,
+        Dtype::C128 => 6,
+    }
+}
+
+fn read_header<R: Read>(reader: &mut R) -> Result<Header> {
+    let mut bytes = [0u8; Header::SIZE];
+    read_exact(reader, &mut bytes)?;
+    Header::decode(&bytes)
+}
+
+/// `read_exact`, reporting a short read as a truncated file
+pub(crate) fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<()> {
+    reader.read_exact(buf).map_err(|e| {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            format_error("File is truncated")
+        } else {
+            Error::io(None, e)
+        }
+    })
+}
+
+/// Format error without a location, which callers attach as they learn it
+pub(crate) fn format_error(message: impl Into<String>) -> Error {
+    Error::Format {
+        path: None,
+        line: None,
+        column: None,
+        message: message.into(),
+    }
+}
+
+/// Attach `path` to an error from reading or writing that file
+pub(crate) fn in_file(error: Error, path: &Path) -> Error {
+    match error {
+        Error::Io { path: None, source } => Error::io(Some(path), source),
+        error => error.at(path, None),
+    }
+}
+
+/// CRC-32 with the IEEE polynomial, as used by zip and PNG
+pub(crate) struct Crc32(u32);
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                0xEDB8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+impl Crc32 {
+    pub(crate) fn new() -> Self {
+        Crc32(!0)
+    }
+
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = CRC_TABLE[((self.0 ^ byte as u32) & 0xFF) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u32 {
+        !self.0
+    }
+}
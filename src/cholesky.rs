@@ -0,0 +1,187 @@
+//! Cholesky decomposition of symmetric (Hermitian) positive-definite matrices
+//!
+//! Factorizes `A = L L^H` one column block at a time: the block's columns are
+//! factored directly, and the trailing matrix is then updated with a single
+//! product through the blocked GEMM kernel. Only the lower triangle of A is
+//! read. A pivot that is not positive means A is not positive definite.
+
+use crate::element::{Element, Field, Real};
+use crate::error::{Error, Result};
+use crate::kernel::{self, axpy, Strided, TileConfig};
+use crate::matrix::Matrix;
+use std::cmp::Ordering;
+
+/// Columns factored per block before the trailing update
+const BLOCK: usize = 64;
+
+/// Cholesky factorization `A = L L^H` of a positive-definite matrix
+#[derive(Debug, Clone)]
+pub struct Cholesky<T = f64> {
+    /// Lower-triangular factor; zero above the diagonal
+    l: Matrix<T>,
+}
+
+impl<T: Field> Matrix<T> {
+    /// Factorize this symmetric (Hermitian) positive-definite matrix as `L L^H`
+    ///
+    /// Only the lower triangle is read; the upper one is assumed to mirror it.
+    pub fn cholesky(&self) -> Result<Cholesky<T>> {
+        Cholesky::new(self)
+    }
+}
+
+impl<T: Field> Cholesky<T> {
+    /// Factorize the positive-definite matrix `a`
+    pub fn new(a: &Matrix<T>) -> Result<Self> {
+        if a.rows != a.cols {
+            return Err(Error::NotSquare {
+                operation: "Cholesky decomposition",
+                rows: a.rows,
+                cols: a.cols,
+            });
+        }
+        let n = a.rows;
+        let mut l = a.clone();
+        let tiles = TileConfig::default();
+
+        for kb in (0..n).step_by(BLOCK) {
+            let ke = (kb + BLOCK).min(n);
+            factor_block(&mut l.data, n, kb, ke)?;
+
+            // A22 -= L21 L21^H over the trailing matrix
+            let m = n - ke;
+            if m == 0 {
+                continue;
+            }
+            let nb = ke - kb;
+            let mut panel = vec![T::zero(); m * nb];
+            let mut panel_h = vec![T::zero(); nb * m];
+            for i in 0..m {
+                for j in 0..nb {
+                    let value = l.data[(ke + i) * n + kb + j];
+                    panel[i * nb + j] = value;
+                    panel_h[j * m + i] = value.conj();
+                }
+            }
+            kernel::gemm(
+                m,
+                m,
+                nb,
+                -T::one(),
+                Strided::row_major(&panel, nb),
+                Strided::row_major(&panel_h, m),
+                &mut l.data[ke * n + ke..],
+                n,
+                &tiles,
+            );
+        }
+
+        for i in 0..n {
+            l.data[i * n + i + 1..(i + 1) * n].fill(T::zero());
+        }
+        Ok(Cholesky { l })
+    }
+
+    /// Lower-triangular factor L
+    pub fn l(&self) -> &Matrix<T> {
+        &self.l
+    }
+
+    /// Consume the factorization, returning L
+    pub fn into_l(self) -> Matrix<T> {
+        self.l
+    }
+
+    /// Determinant of the factorized matrix, the squared product of L's diagonal
+    pub fn determinant(&self) -> T::Real {
+        let n = self.l.rows;
+        let product = (0..n).fold(T::Real::one(), |acc, i| acc * self.l.data[i * n + i].real());
+        product * product
+    }
+
+    /// Solve `A X = B` for X, one column of X per column of `b`
+    pub fn solve(&self, b: &Matrix<T>) -> Result<Matrix<T>> {
+        let y = self.solve_lower(b)?;
+        self.solve_upper(&y)
+    }
+
+    /// Forward substitution: solve `L Y = B` for Y
+    pub fn solve_lower(&self, b: &Matrix<T>) -> Result<Matrix<T>> {
+        let n = self.check_rhs(b)?;
+        let m = b.cols;
+        let mut y = b.clone();
+        for i in 0..n {
+            let (done, rest) = y.data.split_at_mut(i * m);
+            let row = &mut rest[..m];
+            for j in 0..i {
+                let lij = self.l.data[i * n + j];
+                if lij != T::zero() {
+                    axpy(row, -lij, &done[j * m..(j + 1) * m]);
+                }
+            }
+            let pivot = self.l.data[i * n + i];
+            row.iter_mut().for_each(|v| *v /= pivot);
+        }
+        Ok(y)
+    }
+
+    /// Back substitution: solve `L^H X = Y` for X
+    pub fn solve_upper(&self, y: &Matrix<T>) -> Result<Matrix<T>> {
+        let n = self.check_rhs(y)?;
+        let m = y.cols;
+        let mut x = y.clone();
+        for i in (0..n).rev() {
+            let (head, done) = x.data.split_at_mut((i + 1) * m);
+            let row = &mut head[i * m..];
+            for j in i + 1..n {
+                // Row i of L^H is column i of L, conjugated
+                let lji = self.l.data[j * n + i];
+                if lji != T::zero() {
+                    axpy(row, -lji.conj(), &done[(j - i - 1) * m..(j - i) * m]);
+                }
+            }
+            let pivot = self.l.data[i * n + i];
+            row.iter_mut().for_each(|v| *v /= pivot);
+        }
+        Ok(x)
+    }
+
+    fn check_rhs(&self, b: &Matrix<T>) -> Result<usize> {
+        let n = self.l.rows;
+        if b.rows != n {
+            return Err(Error::DimensionMismatch {
+                operation: "solve",
+                left: (n, n),
+                right: (b.rows, b.cols),
+            });
+        }
+        Ok(n)
+    }
+}
+
+/// Factor columns `[kb, ke)` of the row-major `n x n` matrix in `data`, whose
+/// earlier blocks have already been subtracted, down to the last row
+fn factor_block<T: Field>(data: &mut [T], n: usize, kb: usize, ke: usize) -> Result<()> {
+    for j in kb..ke {
+        let mut diagonal = data[j * n + j].real();
+        for p in kb..j {
+            let ljp = data[j * n + p].abs();
+            diagonal -= ljp * ljp;
+        }
+        // Also rejects NaN
+        if diagonal.partial_cmp(&T::Real::zero()) != Some(Ordering::Greater) {
+            return Err(Error::NotPositiveDefinite { pivot: j });
+        }
+        let ljj = diagonal.sqrt();
+        data[j * n + j] = T::from_real(ljj);
+
+        for i in j + 1..n {
+            let mut value = data[i * n + j];
+            for p in kb..j {
+                value -= data[i * n + p] * data[j * n + p].conj();
+            }
+            data[i * n + j] = value / T::from_real(ljj);
+        }
+    }
+    Ok(())
+}
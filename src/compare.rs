@@ -0,0 +1,134 @@
+//! Tolerance-aware comparison of matrices
+//!
+//! Two elements `a` and `b` agree when `|a - b| <= max(abs_tol, rel_tol *
+//! max(|a|, |b|))`, so the absolute tolerance governs values near zero and
+//! the relative one everything else. Equal infinities agree; NaN never does.
+
+use crate::element::Approx;
+use crate::error::{Error, Result};
+use crate::matrix::Matrix;
+use std::fmt;
+
+/// Tolerance used by the `diff` command when none is given
+pub const DEFAULT_TOLERANCE: f64 = 1e-5;
+
+/// Error statistics of one matrix against another of the same shape
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    /// Number of elements compared
+    pub elements: usize,
+    /// Elements that do not agree within the tolerances
+    pub mismatches: usize,
+    /// Largest `|a - b|` and the first position where it occurs
+    pub max_abs_error: f64,
+    pub max_abs_position: Option<(usize, usize)>,
+    /// Largest `|a - b| / max(|a|, |b|)` and the first position where it occurs
+    pub max_rel_error: f64,
+    pub max_rel_position: Option<(usize, usize)>,
+    /// Largest distance in units in the last place and the first position
+    /// where it occurs
+    pub max_ulp_distance: u64,
+    pub max_ulp_position: Option<(usize, usize)>,
+}
+
+impl Comparison {
+    /// Whether every element agreed within the tolerances
+    pub fn passed(&self) -> bool {
+        self.mismatches == 0
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let at = |position: Option<(usize, usize)>| match position {
+            Some((row, col)) => format!(" at ({}, {})", row, col),
+            None => String::new(),
+        };
+        writeln!(
+            f,
+            "{} of {} elements outside tolerance",
+            self.mismatches, self.elements
+        )?;
+        writeln!(
+            f,
+            "  max absolute error: {:e}{}",
+            self.max_abs_error,
+            at(self.max_abs_position)
+        )?;
+        writeln!(
+            f,
+            "  max relative error: {:e}{}",
+            self.max_rel_error,
+            at(self.max_rel_position)
+        )?;
+        write!(
+            f,
+            "  max ULP distance:   {}{}",
+            self.max_ulp_distance,
+            at(self.max_ulp_position)
+        )
+    }
+}
+
+impl<T: Approx> Matrix<T> {
+    /// Whether both matrices have the same shape and every pair of elements
+    /// agrees within the tolerances
+    pub fn approx_eq(&self, other: &Matrix<T>, abs_tol: f64, rel_tol: f64) -> bool {
+        self.compare(other, abs_tol, rel_tol)
+            .is_ok_and(|comparison| comparison.passed())
+    }
+
+    /// Compare against `other` element by element, counting the pairs that
+    /// do not agree within the tolerances
+    pub fn compare(&self, other: &Matrix<T>, abs_tol: f64, rel_tol: f64) -> Result<Comparison> {
+        if self.rows != other.rows || self.cols != other.cols {
+            return Err(Error::DimensionMismatch {
+                operation: "compare",
+                left: (self.rows, self.cols),
+                right: (other.rows, other.cols),
+            });
+        }
+
+        let mut comparison = Comparison {
+            elements: self.data.len(),
+            mismatches: 0,
+            max_abs_error: 0.0,
+            max_abs_position: None,
+            max_rel_error: 0.0,
+            max_rel_position: None,
+            max_ulp_distance: 0,
+            max_ulp_position: None,
+        };
+        for (index, (&a, &b)) in self.data.iter().zip(&other.data).enumerate() {
+            let position = Some((index / self.cols, index % self.cols));
+            let abs_error = a.abs_diff(b);
+            let scale = a.magnitude().max(b.magnitude());
+            // Unequal pairs involving infinity or NaN have an infinite error
+            let rel_error = if abs_error == 0.0 {
+                0.0
+            } else if abs_error.is_infinite() {
+                f64::INFINITY
+            } else {
+                abs_error / scale
+            };
+            let ulp_distance = a.ulp_distance(b);
+
+            if abs_error.is_infinite() || abs_error > abs_tol.max(rel_tol * scale) {
+                comparison.mismatches += 1;
+            }
+            if abs_error > comparison.max_abs_error {
+                comparison.max_abs_error = abs_error;
+                comparison.max_abs_position = position;
+            }
+            if rel_error > comparison.max_rel_error {
+                comparison.max_rel_error = rel_error;
+                comparison.max_rel_position = position;
+            }
+            if ulp_distance > comparison.max_ulp_distance {
+                comparison.max_ulp_distance = ulp_distance;
+                comparison.max_ulp_position = position;
+            }
+        }
+        Ok(comparison)
+    }
+}
@@ -1,24 +1,50 @@
-use crate::matrix::Matrix;
+use crate::binary::{self, in_file, BinaryWriter};
+use crate::csv::CsvOverrides;
+use crate::element::Element;
+use crate::error::{Error, Result};
+use crate::kernel::GemmSpec;
+use crate::mapped::MappedMatrix;
+use crate::matrix::{has_extension, Matrix};
 use crate::mpi_utils::*;
+use crate::view::MatrixView;
 use mpi::traits::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::path::Path;
 
 pub struct Coordinator<C: Communicator> {
     world: C,
     worker_count: usize,
+    csv: CsvOverrides,
+    verify_checksums: bool,
 }
 
 impl<C: Communicator> Coordinator<C> {
     /// Create a new coordinator
     pub fn new(world: C) -> Self {
         let size = world.size() as usize;
-        let worker_count = if size > 1 { size - 1 } else { 0 };
+        let worker_count = size.saturating_sub(1);
         Coordinator {
             world,
             worker_count,
+            csv: CsvOverrides::default(),
+            verify_checksums: false,
         }
     }
 
+    /// Set how CSV and TSV files are laid out, both the inputs and the result
+    pub fn with_csv(mut self, csv: CsvOverrides) -> Self {
+        self.csv = csv;
+        self
+    }
+
+    /// Set whether the checksums of binary operands are verified before any
+    /// work is sent; off by default since it reads every operand in full
+    pub fn with_checksum_verification(mut self, verify: bool) -> Self {
+        self.verify_checksums = verify;
+        self
+    }
+
     /// Get the number of workers
     pub fn worker_count(&self) -> usize {
         self.worker_count
@@ -30,40 +56,311 @@ impl<C: Communicator> Coordinator<C> {
         matrix_a_path: &Path,
         matrix_b_path: &Path,
         output_path: &Path,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
+        self.multiply_matrices_as::<f64>(matrix_a_path, matrix_b_path, output_path)
+    }
+
+    /// Multiply two matrices with element type `T` using distributed workers
+    ///
+    /// Workers must be running [`Worker::process_work_as`](crate::worker::Worker::process_work_as)
+    /// with the same `T`.
+    pub fn multiply_matrices_as<T: Element>(
+        &self,
+        matrix_a_path: &Path,
+        matrix_b_path: &Path,
+        output_path: &Path,
+    ) -> Result<()> {
+        self.gemm_as::<T>(
+            &GemmSpec::default(),
+            matrix_a_path,
+            matrix_b_path,
+            None,
+            output_path,
+        )
+    }
+
+    /// Compute `alpha * op(A) * op(B) + beta * C` using distributed workers
+    ///
+    /// Without `matrix_c_path` C is taken to be zero.
+    pub fn gemm(
+        &self,
+        spec: &GemmSpec,
+        matrix_a_path: &Path,
+        matrix_b_path: &Path,
+        matrix_c_path: Option<&Path>,
+        output_path: &Path,
+    ) -> Result<()> {
+        self.gemm_as::<f64>(
+            spec,
+            matrix_a_path,
+            matrix_b_path,
+            matrix_c_path,
+            output_path,
+        )
+    }
+
+    /// [`gemm`](Coordinator::gemm) with element type `T`
+    ///
+    /// Operands are sent as stored together with the transpose flags, so no
+    /// transposed copy of A or B is ever built. Binary operands are mapped
+    /// rather than loaded (see [`open_operand`]), and a `.bin` result is
+    /// written out as its chunks arrive, so none of the matrices has to fit
+    /// in the coordinator's memory.
+    pub fn gemm_as<T: Element>(
+        &self,
+        spec: &GemmSpec<T>,
+        matrix_a_path: &Path,
+        matrix_b_path: &Path,
+        matrix_c_path: Option<&Path>,
+        output_path: &Path,
+    ) -> Result<()> {
         let total_size = self.world.size() as usize;
-        let actual_worker_count = if total_size > 1 { total_size - 1 } else { 0 };
+        let actual_worker_count = total_size.saturating_sub(1);
         
         if actual_worker_count == 0 {
-            return Err(format!(
-                "No workers available. Need at least 2 processes (1 coordinator + 1 worker). Current size: {}",
-                total_size
-            ));
+            return Err(Error::Mpi {
+                rank: self.world.rank(),
+                message: format!(
+                    "No workers available. Need at least 2 processes (1 coordinator + 1 worker). Current size: {}",
+                    total_size
+                ),
+            });
         }
 
         println!("[Coordinator] Loading matrices...");
-        let matrix_a = Matrix::load_from_file(matrix_a_path)
-            .map_err(|e| format!("Failed to load matrix A: {}", e))?;
-        let matrix_b = Matrix::load_from_file(matrix_b_path)
-            .map_err(|e| format!("Failed to load matrix B: {}", e))?;
-
-        // Validate dimensions
-        if matrix_a.cols != matrix_b.rows {
-            return Err(format!(
-                "Matrix dimensions incompatible: A is {}x{}, B is {}x{}",
-                matrix_a.rows, matrix_a.cols, matrix_b.rows, matrix_b.cols
-            ));
-        }
+        let operands = (|| {
+            let matrix_a = open_operand::<T>(matrix_a_path, &self.csv, self.verify_checksums)?;
+            let matrix_b = open_operand::<T>(matrix_b_path, &self.csv, self.verify_checksums)?;
+            let matrix_c = match matrix_c_path {
+                Some(path) => Some(open_operand::<T>(path, &self.csv, self.verify_checksums)?),
+                None => None,
+            };
+
+            // Validate dimensions of op(A) * op(B)
+            let (a_rows, a_cols) = matrix_a.dims();
+            let (b_rows, b_cols) = matrix_b.dims();
+            let op_a = if spec.trans_a.is_transposed() {
+                (a_cols, a_rows)
+            } else {
+                (a_rows, a_cols)
+            };
+            let op_b = if spec.trans_b.is_transposed() {
+                (b_cols, b_rows)
+            } else {
+                (b_rows, b_cols)
+            };
+            if op_a.1 != op_b.0 {
+                return Err(Error::DimensionMismatch {
+                    operation: "multiply",
+                    left: op_a,
+                    right: op_b,
+                });
+            }
+            let (rows, cols) = (op_a.0, op_b.1);
+            if let Some(c) = &matrix_c {
+                if c.dims() != (rows, cols) {
+                    return Err(Error::DimensionMismatch {
+                        operation: "accumulate",
+                        left: (rows, cols),
+                        right: c.dims(),
+                    });
+                }
+            }
+            Ok((matrix_a, matrix_b, matrix_c, (rows, cols)))
+        })();
+        // Release the workers before bailing out
+        let (matrix_a, matrix_b, matrix_c, (rows, cols)) = self.or_dismiss(operands)?;
 
+        let ((a_rows, a_cols), (b_rows, b_cols)) = (matrix_a.dims(), matrix_b.dims());
         println!(
             "[Coordinator] Matrix A: {}x{}, Matrix B: {}x{}",
-            matrix_a.rows, matrix_a.cols, matrix_b.rows, matrix_b.cols
+            a_rows, a_cols, b_rows, b_cols
         );
 
-        // Distribute work: split A by rows (1D row decomposition)
-        // Each worker gets: rows [r1, r2) of A and the ENTIRE matrix B
-        // Worker computes: result[r1:r2, :] = A[r1:r2, :] * B
-        let rows_per_worker = (matrix_a.rows + actual_worker_count - 1) / actual_worker_count;
+        // Writing over an operand while it is mapped would pull the data out
+        // from under the sends, so that case collects the result first
+        let overwrites_operand = [Some(matrix_a_path), Some(matrix_b_path), matrix_c_path]
+            .into_iter()
+            .flatten()
+            .any(|path| is_same_file(path, output_path));
+        let matrix_a = Some(matrix_a.as_ref());
+        let matrix_c = matrix_c.as_deref();
+        if has_extension(output_path, binary::EXTENSION) && !overwrites_operand {
+            // Write each chunk out as it arrives instead of assembling the result
+            println!("[Coordinator] Streaming result to {:?}...", output_path);
+            let writer = File::create(output_path)
+                .map_err(|e| Error::io(Some(output_path), e))
+                .and_then(|file| {
+                    BinaryWriter::new(BufWriter::new(file), rows, cols, true)
+                        .map_err(|e| in_file(e, output_path))
+                });
+            let writer = self.or_dismiss(writer)?;
+            let mut sink = FileSink {
+                writer,
+                path: output_path,
+            };
+            self.distribute(
+                spec,
+                matrix_a,
+                matrix_b.as_ref(),
+                matrix_c,
+                false,
+                &mut sink,
+            )?;
+            sink.writer
+                .finish()
+                .and_then(|mut writer| writer.flush().map_err(|e| Error::io(None, e)))
+                .map_err(|e| in_file(e, output_path))?;
+        } else {
+            let mut result = Matrix::new(rows, cols);
+            self.distribute(
+                spec,
+                matrix_a,
+                matrix_b.as_ref(),
+                matrix_c,
+                false,
+                &mut result,
+            )?;
+
+            println!("[Coordinator] Saving result to {:?}...", output_path);
+            result.save_with(output_path, &self.csv)?;
+        }
+        println!("[Coordinator] Multiplication complete!");
+
+        Ok(())
+    }
+
+    /// Compute `A^k` by repeated squaring using distributed workers
+    pub fn power(&self, matrix_path: &Path, k: u32, output_path: &Path) -> Result<()> {
+        self.power_as::<f64>(&GemmSpec::default(), matrix_path, k, output_path)
+    }
+
+    /// [`power`](Coordinator::power) with element type `T`
+    ///
+    /// Each squaring or multiplication by A is one distributed round. Every
+    /// round after the first multiplies the previous result, so workers keep
+    /// the rows they computed and only the right operand travels between rounds.
+    ///
+    /// Of `spec` only the accumulation mode and the reproducible flag are
+    /// used; every round is a plain product.
+    pub fn power_as<T: Element>(
+        &self,
+        spec: &GemmSpec<T>,
+        matrix_path: &Path,
+        k: u32,
+        output_path: &Path,
+    ) -> Result<()> {
+        let total_size = self.world.size() as usize;
+        if total_size < 2 {
+            return Err(Error::Mpi {
+                rank: self.world.rank(),
+                message: format!(
+                    "No workers available. Need at least 2 processes (1 coordinator + 1 worker). Current size: {}",
+                    total_size
+                ),
+            });
+        }
+
+        println!("[Coordinator] Loading matrix...");
+        let matrix = Matrix::<T>::load_with(matrix_path, &self.csv).and_then(|matrix| {
+            if matrix.rows != matrix.cols {
+                return Err(Error::NotSquare {
+                    operation: "matrix power",
+                    rows: matrix.rows,
+                    cols: matrix.cols,
+                });
+            }
+            Ok(matrix)
+        });
+        // Release the workers before bailing out
+        let matrix = self.or_dismiss(matrix)?;
+        let n = matrix.rows;
+        println!("[Coordinator] Computing A^{} for A: {}x{}", k, n, n);
+
+        let result = if k < 2 || n == 0 {
+            self.dismiss_workers()?;
+            if k == 0 {
+                Matrix::identity(n)
+            } else {
+                matrix
+            }
+        } else {
+            let spec = GemmSpec::default()
+                .with_accumulation(spec.accumulation)
+                .with_reproducible(spec.reproducible);
+            let rounds = power_rounds(k);
+            let mut result: Option<Matrix<T>> = None;
+            for (i, round) in rounds.iter().enumerate() {
+                let more = i + 1 < rounds.len();
+                let current = result.as_ref().unwrap_or(&matrix);
+                // Only the first round sends chunks of A; later ones reuse
+                // the rows each worker computed in the round before
+                let left = result.is_none().then_some(&matrix as &dyn ChunkSource<T>);
+                let right = match round {
+                    PowerRound::Square => {
+                        println!("[Coordinator] Squaring...");
+                        current
+                    }
+                    PowerRound::MultiplyByA => {
+                        println!("[Coordinator] Multiplying by A...");
+                        &matrix
+                    }
+                };
+                let mut next = Matrix::new(n, n);
+                self.distribute(&spec, left, right, None, more, &mut next)?;
+                result = Some(next);
+            }
+            result.unwrap_or(matrix)
+        };
+
+        println!("[Coordinator] Saving result to {:?}...", output_path);
+        result.save_with(output_path, &self.csv)?;
+        println!("[Coordinator] Matrix power complete!");
+
+        Ok(())
+    }
+
+    /// Pass `result` through, first dismissing the workers if it is an error
+    /// so that none is left waiting for an assignment that never comes
+    fn or_dismiss<V>(&self, result: Result<V>) -> Result<V> {
+        if result.is_err() {
+            // The original error is the one worth reporting
+            let _ = self.dismiss_workers();
+        }
+        result
+    }
+
+    /// Send every worker an empty assignment so that it stops waiting
+    fn dismiss_workers(&self) -> Result<()> {
+        for worker_rank in 1..self.world.size() {
+            send_work_assignment(&self.world, worker_rank, &WorkAssignment::default())?;
+        }
+        Ok(())
+    }
+
+    /// Run one distributed `alpha * op(A) * op(B) + beta * C` into `result`,
+    /// splitting the rows of op(A) across the workers
+    ///
+    /// Without `matrix_a` each worker multiplies the block it computed in the
+    /// previous round. With `more` the workers wait for another round after
+    /// this one.
+    fn distribute<T: Element>(
+        &self,
+        spec: &GemmSpec<T>,
+        matrix_a: Option<&dyn ChunkSource<T>>,
+        matrix_b: &dyn ChunkSource<T>,
+        matrix_c: Option<&dyn ChunkSource<T>>,
+        more: bool,
+        result: &mut dyn ResultSink<T>,
+    ) -> Result<()> {
+        let (rows, cols) = result.dims();
+        let total_size = self.world.size() as usize;
+        let actual_worker_count = total_size - 1;
+
+        // Distribute work: split op(A) by rows (1D row decomposition)
+        // Each worker gets: rows [r1, r2) of op(A) and the ENTIRE matrix B
+        // Worker computes: result[r1:r2, :] = alpha * op(A)[r1:r2, :] * op(B) + beta * C[r1:r2, :]
+        let rows_per_worker = rows.div_ceil(actual_worker_count);
 
         println!(
             "[Coordinator] Distributing work: {} rows per worker (row-based decomposition)",
@@ -76,11 +373,15 @@ impl<C: Communicator> Coordinator<C> {
 
             // Calculate row range for this worker
             let row_start = (worker_rank - 1) * rows_per_worker;
-            let row_end = (row_start + rows_per_worker).min(matrix_a.rows);
+            let row_end = (row_start + rows_per_worker).min(rows);
 
-            if row_start >= matrix_a.rows {
+            if row_start >= rows {
                 // No work for this worker - send empty assignment
-                send_work_assignment(&self.world, worker_rank_i32, 0, 0, 0, 0)?;
+                let assignment = WorkAssignment {
+                    more,
+                    ..WorkAssignment::default()
+                };
+                send_work_assignment(&self.world, worker_rank_i32, &assignment)?;
                 continue;
             }
 
@@ -89,19 +390,37 @@ impl<C: Communicator> Coordinator<C> {
                 worker_rank, row_start, row_end
             );
 
-            // Send work assignment (col range is full width: 0 to matrix_b.cols)
-            send_work_assignment(&self.world, worker_rank_i32, row_start, row_end, 0, matrix_b.cols)?;
+            // Send work assignment (col range is full width: 0 to cols)
+            let assignment = WorkAssignment {
+                trans_a: spec.trans_a,
+                trans_b: spec.trans_b,
+                accumulate: matrix_c.is_some(),
+                reuse_a: matrix_a.is_none(),
+                more,
+                accumulation: spec.accumulation,
+                reproducible: spec.reproducible,
+                ..WorkAssignment::new(row_start, row_end, 0, cols)
+            };
+            send_work_assignment(&self.world, worker_rank_i32, &assignment)?;
+            send_scalars(&self.world, worker_rank_i32, &[spec.alpha, spec.beta])?;
 
-            // Send row chunk from A
-            let row_chunk = matrix_a.get_row_chunk(row_start, row_end - row_start)?;
-            send_matrix(&self.world, worker_rank_i32, &row_chunk)?;
+            // Send the chunk of A as stored: rows of A, or columns of A when
+            // it is transposed. Row chunks go out without being copied
+            let num_rows = row_end - row_start;
+            let send =
+                &mut |chunk: MatrixView<'_, T>| send_view(&self.world, worker_rank_i32, chunk);
+            if let Some(matrix_a) = matrix_a {
+                matrix_a.with_chunk(spec.trans_a.is_transposed(), row_start, num_rows, send)?;
+            }
 
             // Send entire matrix B to each worker
-            send_matrix(&self.world, worker_rank_i32, &matrix_b)?;
-        }
+            matrix_b.with_chunk(false, 0, matrix_b.dims().0, send)?;
 
-        // Initialize result matrix
-        let mut result = Matrix::new(matrix_a.rows, matrix_b.cols);
+            // Send the matching rows of C to accumulate into
+            if let Some(c) = matrix_c {
+                c.with_chunk(false, row_start, num_rows, send)?;
+            }
+        }
 
         // Collect results from workers
         println!("[Coordinator] Collecting results from workers...");
@@ -111,34 +430,200 @@ impl<C: Communicator> Coordinator<C> {
             // Calculate expected row range
             let row_start = (worker_rank - 1) * rows_per_worker;
 
-            if row_start >= matrix_a.rows {
+            if row_start >= rows {
                 continue;
             }
 
             // Receive result chunk
-            let result_chunk = receive_result(&self.world, worker_rank_i32)?;
+            let result_chunk = receive_result::<T>(&self.world, worker_rank_i32)?;
+            let expected_rows = rows_per_worker.min(rows - row_start);
+            if result_chunk.rows != expected_rows || result_chunk.cols != cols {
+                return Err(Error::Mpi {
+                    rank: worker_rank_i32,
+                    message: format!(
+                        "Result chunk is {}x{}, expected {}x{}",
+                        result_chunk.rows, result_chunk.cols, expected_rows, cols
+                    ),
+                });
+            }
 
             println!(
                 "[Coordinator] Received result from worker {}: {}x{}",
                 worker_rank, result_chunk.rows, result_chunk.cols
             );
 
-            // Copy result chunk into final result matrix (full row width)
-            for i in 0..result_chunk.rows {
-                for j in 0..result_chunk.cols {
-                    let global_row = row_start + i;
-                    if global_row < result.rows && j < result.cols {
-                        result.set(global_row, j, result_chunk.get(i, j)?)?;
-                    }
-                }
-            }
+            result.put(row_start, &result_chunk)?;
         }
 
-        // Save result
-        println!("[Coordinator] Saving result to {:?}...", output_path);
-        result.save_to_file(output_path)?;
-        println!("[Coordinator] Multiplication complete!");
-
         Ok(())
     }
 }
+
+/// One distributed round of [`Coordinator::power`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerRound {
+    /// Square the current result
+    Square,
+    /// Multiply the current result by A
+    MultiplyByA,
+}
+
+/// The rounds computing `A^k` from A by left-to-right binary exponentiation;
+/// none for `k < 2`
+pub fn power_rounds(k: u32) -> Vec<PowerRound> {
+    let mut rounds = Vec::new();
+    for bit in (0..u32::BITS.saturating_sub(k.leading_zeros() + 1)).rev() {
+        rounds.push(PowerRound::Square);
+        if (k >> bit) & 1 == 1 {
+            rounds.push(PowerRound::MultiplyByA);
+        }
+    }
+    rounds
+}
+
+/// Operand the coordinator cuts into chunks for the workers
+pub trait ChunkSource<T: Element> {
+    /// Rows and columns as stored
+    fn dims(&self) -> (usize, usize);
+
+    /// Call `f` with `count` rows, or columns if `by_columns`, starting at
+    /// `start`: a borrowed view where the storage allows, a copy otherwise
+    fn with_chunk(
+        &self,
+        by_columns: bool,
+        start: usize,
+        count: usize,
+        f: &mut dyn FnMut(MatrixView<'_, T>) -> Result<()>,
+    ) -> Result<()>;
+}
+
+impl<T: Element> ChunkSource<T> for Matrix<T> {
+    fn dims(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    fn with_chunk(
+        &self,
+        by_columns: bool,
+        start: usize,
+        count: usize,
+        f: &mut dyn FnMut(MatrixView<'_, T>) -> Result<()>,
+    ) -> Result<()> {
+        self.view().with_chunk(by_columns, start, count, f)
+    }
+}
+
+impl<T: Element> ChunkSource<T> for MatrixView<'_, T> {
+    fn dims(&self) -> (usize, usize) {
+        (self.rows(), self.cols())
+    }
+
+    fn with_chunk(
+        &self,
+        by_columns: bool,
+        start: usize,
+        count: usize,
+        f: &mut dyn FnMut(MatrixView<'_, T>) -> Result<()>,
+    ) -> Result<()> {
+        if by_columns {
+            f(self.col_chunk(start, count)?)
+        } else {
+            f(self.row_chunk(start, count)?)
+        }
+    }
+}
+
+impl<T: Element> ChunkSource<T> for MappedMatrix<T> {
+    fn dims(&self) -> (usize, usize) {
+        (self.rows(), self.cols())
+    }
+
+    fn with_chunk(
+        &self,
+        by_columns: bool,
+        start: usize,
+        count: usize,
+        f: &mut dyn FnMut(MatrixView<'_, T>) -> Result<()>,
+    ) -> Result<()> {
+        if let Some(view) = self.view() {
+            return view.with_chunk(by_columns, start, count, f);
+        }
+        let chunk = if by_columns {
+            self.col_chunk(start, count)?
+        } else {
+            self.row_chunk(start, count)?
+        };
+        f(chunk.view())
+    }
+}
+
+/// Open an operand of a distributed product
+///
+/// A file in the binary format is mapped rather than loaded, so only the
+/// chunks being sent are read, straight from the map if the file is in
+/// native byte order. Its checksum is only checked up front with
+/// `verify_checksum`, since that reads the whole file. Any other file is
+/// loaded, with `csv` applying to CSV and TSV files.
+pub fn open_operand<T: Element>(
+    path: &Path,
+    csv: &CsvOverrides,
+    verify_checksum: bool,
+) -> Result<Box<dyn ChunkSource<T>>> {
+    if binary::is_binary_file(path)? {
+        let mapped = MappedMatrix::<T>::open(path)?;
+        if verify_checksum {
+            mapped.verify_checksum()?;
+        }
+        Ok(Box::new(mapped))
+    } else {
+        Ok(Box::new(Matrix::<T>::load_with(path, csv)?))
+    }
+}
+
+/// Whether both paths name the same existing file
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Where [`Coordinator::distribute`] puts the result chunks, in row order
+trait ResultSink<T: Element> {
+    /// Rows and columns of the whole result
+    fn dims(&self) -> (usize, usize);
+
+    /// Take the rows of the result starting at `row_start`
+    fn put(&mut self, row_start: usize, chunk: &Matrix<T>) -> Result<()>;
+}
+
+impl<T: Element> ResultSink<T> for Matrix<T> {
+    fn dims(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    fn put(&mut self, row_start: usize, chunk: &Matrix<T>) -> Result<()> {
+        self.block_mut(row_start, 0, chunk.rows, chunk.cols)?
+            .copy_from(&chunk.view())
+    }
+}
+
+/// Result streamed into a binary file
+struct FileSink<'a, T: Element> {
+    writer: BinaryWriter<BufWriter<File>, T>,
+    path: &'a Path,
+}
+
+impl<T: Element> ResultSink<T> for FileSink<'_, T> {
+    fn dims(&self) -> (usize, usize) {
+        self.writer.dims()
+    }
+
+    fn put(&mut self, row_start: usize, chunk: &Matrix<T>) -> Result<()> {
+        // Workers are collected in rank order, which is row order
+        debug_assert_eq!(row_start, self.writer.rows_written());
+        self.writer
+            .write_rows(chunk.view())
+            .map_err(|e| in_file(e, self.path))
+    }
+}
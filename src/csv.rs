@@ -0,0 +1,515 @@
+//! Delimited text files (CSV, TSV)
+//!
+//! Each record is a line of fields separated by a delimiter. A field may be
+//! quoted, in which case it can hold delimiters, line breaks and the quote
+//! character itself (written twice), as in RFC 4180; unquoted fields have
+//! surrounding whitespace trimmed. Blank lines and lines starting with the
+//! comment character are skipped. Optionally the first record names the
+//! columns and the first field of every record labels its row, as in
+//! spreadsheet exports.
+
+use crate::binary::in_file;
+use crate::element::Element;
+use crate::error::{Error, Result};
+use crate::matrix::Matrix;
+use crate::view::MatrixView;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::Path;
+
+/// Layout of a delimited text file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Separator between the fields of a record
+    pub delimiter: char,
+    /// Character that encloses quoted fields
+    pub quote: char,
+    /// Lines starting with this character are skipped
+    pub comment: Option<char>,
+    /// The first record holds column names
+    pub header: bool,
+    /// The first field of every record is a row label
+    pub row_labels: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions::csv()
+    }
+}
+
+impl CsvOptions {
+    /// Comma-separated values with `"` quotes and `#` comments
+    pub fn csv() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            quote: '"',
+            comment: Some('#'),
+            header: false,
+            row_labels: false,
+        }
+    }
+
+    /// Tab-separated values, otherwise as [`CsvOptions::csv`]
+    pub fn tsv() -> Self {
+        CsvOptions::csv().with_delimiter('\t')
+    }
+
+    /// TSV for paths ending in `.tsv` or `.tab`, CSV otherwise
+    pub fn for_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some(TSV_EXTENSION | TAB_EXTENSION) => CsvOptions::tsv(),
+            _ => CsvOptions::csv(),
+        }
+    }
+
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_quote(mut self, quote: char) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    pub fn with_comment(mut self, comment: Option<char>) -> Self {
+        self.comment = comment;
+        self
+    }
+
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    pub fn with_row_labels(mut self, row_labels: bool) -> Self {
+        self.row_labels = row_labels;
+        self
+    }
+
+    /// Check that the special characters can be told apart
+    pub fn validate(&self) -> Result<()> {
+        let line_break = |c: char| c == '\n' || c == '\r';
+        if line_break(self.delimiter) || line_break(self.quote) || self.delimiter == self.quote {
+            return Err(Error::invalid_argument(
+                "CSV delimiter and quote",
+                format!("{:?} and {:?}", self.delimiter, self.quote),
+            ));
+        }
+        if let Some(comment) = self.comment {
+            if line_break(comment) || comment == self.delimiter || comment == self.quote {
+                return Err(Error::invalid_argument(
+                    "CSV comment character",
+                    format!("{:?}", comment),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whitespace that is trimmed around fields, i.e. all but the delimiter
+    fn is_padding(&self, c: char) -> bool {
+        c.is_whitespace() && c != self.delimiter
+    }
+}
+
+/// Settings for every delimited file of a run, whatever its extension;
+/// whatever is not set follows [`CsvOptions::for_path`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CsvOverrides {
+    /// Separator to use instead of the one the extension implies
+    pub delimiter: Option<char>,
+    /// The first record holds column names
+    pub header: bool,
+    /// The first field of every record is a row label
+    pub row_labels: bool,
+}
+
+impl CsvOverrides {
+    /// Options for the delimited file at `path`
+    pub fn options_for<P: AsRef<Path>>(&self, path: P) -> CsvOptions {
+        let options = CsvOptions::for_path(path)
+            .with_header(self.header)
+            .with_row_labels(self.row_labels);
+        match self.delimiter {
+            Some(delimiter) => options.with_delimiter(delimiter),
+            None => options,
+        }
+    }
+}
+
+/// File extension that [`Matrix::save`] writes as CSV
+pub const CSV_EXTENSION: &str = "csv";
+
+/// File extension that [`Matrix::save`] writes as TSV
+pub const TSV_EXTENSION: &str = "tsv";
+
+/// Other file extension that [`Matrix::save`] writes as TSV
+pub const TAB_EXTENSION: &str = "tab";
+
+/// Whether the extension of `path` names a delimited text format
+pub fn is_delimited_extension<P: AsRef<Path>>(path: P) -> bool {
+    matches!(
+        path.as_ref().extension().and_then(|e| e.to_str()),
+        Some(CSV_EXTENSION | TSV_EXTENSION | TAB_EXTENSION)
+    )
+}
+
+/// Matrix with the column names and row labels of a delimited file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table<T = f64> {
+    pub matrix: Matrix<T>,
+    /// Names of the matrix columns, without the row-label column
+    pub column_names: Option<Vec<String>>,
+    pub row_labels: Option<Vec<String>>,
+}
+
+impl<T: Element> Table<T> {
+    /// Table without names or labels
+    pub fn new(matrix: Matrix<T>) -> Self {
+        Table {
+            matrix,
+            column_names: None,
+            row_labels: None,
+        }
+    }
+
+    /// Load a table from a delimited text file
+    pub fn load<P: AsRef<Path>>(path: P, options: &CsvOptions) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| Error::io(Some(path), e))?;
+        Table::read(BufReader::new(file), options).map_err(|e| in_file(e, path))
+    }
+
+    /// Read a table in the delimited text format
+    pub fn read<R: BufRead>(reader: R, options: &CsvOptions) -> Result<Self> {
+        options.validate()?;
+        let mut records = Records {
+            lines: reader.lines(),
+            line: 0,
+            options,
+        };
+
+        let mut column_names = None;
+        if options.header {
+            if let Some(record) = records.next_record()? {
+                let skip = options.row_labels as usize;
+                column_names = Some(record.into_iter().skip(skip).map(|f| f.text).collect());
+            }
+        }
+        let mut cols = column_names.as_ref().map(Vec::len);
+        let mut row_labels = options.row_labels.then(Vec::new);
+        let mut data = Vec::new();
+        let mut rows = 0;
+
+        while let Some(mut record) = records.next_record()? {
+            let line = record[0].line;
+            if let Some(labels) = &mut row_labels {
+                labels.push(record.remove(0).text);
+            }
+            let expected = *cols.get_or_insert(record.len());
+            if record.len() != expected {
+                // Point at the first surplus field, or at the line if some are missing
+                let column = record.get(expected).map(|field| field.column);
+                return Err(Error::Format {
+                    path: None,
+                    line: Some(line),
+                    column,
+                    message: format!(
+                        "Inconsistent column count: expected {}, found {}",
+                        expected,
+                        record.len()
+                    ),
+                });
+            }
+            for field in record {
+                let value = T::parse_text(field.text.trim())
+                    .map_err(|e| e.at_position(field.line, Some(field.column)))?;
+                data.push(value);
+            }
+            rows += 1;
+        }
+
+        let Some(cols) = cols else {
+            return Err(Error::Format {
+                path: None,
+                line: None,
+                column: None,
+                message: "Matrix file is empty".to_string(),
+            });
+        };
+        Ok(Table {
+            matrix: Matrix::from_vec(data, rows, cols)?,
+            column_names,
+            row_labels,
+        })
+    }
+
+    /// Save the table to a delimited text file
+    ///
+    /// The header and the row labels are written if `options` asks for them;
+    /// missing names and labels default to the 0-based column and row indices.
+    pub fn save<P: AsRef<Path>>(&self, path: P, options: &CsvOptions) -> Result<()> {
+        self.check(options)?;
+        let path = path.as_ref();
+        let write = || -> io::Result<()> {
+            let mut writer = BufWriter::new(File::create(path)?);
+            self.write_records(&mut writer, options)?;
+            writer.flush()
+        };
+        write().map_err(|e| Error::io(Some(path), e))
+    }
+
+    /// Write the table in the delimited text format
+    pub fn write<W: Write>(&self, writer: &mut W, options: &CsvOptions) -> Result<()> {
+        self.check(options)?;
+        self.write_records(writer, options)
+            .map_err(|e| Error::io(None, e))
+    }
+
+    /// Check the options and that there is one name per column and one
+    /// label per row
+    fn check(&self, options: &CsvOptions) -> Result<()> {
+        options.validate()?;
+        let (rows, cols) = (self.matrix.rows, self.matrix.cols);
+        if let Some(names) = &self.column_names {
+            if names.len() != cols {
+                return Err(Error::invalid_argument(
+                    "column names",
+                    format!("{} names for {} columns", names.len(), cols),
+                ));
+            }
+        }
+        if let Some(labels) = &self.row_labels {
+            if labels.len() != rows {
+                return Err(Error::invalid_argument(
+                    "row labels",
+                    format!("{} labels for {} rows", labels.len(), rows),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn write_records<W: Write>(&self, writer: &mut W, options: &CsvOptions) -> io::Result<()> {
+        write_table(
+            writer,
+            &self.matrix.view(),
+            self.column_names.as_deref(),
+            self.row_labels.as_deref(),
+            options,
+        )
+    }
+}
+
+impl<T: Element> Matrix<T> {
+    /// Load a matrix from a delimited text file, dropping any names and labels
+    pub fn load_csv<P: AsRef<Path>>(path: P, options: &CsvOptions) -> Result<Self> {
+        Table::load(path, options).map(|table| table.matrix)
+    }
+
+    /// Read a matrix in the delimited text format
+    pub fn read_csv<R: BufRead>(reader: R, options: &CsvOptions) -> Result<Self> {
+        Table::read(reader, options).map(|table| table.matrix)
+    }
+
+    /// Save a matrix to a delimited text file; see [`Table::save`]
+    pub fn save_csv<P: AsRef<Path>>(&self, path: P, options: &CsvOptions) -> Result<()> {
+        self.view().save_csv(path, options)
+    }
+}
+
+impl<T: Element> MatrixView<'_, T> {
+    /// Save the viewed elements to a delimited text file, with index names
+    /// and labels if `options` asks for a header or row labels
+    pub fn save_csv<P: AsRef<Path>>(&self, path: P, options: &CsvOptions) -> Result<()> {
+        options.validate()?;
+        let path = path.as_ref();
+        let write = || -> io::Result<()> {
+            let mut writer = BufWriter::new(File::create(path)?);
+            write_table(&mut writer, self, None, None, options)?;
+            writer.flush()
+        };
+        write().map_err(|e| Error::io(Some(path), e))
+    }
+
+    /// Write the viewed elements in the delimited text format
+    pub fn write_csv<W: Write>(&self, writer: &mut W, options: &CsvOptions) -> Result<()> {
+        options.validate()?;
+        write_table(writer, self, None, None, options).map_err(|e| Error::io(None, e))
+    }
+}
+
+/// Write the header, if asked for, and one record per row
+fn write_table<T: Element, W: Write>(
+    writer: &mut W,
+    view: &MatrixView<'_, T>,
+    column_names: Option<&[String]>,
+    row_labels: Option<&[String]>,
+    options: &CsvOptions,
+) -> io::Result<()> {
+    let delimiter = options.delimiter;
+    if options.header {
+        if options.row_labels {
+            // The row-label column has no name
+            write!(writer, "{}", delimiter)?;
+        }
+        for j in 0..view.cols() {
+            if j > 0 {
+                write!(writer, "{}", delimiter)?;
+            }
+            match column_names {
+                Some(names) => write_field(writer, &names[j], options)?,
+                None => write!(writer, "{}", j)?,
+            }
+        }
+        writeln!(writer)?;
+    }
+    for i in 0..view.rows() {
+        if options.row_labels {
+            match row_labels {
+                Some(labels) => write_field(writer, &labels[i], options)?,
+                None => write!(writer, "{}", i)?,
+            }
+            if view.cols() > 0 {
+                write!(writer, "{}", delimiter)?;
+            }
+        }
+        for j in 0..view.cols() {
+            if j > 0 {
+                write!(writer, "{}", delimiter)?;
+            }
+            write_field(writer, &view.at(i, j).to_string(), options)?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Write one field, quoted if it would not read back as it is
+fn write_field<W: Write>(writer: &mut W, text: &str, options: &CsvOptions) -> io::Result<()> {
+    let needs_quotes = text.is_empty()
+        || text.starts_with(|c| options.is_padding(c) || Some(c) == options.comment)
+        || text.ends_with(|c| options.is_padding(c))
+        || text.contains([options.delimiter, options.quote, '\n', '\r']);
+    if !needs_quotes {
+        return write!(writer, "{}", text);
+    }
+    let quote = options.quote.to_string();
+    let escaped = text.replace(&quote, &quote.repeat(2));
+    write!(writer, "{}{}{}", quote, escaped, quote)
+}
+
+/// Text of one field and where it starts, counting lines and columns from 1
+struct Field {
+    text: String,
+    line: usize,
+    column: usize,
+}
+
+/// Splits lines into records, joining the lines of quoted line breaks
+struct Records<'a, R> {
+    lines: Lines<R>,
+    /// Number of the last line read
+    line: usize,
+    options: &'a CsvOptions,
+}
+
+impl<R: BufRead> Records<'_, R> {
+    fn next_line(&mut self) -> Result<Option<Vec<char>>> {
+        let Some(line) = self.lines.next() else {
+            return Ok(None);
+        };
+        self.line += 1;
+        let line = line.map_err(|e| Error::io(None, e))?;
+        Ok(Some(line.chars().collect()))
+    }
+
+    /// Next record that is not blank or a comment
+    fn next_record(&mut self) -> Result<Option<Vec<Field>>> {
+        loop {
+            let Some(chars) = self.next_line()? else {
+                return Ok(None);
+            };
+            let first = chars.iter().position(|&c| !c.is_whitespace());
+            match first {
+                None => continue,
+                Some(i) if Some(chars[i]) == self.options.comment => continue,
+                Some(_) => return self.parse_record(chars).map(Some),
+            }
+        }
+    }
+
+    fn parse_record(&mut self, mut chars: Vec<char>) -> Result<Vec<Field>> {
+        let options = self.options;
+        let error = |line: usize, column: usize, message: &str| Error::Format {
+            path: None,
+            line: Some(line),
+            column: Some(column),
+            message: message.to_string(),
+        };
+        let mut fields = Vec::new();
+        let mut i = 0;
+        loop {
+            while i < chars.len() && options.is_padding(chars[i]) {
+                i += 1;
+            }
+            let (line, column) = (self.line, i + 1);
+            let mut text = String::new();
+
+            if chars.get(i) == Some(&options.quote) {
+                i += 1;
+                loop {
+                    if i == chars.len() {
+                        // The quoted field continues on the next line
+                        let Some(next) = self.next_line()? else {
+                            return Err(error(line, column, "Unterminated quoted field"));
+                        };
+                        text.push('\n');
+                        chars = next;
+                        i = 0;
+                        continue;
+                    }
+                    if chars[i] == options.quote {
+                        if chars.get(i + 1) == Some(&options.quote) {
+                            text.push(options.quote);
+                            i += 2;
+                            continue;
+                        }
+                        i += 1;
+                        break;
+                    }
+                    text.push(chars[i]);
+                    i += 1;
+                }
+                while i < chars.len() && options.is_padding(chars[i]) {
+                    i += 1;
+                }
+                if i < chars.len() && chars[i] != options.delimiter {
+                    return Err(error(
+                        self.line,
+                        i + 1,
+                        "Expected a delimiter after the closing quote",
+                    ));
+                }
+            } else {
+                while i < chars.len() && chars[i] != options.delimiter {
+                    if chars[i] == options.quote {
+                        return Err(error(self.line, i + 1, "Quote inside an unquoted field"));
+                    }
+                    text.push(chars[i]);
+                    i += 1;
+                }
+                text.truncate(text.trim_end_matches(|c| options.is_padding(c)).len());
+            }
+
+            fields.push(Field { text, line, column });
+            if i == chars.len() {
+                return Ok(fields);
+            }
+            // Skip the delimiter; a trailing one ends in an empty field
+            i += 1;
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Eigenvalue solvers
+//!
+//! Symmetric matrices are diagonalized with the cyclic Jacobi method: every
+//! sweep visits each off-diagonal pair once and zeroes it with a plane
+//! rotation, until the off-diagonal mass is negligible. Jacobi is slower than
+//! tridiagonal QR on large inputs but computes small eigenvalues to high
+//! relative accuracy and returns orthogonal eigenvectors directly.
+//!
+//! [`Matrix::power_iteration`] finds only the dominant eigenpair, for any
+//! square matrix, with one matrix-vector product per step.
+
+use crate::element::{Element, Field, Real};
+use crate::error::{Error, Result};
+use crate::matrix::Matrix;
+
+/// Sweeps allowed before Jacobi gives up; it converges quadratically and
+/// typically needs fewer than ten
+const MAX_SWEEPS: usize = 50;
+
+/// Eigendecomposition `A = V diag(values) V^T` of a symmetric matrix
+#[derive(Debug, Clone)]
+pub struct SymmetricEigen<T = f64> {
+    /// Eigenvalues in ascending order
+    pub values: Vec<T>,
+    /// Orthonormal eigenvectors, column `i` belonging to `values[i]`
+    pub vectors: Matrix<T>,
+}
+
+impl<T: Real> Matrix<T> {
+    /// Eigenvalues and eigenvectors of this symmetric matrix by cyclic Jacobi
+    ///
+    /// Only the lower triangle is read; the upper one is assumed to mirror it.
+    pub fn symmetric_eigen(&self) -> Result<SymmetricEigen<T>> {
+        if self.rows != self.cols {
+            return Err(Error::NotSquare {
+                operation: "symmetric eigendecomposition",
+                rows: self.rows,
+                cols: self.cols,
+            });
+        }
+        let n = self.rows;
+        let mut a = self.clone();
+        for i in 0..n {
+            for j in i + 1..n {
+                a.data[i * n + j] = a.data[j * n + i];
+            }
+        }
+        let mut v = Matrix::identity(n);
+        let tolerance = T::EPSILON * a.norm_frobenius();
+
+        let mut sweeps = 0;
+        while off_diagonal_norm(&a) > tolerance {
+            if sweeps == MAX_SWEEPS {
+                return Err(Error::NoConvergence {
+                    operation: "Jacobi eigenvalue iteration",
+                    iterations: sweeps,
+                });
+            }
+            for p in 0..n {
+                for q in p + 1..n {
+                    rotate(&mut a, &mut v, p, q);
+                }
+            }
+            sweeps += 1;
+        }
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&i, &j| {
+            a.data[i * n + i]
+                .partial_cmp(&a.data[j * n + j])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let values = order.iter().map(|&i| a.data[i * n + i]).collect();
+        let mut vectors = Matrix::new(n, n);
+        for (dest, &src) in order.iter().enumerate() {
+            for row in 0..n {
+                vectors.data[row * n + dest] = v.data[row * n + src];
+            }
+        }
+        Ok(SymmetricEigen { values, vectors })
+    }
+}
+
+impl<T: Field> Matrix<T> {
+    /// Dominant eigenvalue and a unit eigenvector for it, by power iteration
+    ///
+    /// Stops once the residual `||A x - lambda x||` drops to `tolerance`
+    /// times `|lambda|`. Convergence is slow when the two largest eigenvalues
+    /// are close in magnitude, and fails if they are equal but distinct.
+    pub fn power_iteration(
+        &self,
+        max_iterations: usize,
+        tolerance: T::Real,
+    ) -> Result<(T, Matrix<T>)> {
+        if self.rows != self.cols {
+            return Err(Error::NotSquare {
+                operation: "power iteration",
+                rows: self.rows,
+                cols: self.cols,
+            });
+        }
+        let n = self.rows;
+        if n == 0 {
+            return Ok((T::zero(), Matrix::new(0, 1)));
+        }
+
+        // A fixed start vector with no symmetry, so it is unlikely to be
+        // orthogonal to the dominant eigenvector
+        let start = (0..n)
+            .map(|i| T::from_real(T::Real::from_f64(1.0 + i as f64 / n as f64)))
+            .collect();
+        let mut x = normalized(Matrix::from_vec(start, n, 1)?);
+
+        for _ in 0..max_iterations {
+            let y = self.multiply(&x)?;
+            let value = x
+                .data
+                .iter()
+                .zip(&y.data)
+                .fold(T::zero(), |acc, (&xi, &yi)| acc + xi.conj() * yi);
+            let residual = y
+                .data
+                .iter()
+                .zip(&x.data)
+                .map(|(&yi, &xi)| yi - value * xi)
+                .collect();
+            let residual = Matrix::from_vec(residual, n, 1)?.norm_frobenius();
+            if residual <= tolerance * value.abs() || y.norm_max() == T::Real::zero() {
+                return Ok((value, x));
+            }
+            x = normalized(y);
+        }
+        Err(Error::NoConvergence {
+            operation: "power iteration",
+            iterations: max_iterations,
+        })
+    }
+}
+
+/// Zero `a[p][q]` and `a[q][p]` with the rotation `J^T A J`, accumulating
+/// `V J` into the eigenvectors
+fn rotate<T: Real>(a: &mut Matrix<T>, v: &mut Matrix<T>, p: usize, q: usize) {
+    let n = a.rows;
+    let apq = a.data[p * n + q];
+    if apq == T::zero() {
+        return;
+    }
+    let theta = (a.data[q * n + q] - a.data[p * n + p]) / (T::from_f64(2.0) * apq);
+    let t = T::one() / (theta.abs() + (theta * theta + T::one()).sqrt());
+    let t = if theta < T::zero() { -t } else { t };
+    let c = T::one() / (t * t + T::one()).sqrt();
+    let s = t * c;
+
+    for k in 0..n {
+        let (akp, akq) = (a.data[p * n + k], a.data[q * n + k]);
+        a.data[p * n + k] = c * akp - s * akq;
+        a.data[q * n + k] = s * akp + c * akq;
+    }
+    for m in [&mut *a, v] {
+        for row in m.data.chunks_exact_mut(n) {
+            let (rkp, rkq) = (row[p], row[q]);
+            row[p] = c * rkp - s * rkq;
+            row[q] = s * rkp + c * rkq;
+        }
+    }
+}
+
+fn off_diagonal_norm<T: Real>(a: &Matrix<T>) -> T {
+    let n = a.rows;
+    let mut off = a.clone();
+    for i in 0..n {
+        off.data[i * n + i] = T::zero();
+    }
+    off.norm_frobenius()
+}
+
+fn normalized<T: Field>(mut x: Matrix<T>) -> Matrix<T> {
+    let norm = T::from_real(x.norm_frobenius());
+    x.data.iter_mut().for_each(|v| *v /= norm);
+    x
+}
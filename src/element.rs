@@ -0,0 +1,478 @@
+//! Element types a [`Matrix`](crate::matrix::Matrix) can hold
+//!
+//! Every element type is plain data that MPI can transfer directly, parses
+//! from and prints to the text format, and picks its own GEMM microkernel so
+//! that floating-point types can use SIMD while the others fall back to the
+//! scalar reference kernel.
+
+use crate::accumulate::{self, Accumulation};
+use crate::error::{Error, Result};
+use crate::matrix_market::MarketElement;
+use crate::simd::{self, Microkernel, SimdLevel};
+use mpi::datatype::Equivalence;
+use num_complex::Complex;
+use std::fmt::{self, Debug, Display};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Runtime tag for the element types implemented in this crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dtype {
+    F32,
+    F64,
+    I32,
+    I64,
+    /// Complex number with `f32` parts
+    C64,
+    /// Complex number with `f64` parts
+    C128,
+}
+
+impl Dtype {
+    pub const ALL: [Dtype; 6] = [
+        Dtype::F32,
+        Dtype::F64,
+        Dtype::I32,
+        Dtype::I64,
+        Dtype::C64,
+        Dtype::C128,
+    ];
+
+    /// Short type name as accepted by `--dtype`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dtype::F32 => "f32",
+            Dtype::F64 => "f64",
+            Dtype::I32 => "i32",
+            Dtype::I64 => "i64",
+            Dtype::C64 => "c64",
+            Dtype::C128 => "c128",
+        }
+    }
+
+    /// Size of one element in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            Dtype::F32 | Dtype::I32 => 4,
+            Dtype::F64 | Dtype::I64 | Dtype::C64 => 8,
+            Dtype::C128 => 16,
+        }
+    }
+
+    /// Parse a short type name
+    pub fn parse(name: &str) -> Result<Dtype> {
+        Dtype::ALL
+            .into_iter()
+            .find(|dtype| dtype.name() == name)
+            .ok_or_else(|| Error::invalid_argument("element type", name))
+    }
+}
+
+impl fmt::Display for Dtype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Fixed-size byte encoding used by the binary file formats
+pub trait Bytes: Sized {
+    /// Append the native-endian bytes of `self`
+    fn write_ne(self, out: &mut Vec<u8>);
+
+    /// Decode a value from exactly its size in bytes, stored in the given order
+    fn read(bytes: &[u8], big_endian: bool) -> Self;
+
+    /// Borrow native-endian bytes as values, if they are aligned for them
+    /// and a whole number of values long
+    fn view_ne(bytes: &[u8]) -> Option<&[Self]> {
+        let _ = bytes;
+        None
+    }
+}
+
+macro_rules! impl_bytes {
+    ($($ty:ty),*) => {
+        $(
+            impl Bytes for $ty {
+                fn write_ne(self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_ne_bytes());
+                }
+
+                fn read(bytes: &[u8], big_endian: bool) -> Self {
+                    let bytes = bytes.try_into().expect("one element's worth of bytes");
+                    if big_endian {
+                        <$ty>::from_be_bytes(bytes)
+                    } else {
+                        <$ty>::from_le_bytes(bytes)
+                    }
+                }
+
+                fn view_ne(bytes: &[u8]) -> Option<&[Self]> {
+                    // SAFETY: every bit pattern is a valid value, and
+                    // `align_to` only reinterprets the aligned middle part
+                    let (head, values, tail) = unsafe { bytes.align_to::<$ty>() };
+                    (head.is_empty() && tail.is_empty()).then_some(values)
+                }
+            }
+        )*
+    };
+}
+
+impl_bytes!(f32, f64, i32, i64);
+
+/// Real part first, then imaginary part, each in the element byte order
+impl<F: Bytes> Bytes for Complex<F> {
+    fn write_ne(self, out: &mut Vec<u8>) {
+        self.re.write_ne(out);
+        self.im.write_ne(out);
+    }
+
+    fn read(bytes: &[u8], big_endian: bool) -> Self {
+        let (re, im) = bytes.split_at(bytes.len() / 2);
+        Complex::new(F::read(re, big_endian), F::read(im, big_endian))
+    }
+
+    fn view_ne(bytes: &[u8]) -> Option<&[Self]> {
+        let parts = F::view_ne(bytes)?;
+        if parts.len() % 2 != 0 {
+            return None;
+        }
+        // SAFETY: `Complex` is `repr(C)` with the real and imaginary part as
+        // its fields, so n values are laid out exactly as 2n parts
+        Some(unsafe { std::slice::from_raw_parts(parts.as_ptr().cast(), parts.len() / 2) })
+    }
+}
+
+/// Numeric type stored in a matrix
+pub trait Element:
+    Copy
+    + Send
+    + Sync
+    + Debug
+    + Display
+    + Default
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + Equivalence
+    + Bytes
+    + MarketElement
+    + 'static
+{
+    /// Runtime tag for this type
+    const DTYPE: Dtype;
+
+    /// Additive identity
+    fn zero() -> Self;
+
+    /// Multiplicative identity
+    fn one() -> Self;
+
+    /// Parse one value of the text format
+    fn parse_text(text: &str) -> Result<Self>;
+
+    /// GEMM microkernel for `level`; types without a SIMD kernel use the scalar one
+    fn microkernel(level: SimdLevel) -> Microkernel<Self> {
+        let _ = level;
+        simd::scalar_microkernel::<Self>
+    }
+
+    /// Dot product `sum x[i] * y[i]` summed in `mode`; types whose sums are
+    /// exact ignore the mode
+    fn dot(mode: Accumulation, x: &[Self], y: &[Self]) -> Self {
+        let _ = mode;
+        let mut sum = Self::zero();
+        for (&x, &y) in x.iter().zip(y) {
+            sum += x * y;
+        }
+        sum
+    }
+}
+
+macro_rules! impl_element {
+    ($ty:ty, $dtype:expr, $zero:expr, $one:expr $(, $dot:path)?) => {
+        impl Element for $ty {
+            const DTYPE: Dtype = $dtype;
+
+            fn zero() -> Self {
+                $zero
+            }
+
+            fn one() -> Self {
+                $one
+            }
+
+            fn parse_text(text: &str) -> Result<Self> {
+                text.parse::<$ty>().map_err(|e| Error::parse(text, e))
+            }
+            $(
+                fn dot(mode: Accumulation, x: &[Self], y: &[Self]) -> Self {
+                    $dot(mode, x, y)
+                }
+            )?
+        }
+    };
+}
+
+macro_rules! impl_simd_element {
+    ($ty:ty, $dtype:expr, $dispatch:path) => {
+        impl Element for $ty {
+            const DTYPE: Dtype = $dtype;
+
+            fn zero() -> Self {
+                0.0
+            }
+
+            fn one() -> Self {
+                1.0
+            }
+
+            fn parse_text(text: &str) -> Result<Self> {
+                text.parse::<$ty>().map_err(|e| Error::parse(text, e))
+            }
+
+            fn microkernel(level: SimdLevel) -> Microkernel<Self> {
+                $dispatch(level)
+            }
+
+            fn dot(mode: Accumulation, x: &[Self], y: &[Self]) -> Self {
+                accumulate::real_dot(mode, x, y)
+            }
+        }
+    };
+}
+
+impl_simd_element!(f64, Dtype::F64, simd::f64_microkernel);
+impl_simd_element!(f32, Dtype::F32, simd::f32_microkernel);
+impl_element!(i32, Dtype::I32, 0, 1);
+impl_element!(i64, Dtype::I64, 0, 1);
+impl_element!(
+    Complex<f32>,
+    Dtype::C64,
+    Complex::new(0.0, 0.0),
+    Complex::new(1.0, 0.0),
+    accumulate::complex_dot
+);
+impl_element!(
+    Complex<f64>,
+    Dtype::C128,
+    Complex::new(0.0, 0.0),
+    Complex::new(1.0, 0.0),
+    accumulate::complex_dot
+);
+
+/// Element type with exact division, as needed by the factorizations
+///
+/// Implemented for the floating-point and complex element types; integer
+/// matrices have to be converted first.
+pub trait Field: Element + Div<Output = Self> + DivAssign {
+    /// Type of magnitudes: the type itself for reals, the part type for complex
+    type Real: Real;
+
+    /// Absolute value (modulus for complex numbers)
+    fn abs(self) -> Self::Real;
+
+    /// Complex conjugate; the identity for reals
+    fn conj(self) -> Self;
+
+    /// Real part
+    fn real(self) -> Self::Real;
+
+    /// Embed a real value
+    fn from_real(value: Self::Real) -> Self;
+}
+
+/// Real floating-point element type
+pub trait Real: Field<Real = Self> + PartialOrd {
+    /// Machine epsilon
+    const EPSILON: Self;
+
+    fn sqrt(self) -> Self;
+
+    fn from_f64(value: f64) -> Self;
+
+    fn to_f64(self) -> f64;
+
+    /// The larger of two values, or `self` if they are unordered
+    fn max(self, other: Self) -> Self {
+        if other > self {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+macro_rules! impl_real {
+    ($ty:ty) => {
+        impl Field for $ty {
+            type Real = $ty;
+
+            fn abs(self) -> $ty {
+                <$ty>::abs(self)
+            }
+
+            fn conj(self) -> $ty {
+                self
+            }
+
+            fn real(self) -> $ty {
+                self
+            }
+
+            fn from_real(value: $ty) -> $ty {
+                value
+            }
+        }
+
+        impl Real for $ty {
+            const EPSILON: $ty = <$ty>::EPSILON;
+
+            fn sqrt(self) -> $ty {
+                <$ty>::sqrt(self)
+            }
+
+            fn from_f64(value: f64) -> $ty {
+                value as $ty
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+
+        impl Field for Complex<$ty> {
+            type Real = $ty;
+
+            fn abs(self) -> $ty {
+                self.norm()
+            }
+
+            fn conj(self) -> Self {
+                Complex::conj(&self)
+            }
+
+            fn real(self) -> $ty {
+                self.re
+            }
+
+            fn from_real(value: $ty) -> Self {
+                Complex::new(value, 0.0)
+            }
+        }
+    };
+}
+
+impl_real!(f32);
+impl_real!(f64);
+
+/// Element type that can be compared within a tolerance
+///
+/// Errors are measured in `f64` whatever the element type, so one set of
+/// tolerances works for every [`Dtype`].
+pub trait Approx: Element {
+    /// `|self - other|`, or infinity if it is undefined (NaN involved)
+    fn abs_diff(self, other: Self) -> f64;
+
+    /// `|self|` as `f64`
+    fn magnitude(self) -> f64;
+
+    /// Number of representable values between `self` and `other`; the
+    /// largest over the parts of complex numbers, and `u64::MAX` for NaN
+    fn ulp_distance(self, other: Self) -> u64;
+}
+
+macro_rules! impl_approx_float {
+    ($ty:ty, $bits:ty) => {
+        impl Approx for $ty {
+            fn abs_diff(self, other: $ty) -> f64 {
+                if self == other {
+                    return 0.0;
+                }
+                let diff = (self as f64 - other as f64).abs();
+                if diff.is_nan() {
+                    f64::INFINITY
+                } else {
+                    diff
+                }
+            }
+
+            fn magnitude(self) -> f64 {
+                (self as f64).abs()
+            }
+
+            fn ulp_distance(self, other: $ty) -> u64 {
+                if self == other {
+                    return 0;
+                }
+                if self.is_nan() || other.is_nan() {
+                    return u64::MAX;
+                }
+                // Reorder the sign-magnitude bit patterns so that adjacent
+                // floats map to adjacent integers, with both zeros at 0
+                let ordered = |x: $ty| {
+                    let bits = x.to_bits() as $bits;
+                    if bits < 0 {
+                        <$bits>::MIN as i128 - bits as i128
+                    } else {
+                        bits as i128
+                    }
+                };
+                (ordered(self) - ordered(other)).unsigned_abs() as u64
+            }
+        }
+
+        impl Approx for Complex<$ty> {
+            fn abs_diff(self, other: Self) -> f64 {
+                if self == other {
+                    return 0.0;
+                }
+                let re = self.re as f64 - other.re as f64;
+                let im = self.im as f64 - other.im as f64;
+                let diff = re.hypot(im);
+                if diff.is_nan() {
+                    f64::INFINITY
+                } else {
+                    diff
+                }
+            }
+
+            fn magnitude(self) -> f64 {
+                (self.re as f64).hypot(self.im as f64)
+            }
+
+            fn ulp_distance(self, other: Self) -> u64 {
+                self.re
+                    .ulp_distance(other.re)
+                    .max(self.im.ulp_distance(other.im))
+            }
+        }
+    };
+}
+
+macro_rules! impl_approx_int {
+    ($ty:ty) => {
+        impl Approx for $ty {
+            fn abs_diff(self, other: $ty) -> f64 {
+                (self as i128 - other as i128).unsigned_abs() as f64
+            }
+
+            fn magnitude(self) -> f64 {
+                (self as f64).abs()
+            }
+
+            fn ulp_distance(self, other: $ty) -> u64 {
+                (self as i128 - other as i128).unsigned_abs() as u64
+            }
+        }
+    };
+}
+
+impl_approx_float!(f32, i32);
+impl_approx_float!(f64, i64);
+impl_approx_int!(i32);
+impl_approx_int!(i64);
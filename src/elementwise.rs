@@ -0,0 +1,91 @@
+//! Element-wise combinators and products on [`Matrix`]
+//!
+//! `map`, `zip_map` and `apply` cover the pre- and post-processing that
+//! would otherwise be written as index loops over `data`. Shape mismatches
+//! are reported as errors, as for [`Matrix::multiply`].
+
+use crate::element::Element;
+use crate::error::{Error, Result};
+use crate::matrix::Matrix;
+
+impl<T: Element> Matrix<T> {
+    /// New matrix of the same shape with `f` applied to every element
+    pub fn map<U: Element>(&self, f: impl FnMut(T) -> U) -> Matrix<U> {
+        Matrix {
+            data: self.data.iter().copied().map(f).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
+    /// New matrix with `f` applied to matching elements of `self` and `other`
+    pub fn zip_map<U: Element, V: Element>(
+        &self,
+        other: &Matrix<U>,
+        f: impl FnMut(T, U) -> V,
+    ) -> Result<Matrix<V>> {
+        self.zip_map_as("combine element-wise", other, f)
+    }
+
+    /// [`zip_map`](Matrix::zip_map), naming `operation` in a shape mismatch
+    fn zip_map_as<U: Element, V: Element>(
+        &self,
+        operation: &'static str,
+        other: &Matrix<U>,
+        mut f: impl FnMut(T, U) -> V,
+    ) -> Result<Matrix<V>> {
+        check_same_shape(operation, self, other)?;
+        Ok(Matrix {
+            data: self
+                .data
+                .iter()
+                .zip(&other.data)
+                .map(|(&x, &y)| f(x, y))
+                .collect(),
+            rows: self.rows,
+            cols: self.cols,
+        })
+    }
+
+    /// Replace every element `x` with `f(x)` in place
+    pub fn apply(&mut self, mut f: impl FnMut(T) -> T) {
+        for x in &mut self.data {
+            *x = f(*x);
+        }
+    }
+
+    /// Hadamard (element-wise) product of two matrices of the same shape
+    pub fn hadamard(&self, other: &Matrix<T>) -> Result<Matrix<T>> {
+        self.zip_map_as("Hadamard product", other, |x, y| x * y)
+    }
+
+    /// Kronecker product: block `(i, j)` of the result is `self[i][j] * other`
+    pub fn kronecker(&self, other: &Matrix<T>) -> Matrix<T> {
+        let rows = self.rows * other.rows;
+        let cols = self.cols * other.cols;
+        let mut data = Vec::with_capacity(rows * cols);
+        for a_row in self.data.chunks_exact(self.cols.max(1)).take(self.rows) {
+            for b_row in other.data.chunks_exact(other.cols.max(1)).take(other.rows) {
+                for &a in a_row {
+                    data.extend(b_row.iter().map(|&b| a * b));
+                }
+            }
+        }
+        Matrix { data, rows, cols }
+    }
+}
+
+fn check_same_shape<T: Element, U: Element>(
+    operation: &'static str,
+    lhs: &Matrix<T>,
+    rhs: &Matrix<U>,
+) -> Result<()> {
+    if lhs.rows != rhs.rows || lhs.cols != rhs.cols {
+        return Err(Error::DimensionMismatch {
+            operation,
+            left: (lhs.rows, lhs.cols),
+            right: (rhs.rows, rhs.cols),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,261 @@
+//! Crate-wide error type
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Result with this crate's [`Error`]
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Opening, reading or writing a file failed
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    /// A value in a matrix file is not a valid element
+    Parse {
+        path: Option<PathBuf>,
+        line: Option<usize>,
+        column: Option<usize>,
+        value: String,
+        message: String,
+    },
+    /// A matrix file is malformed (ragged rows, no data, ...)
+    Format {
+        path: Option<PathBuf>,
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+    /// Operand shapes do not fit the operation
+    DimensionMismatch {
+        operation: &'static str,
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+    /// A buffer does not hold `rows * cols` elements
+    DataLength {
+        len: usize,
+        rows: usize,
+        cols: usize,
+    },
+    /// A `size` block starting at `start` does not fit in a `dims` matrix
+    OutOfBounds {
+        start: (usize, usize),
+        size: (usize, usize),
+        dims: (usize, usize),
+    },
+    /// A strided view would reach past the end of its buffer
+    ViewExtent {
+        rows: usize,
+        cols: usize,
+        row_stride: usize,
+        col_stride: usize,
+        len: usize,
+    },
+    /// The operation needs a square matrix
+    NotSquare {
+        operation: &'static str,
+        rows: usize,
+        cols: usize,
+    },
+    /// A factorization met a zero pivot in column `pivot`
+    Singular {
+        operation: &'static str,
+        pivot: usize,
+    },
+    /// A Cholesky pivot was not positive, so the matrix is not positive definite
+    NotPositiveDefinite { pivot: usize },
+    /// An iterative method did not converge within `iterations` steps
+    NoConvergence {
+        operation: &'static str,
+        iterations: usize,
+    },
+    /// An option or configuration value is not accepted
+    InvalidArgument { argument: String, value: String },
+    /// Distributed run failed at `rank`
+    Mpi { rank: i32, message: String },
+    /// A worker could not start its compute threads
+    ThreadPool {
+        rank: i32,
+        source: rayon::ThreadPoolBuildError,
+    },
+}
+
+impl Error {
+    pub(crate) fn io(path: Option<&Path>, source: io::Error) -> Self {
+        Error::Io {
+            path: path.map(Path::to_path_buf),
+            source,
+        }
+    }
+
+    pub fn invalid_argument(argument: impl Into<String>, value: impl Into<String>) -> Self {
+        Error::InvalidArgument {
+            argument: argument.into(),
+            value: value.into(),
+        }
+    }
+
+    pub(crate) fn parse(value: &str, message: impl fmt::Display) -> Self {
+        Error::Parse {
+            path: None,
+            line: None,
+            column: None,
+            value: value.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Attach the file and line a parse or format error was found at
+    pub(crate) fn at(mut self, file: &Path, at_line: Option<usize>) -> Self {
+        if let Error::Parse { path, line, .. } | Error::Format { path, line, .. } = &mut self {
+            *path = Some(file.to_path_buf());
+            if at_line.is_some() {
+                *line = at_line;
+            }
+        }
+        self
+    }
+
+    /// Attach the line and column a parse or format error was found at
+    pub(crate) fn at_position(mut self, at_line: usize, at_column: Option<usize>) -> Self {
+        if let Error::Parse { line, column, .. } | Error::Format { line, column, .. } = &mut self {
+            *line = Some(at_line);
+            *column = at_column;
+        }
+        self
+    }
+}
+
+/// ` in <path>`, ` on line <n>`, `, column <m>` or a combination, for
+/// file-related messages
+struct Location<'a>(&'a Option<PathBuf>, &'a Option<usize>, &'a Option<usize>);
+
+impl fmt::Display for Location<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = self.0 {
+            write!(f, " in {:?}", path)?;
+        }
+        if let Some(line) = self.1 {
+            write!(f, " on line {}", line)?;
+        }
+        if let Some(column) = self.2 {
+            let separator = if self.1.is_some() { "," } else { " in" };
+            write!(f, "{} column {}", separator, column)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io {
+                path: Some(path),
+                source,
+            } => write!(f, "I/O error on {:?}: {}", path, source),
+            Error::Io { path: None, source } => write!(f, "I/O error: {}", source),
+            Error::Parse {
+                path,
+                line,
+                column,
+                value,
+                message,
+            } => write!(
+                f,
+                "Failed to parse value {:?}{}: {}",
+                value,
+                Location(path, line, column),
+                message
+            ),
+            Error::Format {
+                path,
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "Invalid matrix file{}: {}",
+                Location(path, line, column),
+                message
+            ),
+            Error::DimensionMismatch {
+                operation,
+                left,
+                right,
+            } => write!(
+                f,
+                "Matrix dimensions incompatible for {}: {}x{} and {}x{}",
+                operation, left.0, left.1, right.0, right.1
+            ),
+            Error::DataLength { len, rows, cols } => write!(
+                f,
+                "Data length {} does not match dimensions {}x{}",
+                len, rows, cols
+            ),
+            Error::OutOfBounds { start, size, dims } => write!(
+                f,
+                "Index out of bounds: {}x{} block at ({}, {}) for matrix {}x{}",
+                size.0, size.1, start.0, start.1, dims.0, dims.1
+            ),
+            Error::ViewExtent {
+                rows,
+                cols,
+                row_stride,
+                col_stride,
+                len,
+            } => write!(
+                f,
+                "View {}x{} with strides ({}, {}) exceeds buffer of {} elements",
+                rows, cols, row_stride, col_stride, len
+            ),
+            Error::NotSquare {
+                operation,
+                rows,
+                cols,
+            } => write!(
+                f,
+                "Matrix must be square for {}: got {}x{}",
+                operation, rows, cols
+            ),
+            Error::Singular { operation, pivot } => write!(
+                f,
+                "Matrix is singular to working precision: {} found a zero pivot in column {}",
+                operation, pivot
+            ),
+            Error::NotPositiveDefinite { pivot } => write!(
+                f,
+                "Matrix is not positive definite: pivot {} is not positive",
+                pivot
+            ),
+            Error::NoConvergence {
+                operation,
+                iterations,
+            } => write!(
+                f,
+                "{} did not converge after {} iterations",
+                operation, iterations
+            ),
+            Error::InvalidArgument { argument, value } => {
+                write!(f, "Invalid {}: {}", argument, value)
+            }
+            Error::Mpi { rank, message } => write!(f, "Rank {}: {}", rank, message),
+            Error::ThreadPool { rank, source } => {
+                write!(f, "Rank {}: failed to create thread pool: {}", rank, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::ThreadPool { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,411 @@
+//! Blocked (tiled) GEMM kernel
+//!
+//! Computes `C += A * B` with the usual three-level blocking: B is packed into
+//! `kc x nc` panels, A into `mc x kc` blocks, and a small `MR x NR` microkernel
+//! runs over the packed data so that both operands are read with unit stride.
+//! [`gemm_parallel`] shares each packed B panel between threads and hands out
+//! disjoint row blocks of C.
+
+use crate::accumulate::Accumulation;
+use crate::element::Element;
+use crate::error::{Error, Result};
+use crate::simd::{self, Microkernel, SimdLevel};
+use crate::view::MatrixView;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+
+/// Rows of C produced by one microkernel call
+pub const MR: usize = 4;
+/// Columns of C produced by one microkernel call
+pub const NR: usize = 8;
+
+/// Multiplication algorithm used for a product
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+    /// Blocked kernel only
+    #[default]
+    Blocked,
+    /// Strassen-Winograd recursion down to `crossover`, then the blocked kernel
+    Strassen { crossover: usize },
+}
+
+/// Default size below which Strassen recursion hands over to the blocked kernel
+pub const DEFAULT_STRASSEN_CROSSOVER: usize = 256;
+
+impl Algorithm {
+    /// Parse an algorithm name (`blocked` or `strassen`)
+    pub fn parse(name: &str, crossover: usize) -> Result<Self> {
+        match name {
+            "blocked" | "standard" => Ok(Algorithm::Blocked),
+            "strassen" => Ok(Algorithm::Strassen {
+                crossover: crossover.max(1),
+            }),
+            _ => Err(Error::invalid_argument("algorithm", name)),
+        }
+    }
+}
+
+/// Whether an operand enters a product as stored or transposed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Transpose {
+    #[default]
+    No,
+    Yes,
+}
+
+impl Transpose {
+    /// Parse a BLAS-style transpose flag (`n` or `t`)
+    pub fn parse(flag: &str) -> Result<Self> {
+        match flag {
+            "n" | "N" | "no" => Ok(Transpose::No),
+            "t" | "T" | "yes" => Ok(Transpose::Yes),
+            _ => Err(Error::invalid_argument("transpose flag", flag)),
+        }
+    }
+
+    pub fn from_flag(transposed: bool) -> Self {
+        if transposed {
+            Transpose::Yes
+        } else {
+            Transpose::No
+        }
+    }
+
+    pub fn is_transposed(&self) -> bool {
+        *self == Transpose::Yes
+    }
+
+    /// `op(view)`: the view itself or its transpose
+    pub fn apply<'a, T: Element>(&self, view: MatrixView<'a, T>) -> MatrixView<'a, T> {
+        match self {
+            Transpose::No => view,
+            Transpose::Yes => view.transpose(),
+        }
+    }
+}
+
+/// Scalars and transpose flags of `C = alpha * op(A) * op(B) + beta * C`
+///
+/// The default is a plain product: `alpha = 1`, `beta = 0`, no transposes,
+/// naive accumulation, not reproducible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GemmSpec<T = f64> {
+    pub alpha: T,
+    pub beta: T,
+    pub trans_a: Transpose,
+    pub trans_b: Transpose,
+    /// Summation of the dot products; anything but naive bypasses the
+    /// blocked and Strassen kernels
+    pub accumulation: Accumulation,
+    /// Make every output bit independent of how the work is split; see
+    /// [`accumulate`](crate::accumulate) for the guarantee
+    pub reproducible: bool,
+}
+
+impl<T: Element> Default for GemmSpec<T> {
+    fn default() -> Self {
+        GemmSpec {
+            alpha: T::one(),
+            beta: T::zero(),
+            trans_a: Transpose::No,
+            trans_b: Transpose::No,
+            accumulation: Accumulation::Naive,
+            reproducible: false,
+        }
+    }
+}
+
+impl<T: Element> GemmSpec<T> {
+    pub fn with_alpha(mut self, alpha: T) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn with_beta(mut self, beta: T) -> Self {
+        self.beta = beta;
+        self
+    }
+
+    pub fn with_trans_a(mut self, trans_a: Transpose) -> Self {
+        self.trans_a = trans_a;
+        self
+    }
+
+    pub fn with_trans_b(mut self, trans_b: Transpose) -> Self {
+        self.trans_b = trans_b;
+        self
+    }
+
+    pub fn with_accumulation(mut self, accumulation: Accumulation) -> Self {
+        self.accumulation = accumulation;
+        self
+    }
+
+    pub fn with_reproducible(mut self, reproducible: bool) -> Self {
+        self.reproducible = reproducible;
+        self
+    }
+}
+
+/// Tile sizes used by the blocked kernel
+///
+/// `mc` and `nc` are rounded up to multiples of `MR` and `NR` internally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileConfig {
+    /// Rows of A packed per block (should fit in L2 together with a B micro-panel)
+    pub mc: usize,
+    /// Depth of the packed panels (shared inner dimension)
+    pub kc: usize,
+    /// Columns of B packed per panel (should fit in L3)
+    pub nc: usize,
+}
+
+impl Default for TileConfig {
+    fn default() -> Self {
+        TileConfig {
+            mc: 128,
+            kc: 256,
+            nc: 4096,
+        }
+    }
+}
+
+impl TileConfig {
+    /// Create a tile configuration, rejecting zero-sized tiles
+    pub fn new(mc: usize, kc: usize, nc: usize) -> Result<Self> {
+        if mc == 0 || kc == 0 || nc == 0 {
+            return Err(Error::invalid_argument(
+                "tile sizes (must be non-zero)",
+                format!("mc={}, kc={}, nc={}", mc, kc, nc),
+            ));
+        }
+        Ok(TileConfig { mc, kc, nc })
+    }
+}
+
+/// Read-only operand addressed by explicit row and column strides
+#[derive(Debug, Clone, Copy)]
+pub struct Strided<'a, T = f64> {
+    pub data: &'a [T],
+    pub row_stride: usize,
+    pub col_stride: usize,
+}
+
+impl<'a, T: Element> Strided<'a, T> {
+    /// Operand over a dense row-major buffer with `cols` columns
+    pub fn row_major(data: &'a [T], cols: usize) -> Self {
+        Strided {
+            data,
+            row_stride: cols,
+            col_stride: 1,
+        }
+    }
+
+    /// Operand starting at `(row, col)` of this one, with the same strides
+    pub fn block(&self, row: usize, col: usize) -> Strided<'a, T> {
+        Strided {
+            data: &self.data[row * self.row_stride + col * self.col_stride..],
+            row_stride: self.row_stride,
+            col_stride: self.col_stride,
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn at(&self, row: usize, col: usize) -> T {
+        self.data[row * self.row_stride + col * self.col_stride]
+    }
+}
+
+/// Compute `C += alpha * A * B` where A is `m x k`, B is `k x n` and C is
+/// row-major with leading dimension `ldc`
+#[allow(clippy::too_many_arguments)]
+pub fn gemm<T: Element>(
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: T,
+    a: Strided<'_, T>,
+    b: Strided<'_, T>,
+    c: &mut [T],
+    ldc: usize,
+    tiles: &TileConfig,
+) {
+    if m == 0 || n == 0 || k == 0 {
+        return;
+    }
+
+    let mc = round_up(tiles.mc.min(m), MR);
+    let nc = round_up(tiles.nc.min(n), NR);
+    let kc = tiles.kc.min(k);
+
+    let ukr = simd::microkernel::<T>(SimdLevel::active());
+    let mut a_pack = vec![T::zero(); mc * kc];
+    let mut b_pack = vec![T::zero(); nc * kc];
+
+    for jc in (0..n).step_by(nc) {
+        let nb = nc.min(n - jc);
+        for pc in (0..k).step_by(kc) {
+            let kb = kc.min(k - pc);
+            pack_b(&b, pc, jc, kb, nb, &mut b_pack);
+
+            for ic in (0..m).step_by(mc) {
+                let mb = mc.min(m - ic);
+                pack_a(&a, ic, pc, mb, kb, alpha, &mut a_pack);
+                macro_block(
+                    ukr,
+                    mb,
+                    nb,
+                    kb,
+                    &a_pack,
+                    &b_pack,
+                    &mut c[ic * ldc + jc..],
+                    ldc,
+                );
+            }
+        }
+    }
+}
+
+/// Compute `C += alpha * A * B` like [`gemm`], splitting the rows of C across
+/// the threads of `pool`
+#[allow(clippy::too_many_arguments)]
+pub fn gemm_parallel<T: Element>(
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: T,
+    a: Strided<'_, T>,
+    b: Strided<'_, T>,
+    c: &mut [T],
+    ldc: usize,
+    tiles: &TileConfig,
+    pool: &ThreadPool,
+) {
+    if m == 0 || n == 0 || k == 0 {
+        return;
+    }
+
+    // Shrink the row block so that every thread gets at least one block
+    let threads = pool.current_num_threads().max(1);
+    let mc = round_up(tiles.mc.min(m.div_ceil(threads)), MR);
+    let nc = round_up(tiles.nc.min(n), NR);
+    let kc = tiles.kc.min(k);
+
+    let ukr = simd::microkernel::<T>(SimdLevel::active());
+    let mut b_pack = vec![T::zero(); nc * kc];
+    // C may be a sub-block of a wider matrix: stop at the end of its last row
+    let c = &mut c[..(m - 1) * ldc + n];
+
+    pool.install(|| {
+        for jc in (0..n).step_by(nc) {
+            let nb = nc.min(n - jc);
+            for pc in (0..k).step_by(kc) {
+                let kb = kc.min(k - pc);
+                pack_b(&b, pc, jc, kb, nb, &mut b_pack);
+                let b_pack = &b_pack;
+
+                c.par_chunks_mut(mc * ldc).enumerate().for_each_init(
+                    || vec![T::zero(); mc * kc],
+                    |a_pack, (block, c_block)| {
+                        let ic = block * mc;
+                        let mb = mc.min(m - ic);
+                        pack_a(&a, ic, pc, mb, kb, alpha, a_pack);
+                        macro_block(ukr, mb, nb, kb, a_pack, b_pack, &mut c_block[jc..], ldc);
+                    },
+                );
+            }
+        }
+    });
+}
+
+/// Run the microkernel over every `MR x NR` tile of an `mb x nb` block of C
+#[allow(clippy::too_many_arguments)]
+fn macro_block<T: Element>(
+    ukr: Microkernel<T>,
+    mb: usize,
+    nb: usize,
+    kb: usize,
+    a_pack: &[T],
+    b_pack: &[T],
+    c: &mut [T],
+    ldc: usize,
+) {
+    for jr in (0..nb).step_by(NR) {
+        let nr = NR.min(nb - jr);
+        let b_panel = &b_pack[jr * kb..(jr + NR) * kb];
+        for ir in (0..mb).step_by(MR) {
+            let mr = MR.min(mb - ir);
+            let a_panel = &a_pack[ir * kb..(ir + MR) * kb];
+            ukr(kb, a_panel, b_panel, &mut c[ir * ldc + jr..], ldc, mr, nr);
+        }
+    }
+}
+
+/// Pack an `mb x kb` block of A starting at `(row, col)` into `MR`-row panels,
+/// scaled by `alpha` and zero-padding the last panel
+#[allow(clippy::too_many_arguments)]
+fn pack_a<T: Element>(
+    a: &Strided<'_, T>,
+    row: usize,
+    col: usize,
+    mb: usize,
+    kb: usize,
+    alpha: T,
+    out: &mut [T],
+) {
+    let scaled = alpha != T::one();
+    for ir in (0..mb).step_by(MR) {
+        let panel = &mut out[ir * kb..(ir + MR) * kb];
+        let rows = MR.min(mb - ir);
+        for p in 0..kb {
+            for i in 0..MR {
+                panel[p * MR + i] = if i < rows {
+                    let value = a.at(row + ir + i, col + p);
+                    if scaled {
+                        alpha * value
+                    } else {
+                        value
+                    }
+                } else {
+                    T::zero()
+                };
+            }
+        }
+    }
+}
+
+/// Pack a `kb x nb` panel of B starting at `(row, col)` into `NR`-column
+/// micro-panels, zero-padding the last one
+fn pack_b<T: Element>(
+    b: &Strided<'_, T>,
+    row: usize,
+    col: usize,
+    kb: usize,
+    nb: usize,
+    out: &mut [T],
+) {
+    for jr in (0..nb).step_by(NR) {
+        let panel = &mut out[jr * kb..(jr + NR) * kb];
+        let cols = NR.min(nb - jr);
+        for p in 0..kb {
+            for j in 0..NR {
+                panel[p * NR + j] = if j < cols {
+                    b.at(row + p, col + jr + j)
+                } else {
+                    T::zero()
+                };
+            }
+        }
+    }
+}
+
+/// `y += alpha * x`, element by element
+pub(crate) fn axpy<T: Element>(y: &mut [T], alpha: T, x: &[T]) {
+    for (y, &x) in y.iter_mut().zip(x) {
+        *y += alpha * x;
+    }
+}
+
+fn round_up(value: usize, multiple: usize) -> usize {
+    value.div_ceil(multiple) * multiple
+}
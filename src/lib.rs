@@ -1,8 +1,39 @@
+pub mod accumulate;
+pub mod binary;
+pub mod cholesky;
+pub mod compare;
 pub mod coordinator;
+pub mod csv;
+pub mod eigen;
+pub mod element;
+pub mod elementwise;
+pub mod error;
+pub mod kernel;
+pub mod lu;
+pub mod mapped;
 pub mod matrix;
+pub mod matrix_market;
 pub mod mpi_utils;
+pub mod norm;
+pub mod npy;
+pub mod ops;
+pub mod qr;
+pub mod simd;
+pub mod strassen;
+pub mod transpose;
+pub mod view;
 pub mod worker;
 
+pub use accumulate::Accumulation;
+pub use cholesky::Cholesky;
+pub use compare::Comparison;
 pub use coordinator::Coordinator;
+pub use eigen::SymmetricEigen;
+pub use element::{Approx, Dtype, Element, Field, Real};
+pub use error::Error;
+pub use kernel::{Algorithm, TileConfig};
+pub use lu::Lu;
 pub use matrix::Matrix;
+pub use qr::Qr;
+pub use view::{MatrixView, MatrixViewMut};
 pub use worker::Worker;
@@ -0,0 +1,254 @@
+//! LU decomposition with partial pivoting
+//!
+//! Factorizes `P A = L U` with L unit lower-triangular and U upper-triangular,
+//! both kept in one matrix. Each step swaps the row holding the largest entry
+//! of the column into the pivot position, and eliminations run along
+//! contiguous rows of the row-major storage.
+
+use crate::element::{Element, Field, Real};
+use crate::error::{Error, Result};
+use crate::kernel::axpy;
+use crate::matrix::Matrix;
+
+/// LU factorization `P A = L U` of a square matrix
+///
+/// Singular matrices still factorize, so that their determinant is available;
+/// [`solve`](Lu::solve) and [`inverse`](Lu::inverse) then fail with
+/// [`Error::Singular`].
+#[derive(Debug, Clone)]
+pub struct Lu<T = f64> {
+    /// L below the diagonal (its unit diagonal is implied) and U on and above
+    factors: Matrix<T>,
+    /// Row `i` of `P A` is row `perm[i]` of A
+    perm: Vec<usize>,
+    /// Whether P swaps an odd number of rows
+    odd: bool,
+    /// First column whose pivot is negligible, if any
+    singular: Option<usize>,
+}
+
+impl<T: Field> Matrix<T> {
+    /// Factorize this square matrix as `P A = L U`
+    pub fn lu(&self) -> Result<Lu<T>> {
+        Lu::new(self)
+    }
+}
+
+impl<T: Field> Lu<T> {
+    /// Factorize the square matrix `a`
+    ///
+    /// Pivots no larger than `n * eps * max|a_ij|` mark the matrix singular.
+    pub fn new(a: &Matrix<T>) -> Result<Self> {
+        if a.rows != a.cols {
+            return Err(Error::NotSquare {
+                operation: "LU decomposition",
+                rows: a.rows,
+                cols: a.cols,
+            });
+        }
+        let n = a.rows;
+        let mut factors = a.clone();
+        let mut perm: Vec<usize> = (0..n).collect();
+        let mut odd = false;
+        let mut singular = None;
+
+        let zero = T::Real::zero();
+        let scale = a.data.iter().map(|x| x.abs()).fold(zero, Real::max);
+        let tolerance = scale * T::Real::EPSILON * T::Real::from_f64(n as f64);
+
+        for k in 0..n {
+            let (mut p, mut pivot_abs) = (k, zero);
+            for i in k..n {
+                let candidate = factors.data[i * n + k].abs();
+                if candidate > pivot_abs {
+                    (p, pivot_abs) = (i, candidate);
+                }
+            }
+
+            if p != k {
+                let (top, bottom) = factors.data.split_at_mut(p * n);
+                top[k * n..(k + 1) * n].swap_with_slice(&mut bottom[..n]);
+                perm.swap(k, p);
+                odd = !odd;
+            }
+            if pivot_abs <= tolerance {
+                singular.get_or_insert(k);
+                if pivot_abs == zero {
+                    // The whole column below the diagonal is zero already
+                    continue;
+                }
+            }
+
+            let (upper, lower) = factors.data.split_at_mut((k + 1) * n);
+            let pivot_row = &upper[k * n..];
+            let pivot = pivot_row[k];
+            for row in lower.chunks_exact_mut(n) {
+                let factor = row[k] / pivot;
+                row[k] = factor;
+                if factor != T::zero() {
+                    for (x, &u) in row[k + 1..].iter_mut().zip(&pivot_row[k + 1..]) {
+                        *x -= factor * u;
+                    }
+                }
+            }
+        }
+
+        Ok(Lu {
+            factors,
+            perm,
+            odd,
+            singular,
+        })
+    }
+
+    /// Order of the factorized matrix
+    pub fn size(&self) -> usize {
+        self.factors.rows
+    }
+
+    /// Row permutation: row `i` of `P A` is row `permutation()[i]` of A
+    pub fn permutation(&self) -> &[usize] {
+        &self.perm
+    }
+
+    /// Whether a pivot was negligible, making solves and the inverse fail
+    pub fn is_singular(&self) -> bool {
+        self.singular.is_some()
+    }
+
+    /// Unit lower-triangular factor L
+    pub fn l(&self) -> Matrix<T> {
+        let n = self.size();
+        let mut l = Matrix::identity(n);
+        for i in 0..n {
+            l.data[i * n..i * n + i].copy_from_slice(&self.factors.data[i * n..i * n + i]);
+        }
+        l
+    }
+
+    /// Upper-triangular factor U
+    pub fn u(&self) -> Matrix<T> {
+        let n = self.size();
+        let mut u = Matrix::new(n, n);
+        for i in 0..n {
+            u.data[i * n + i..(i + 1) * n]
+                .copy_from_slice(&self.factors.data[i * n + i..(i + 1) * n]);
+        }
+        u
+    }
+
+    /// Determinant of the factorized matrix
+    pub fn determinant(&self) -> T {
+        let n = self.size();
+        let det = (0..n).fold(T::one(), |det, i| det * self.factors.data[i * n + i]);
+        if self.odd {
+            -det
+        } else {
+            det
+        }
+    }
+
+    /// Solve `A X = B` for X, one column of X per column of `b`
+    pub fn solve(&self, b: &Matrix<T>) -> Result<Matrix<T>> {
+        let n = self.check_rhs(b)?;
+        let m = b.cols;
+        let mut x = Matrix::new(n, m);
+        for (i, &src) in self.perm.iter().enumerate() {
+            x.data[i * m..(i + 1) * m].copy_from_slice(&b.data[src * m..(src + 1) * m]);
+        }
+
+        // Forward substitution with the unit lower triangle: row i of X loses
+        // L[i][j] times every earlier row j
+        for i in 1..n {
+            let (done, rest) = x.data.split_at_mut(i * m);
+            let row = &mut rest[..m];
+            for j in 0..i {
+                let l = self.factors.data[i * n + j];
+                if l != T::zero() {
+                    axpy(row, -l, &done[j * m..(j + 1) * m]);
+                }
+            }
+        }
+
+        // Back substitution with the upper triangle
+        for i in (0..n).rev() {
+            let (head, done) = x.data.split_at_mut((i + 1) * m);
+            let row = &mut head[i * m..];
+            for j in i + 1..n {
+                let u = self.factors.data[i * n + j];
+                if u != T::zero() {
+                    axpy(row, -u, &done[(j - i - 1) * m..(j - i) * m]);
+                }
+            }
+            let pivot = self.factors.data[i * n + i];
+            row.iter_mut().for_each(|v| *v /= pivot);
+        }
+
+        Ok(x)
+    }
+
+    /// Solve `A^H X = B` for X, reusing the factorization of A
+    pub fn solve_adjoint(&self, b: &Matrix<T>) -> Result<Matrix<T>> {
+        let n = self.check_rhs(b)?;
+        let m = b.cols;
+        let mut w = b.clone();
+
+        // A^H = U^H L^H P: forward substitution with U^H first
+        for i in 0..n {
+            let (done, rest) = w.data.split_at_mut(i * m);
+            let row = &mut rest[..m];
+            for j in 0..i {
+                let u = self.factors.data[j * n + i];
+                if u != T::zero() {
+                    axpy(row, -u.conj(), &done[j * m..(j + 1) * m]);
+                }
+            }
+            let pivot = self.factors.data[i * n + i].conj();
+            row.iter_mut().for_each(|v| *v /= pivot);
+        }
+
+        // Then back substitution with the unit upper triangle L^H
+        for i in (0..n).rev() {
+            let (head, done) = w.data.split_at_mut((i + 1) * m);
+            let row = &mut head[i * m..];
+            for j in i + 1..n {
+                let l = self.factors.data[j * n + i];
+                if l != T::zero() {
+                    axpy(row, -l.conj(), &done[(j - i - 1) * m..(j - i) * m]);
+                }
+            }
+        }
+
+        // Undo the row permutation
+        let mut x = Matrix::new(n, m);
+        for (i, &dest) in self.perm.iter().enumerate() {
+            x.data[dest * m..(dest + 1) * m].copy_from_slice(&w.data[i * m..(i + 1) * m]);
+        }
+        Ok(x)
+    }
+
+    /// Inverse of the factorized matrix
+    pub fn inverse(&self) -> Result<Matrix<T>> {
+        self.solve(&Matrix::identity(self.size()))
+    }
+
+    /// Check that solves are possible with `b` as right-hand side, returning
+    /// the order of the matrix
+    fn check_rhs(&self, b: &Matrix<T>) -> Result<usize> {
+        let n = self.size();
+        if let Some(pivot) = self.singular {
+            return Err(Error::Singular {
+                operation: "LU decomposition",
+                pivot,
+            });
+        }
+        if b.rows != n {
+            return Err(Error::DimensionMismatch {
+                operation: "solve",
+                left: (n, n),
+                right: (b.rows, b.cols),
+            });
+        }
+        Ok(n)
+    }
+}
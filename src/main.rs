@@ -1,8 +1,335 @@
+use distribiuted_matrix_multiplication::accumulate::Accumulation;
+use distribiuted_matrix_multiplication::compare::{Comparison, DEFAULT_TOLERANCE};
 use distribiuted_matrix_multiplication::coordinator::Coordinator;
-use distribiuted_matrix_multiplication::worker::Worker;
+use distribiuted_matrix_multiplication::csv::CsvOverrides;
+use distribiuted_matrix_multiplication::element::{Approx, Dtype, Element};
+use distribiuted_matrix_multiplication::error::{Error, Result};
+use distribiuted_matrix_multiplication::kernel::{
+    Algorithm, GemmSpec, Transpose, DEFAULT_STRASSEN_CROSSOVER,
+};
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use distribiuted_matrix_multiplication::worker::{self, Worker};
+use mpi::topology::SimpleCommunicator;
 use mpi::traits::*;
+use num_complex::Complex;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Call a generic function with the element type matching a [`Dtype`]
+macro_rules! with_dtype {
+    ($dtype:expr, $func:ident($($arg:expr),*)) => {
+        match $dtype {
+            Dtype::F32 => $func::<f32>($($arg),*),
+            Dtype::F64 => $func::<f64>($($arg),*),
+            Dtype::I32 => $func::<i32>($($arg),*),
+            Dtype::I64 => $func::<i64>($($arg),*),
+            Dtype::C64 => $func::<Complex<f32>>($($arg),*),
+            Dtype::C128 => $func::<Complex<f64>>($($arg),*),
+        }
+    };
+}
+
+/// Command-line options shared by the coordinator and the workers
+struct Options {
+    positional: Vec<String>,
+    threads: Option<usize>,
+    algorithm: Algorithm,
+    dtype: Dtype,
+    /// GEMM scalars, parsed once the element type is known
+    alpha: Option<String>,
+    beta: Option<String>,
+    trans_a: Transpose,
+    trans_b: Transpose,
+    accumulation: Accumulation,
+    reproducible: bool,
+    verify_checksums: bool,
+    matrix_c: Option<PathBuf>,
+    /// Exponent for `--power`, which takes a single input matrix
+    power: Option<u32>,
+    /// Layout of CSV and TSV inputs
+    csv: CsvOverrides,
+    /// Tolerances for `diff`
+    abs_tol: f64,
+    rel_tol: f64,
+}
+
+/// Split `--name value` (or `--name=value`) options from the positional arguments
+fn parse_args(args: &[String]) -> Result<Options> {
+    let mut positional = Vec::new();
+    let mut threads = None;
+    let mut algorithm = String::from("blocked");
+    let mut crossover = DEFAULT_STRASSEN_CROSSOVER;
+    let mut dtype = Dtype::F64;
+    let mut alpha = None;
+    let mut beta = None;
+    let mut trans_a = Transpose::No;
+    let mut trans_b = Transpose::No;
+    let mut accumulation = Accumulation::Naive;
+    let mut reproducible = false;
+    let mut verify_checksums = false;
+    let mut matrix_c = None;
+    let mut power = None;
+    let mut csv = CsvOverrides::default();
+    let mut abs_tol = DEFAULT_TOLERANCE;
+    let mut rel_tol = DEFAULT_TOLERANCE;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let Some(flag) = arg.strip_prefix("--") else {
+            positional.push(arg.clone());
+            continue;
+        };
+        // Switches take no value
+        let switch = match flag {
+            "reproducible" => Some(&mut reproducible),
+            "verify-checksums" => Some(&mut verify_checksums),
+            "header" => Some(&mut csv.header),
+            "row-labels" => Some(&mut csv.row_labels),
+            _ => None,
+        };
+        if let Some(switch) = switch {
+            *switch = true;
+            continue;
+        }
+        let (name, value) = match flag.split_once('=') {
+            Some((name, value)) => (name, value.to_string()),
+            None => {
+                let value = iter.next().ok_or_else(|| {
+                    Error::invalid_argument("option", format!("--{} (missing value)", flag))
+                })?;
+                (flag, value.clone())
+            }
+        };
+
+        match name {
+            "threads" => threads = Some(parse_positive(name, &value)?),
+            "algorithm" => algorithm = value,
+            "crossover" => crossover = parse_positive(name, &value)?,
+            "dtype" => dtype = Dtype::parse(&value)?,
+            "alpha" => alpha = Some(value),
+            "beta" => beta = Some(value),
+            "trans-a" => trans_a = Transpose::parse(&value)?,
+            "trans-b" => trans_b = Transpose::parse(&value)?,
+            "accumulation" => accumulation = Accumulation::parse(&value)?,
+            "c" => matrix_c = Some(PathBuf::from(value)),
+            "delimiter" => csv.delimiter = Some(parse_delimiter(name, &value)?),
+            "abs-tol" => abs_tol = parse_tolerance(name, &value)?,
+            "rel-tol" => rel_tol = parse_tolerance(name, &value)?,
+            "power" => {
+                power = Some(value.parse::<u32>().map_err(|_| {
+                    Error::invalid_argument(format!("value for --{}", name), value.as_str())
+                })?)
+            }
+            _ => return Err(Error::invalid_argument("option", format!("--{}", name))),
+        }
+    }
+
+    // A power only chains plain products, so the GEMM-only options mean nothing
+    if power.is_some() {
+        let unsupported = [
+            ("alpha", alpha.is_some()),
+            ("beta", beta.is_some()),
+            ("trans-a", trans_a.is_transposed()),
+            ("trans-b", trans_b.is_transposed()),
+            ("c", matrix_c.is_some()),
+        ];
+        if let Some((name, _)) = unsupported.iter().find(|(_, given)| *given) {
+            return Err(Error::invalid_argument(
+                "option with --power",
+                format!("--{}", name),
+            ));
+        }
+    }
+
+    Ok(Options {
+        positional,
+        threads,
+        algorithm: Algorithm::parse(&algorithm, crossover)?,
+        dtype,
+        alpha,
+        beta,
+        trans_a,
+        trans_b,
+        accumulation,
+        reproducible,
+        verify_checksums,
+        matrix_c,
+        power,
+        csv,
+        abs_tol,
+        rel_tol,
+    })
+}
+
+fn parse_positive(name: &str, value: &str) -> Result<usize> {
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| n > 0)
+        .ok_or_else(|| Error::invalid_argument(format!("value for --{}", name), value))
+}
+
+/// A single character, or `tab`
+fn parse_delimiter(name: &str, value: &str) -> Result<char> {
+    let mut chars = value.chars();
+    match (value, chars.next(), chars.next()) {
+        ("tab", _, _) => Ok('\t'),
+        (_, Some(c), None) => Ok(c),
+        _ => Err(Error::invalid_argument(
+            format!("value for --{}", name),
+            value,
+        )),
+    }
+}
+
+fn parse_tolerance(name: &str, value: &str) -> Result<f64> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|&tol| tol >= 0.0)
+        .ok_or_else(|| Error::invalid_argument(format!("value for --{}", name), value))
+}
+
+fn print_usage(program: &str) {
+    eprintln!(
+        "Usage: {} [options] <matrix_a> <matrix_b> <output>",
+        program
+    );
+    eprintln!(
+        "  matrix_a: Path to first matrix file (text, CSV/TSV, binary, NumPy or Matrix Market)"
+    );
+    eprintln!(
+        "  matrix_b: Path to second matrix file (text, CSV/TSV, binary, NumPy or Matrix Market)"
+    );
+    eprintln!(
+        "  output:   Path to output matrix file (.bin, .npy, .npz, .mtx, .csv, .tsv or text)"
+    );
+    eprintln!("   or: {} [options] --power K <matrix> <output>", program);
+    eprintln!("   or: {} [options] diff <matrix> <expected>", program);
+    eprintln!("Options:");
+    eprintln!(
+        "  --threads N          Compute threads per worker (default: ${} or all cores)",
+        worker::THREADS_ENV_VAR
+    );
+    eprintln!("  --algorithm NAME     Worker algorithm: blocked (default) or strassen");
+    eprintln!(
+        "  --crossover N        Size below which strassen uses the blocked kernel (default: {})",
+        DEFAULT_STRASSEN_CROSSOVER
+    );
+    eprintln!("  --dtype TYPE         Element type: f64 (default), f32, i32, i64, c64 or c128");
+    eprintln!("  --verify-checksums   Check binary input checksums before sending any work");
+    eprintln!("CSV and TSV inputs and output:");
+    eprintln!(
+        "  --delimiter C        Field separator, a character or 'tab' (default: by extension)"
+    );
+    eprintln!("  --header             The first line names the columns");
+    eprintln!("  --row-labels         The first field of every line labels its row");
+    eprintln!("Output is alpha * op(A) * op(B) + beta * C:");
+    eprintln!("  --alpha X            Scale of the product (default: 1)");
+    eprintln!("  --beta X             Scale of C (default: 0)");
+    eprintln!("  --trans-a n|t        Use A as stored (default) or transposed");
+    eprintln!("  --trans-b n|t        Use B as stored (default) or transposed");
+    eprintln!("  --accumulation MODE  Dot-product summation: naive (default), pairwise, kahan");
+    eprintln!("                       or double-double");
+    eprintln!("  --reproducible       Same output bits for any number of workers and threads");
+    eprintln!("  --c PATH             Existing C to accumulate into (default: none)");
+    eprintln!("  --power K            Raise a single square matrix to the power K instead;");
+    eprintln!("                       --alpha, --beta, --trans-a, --trans-b and --c do not apply");
+    eprintln!("diff compares two matrix files and exits with status 1 if they differ:");
+    eprintln!(
+        "  --abs-tol X          Absolute tolerance, for values near zero (default: {:e})",
+        DEFAULT_TOLERANCE
+    );
+    eprintln!(
+        "  --rel-tol X          Tolerance relative to the larger magnitude (default: {:e})",
+        DEFAULT_TOLERANCE
+    );
+}
+
+/// Parse a GEMM scalar option for element type `T`
+fn parse_scalar<T: Element>(name: &str, value: Option<&String>, default: T) -> Result<T> {
+    match value {
+        Some(value) => T::parse_text(value)
+            .map_err(|_| Error::invalid_argument(format!("value for --{}", name), value.as_str())),
+        None => Ok(default),
+    }
+}
+
+// Process exit codes, one per class of error
+const EXIT_DIFFERENT: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+const EXIT_IO: i32 = 3;
+const EXIT_BAD_INPUT: i32 = 4;
+const EXIT_DIMENSIONS: i32 = 5;
+const EXIT_RUNTIME: i32 = 6;
+
+fn exit_code(error: &Error) -> i32 {
+    match error {
+        Error::InvalidArgument { .. } => EXIT_USAGE,
+        Error::Io { .. } => EXIT_IO,
+        Error::Parse { .. }
+        | Error::Format { .. }
+        | Error::Singular { .. }
+        | Error::NotPositiveDefinite { .. }
+        | Error::NoConvergence { .. } => EXIT_BAD_INPUT,
+        Error::DimensionMismatch { .. }
+        | Error::NotSquare { .. }
+        | Error::DataLength { .. }
+        | Error::OutOfBounds { .. }
+        | Error::ViewExtent { .. } => EXIT_DIMENSIONS,
+        Error::Mpi { .. } | Error::ThreadPool { .. } => EXIT_RUNTIME,
+    }
+}
+
+fn run_coordinator<T: Element>(
+    coordinator: &Coordinator<SimpleCommunicator>,
+    options: &Options,
+    matrix_a_path: &Path,
+    matrix_b_path: &Path,
+    output_path: &Path,
+) -> Result<()> {
+    let spec = GemmSpec::default()
+        .with_alpha(parse_scalar("alpha", options.alpha.as_ref(), T::one())?)
+        .with_beta(parse_scalar("beta", options.beta.as_ref(), T::zero())?)
+        .with_trans_a(options.trans_a)
+        .with_trans_b(options.trans_b)
+        .with_accumulation(options.accumulation)
+        .with_reproducible(options.reproducible);
+    coordinator.gemm_as::<T>(
+        &spec,
+        matrix_a_path,
+        matrix_b_path,
+        options.matrix_c.as_deref(),
+        output_path,
+    )
+}
+
+fn run_power<T: Element>(
+    coordinator: &Coordinator<SimpleCommunicator>,
+    options: &Options,
+    matrix_path: &Path,
+    k: u32,
+    output_path: &Path,
+) -> Result<()> {
+    let spec = GemmSpec::default()
+        .with_accumulation(options.accumulation)
+        .with_reproducible(options.reproducible);
+    coordinator.power_as::<T>(&spec, matrix_path, k, output_path)
+}
+
+fn run_diff<T: Approx>(
+    options: &Options,
+    matrix_path: &Path,
+    expected_path: &Path,
+) -> Result<Comparison> {
+    let matrix = Matrix::<T>::load_with(matrix_path, &options.csv)?;
+    let expected = Matrix::<T>::load_with(expected_path, &options.csv)?;
+    matrix.compare(&expected, options.abs_tol, options.rel_tol)
+}
+
+fn run_worker<T: Element>(worker: &Worker<SimpleCommunicator>) -> Result<()> {
+    worker.process_work_as::<T>()
+}
 
 fn main() {
     let universe = mpi::initialize().expect("Failed to initialize MPI");
@@ -16,37 +343,135 @@ fn main() {
         eprintln!("Usage: <matrix_a> <matrix_b> <output>");
         std::process::exit(1);
     }
-    
+
+    let args: Vec<String> = env::args().collect();
+    let options = match parse_args(&args[1..]) {
+        Ok(options) => options,
+        Err(e) => {
+            if rank == 0 {
+                eprintln!("Error: {}", e);
+                print_usage(&args[0]);
+            }
+            std::process::exit(exit_code(&e));
+        }
+    };
+
+    // Comparing files needs no workers; any extra ranks just exit
+    if options
+        .positional
+        .first()
+        .is_some_and(|mode| mode == "diff")
+    {
+        if rank == 0 {
+            if options.positional.len() != 3 {
+                print_usage(&args[0]);
+                std::process::exit(EXIT_USAGE);
+            }
+            let matrix_path = PathBuf::from(&options.positional[1]);
+            let expected_path = PathBuf::from(&options.positional[2]);
+            let code = match with_dtype!(
+                options.dtype,
+                run_diff(&options, &matrix_path, &expected_path)
+            ) {
+                Ok(comparison) => {
+                    println!("{}", comparison);
+                    if comparison.passed() {
+                        0
+                    } else {
+                        EXIT_DIFFERENT
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    exit_code(&e)
+                }
+            };
+            drop(universe);
+            std::process::exit(code);
+        }
+        return;
+    }
+
     if rank == 0 {
-        let args: Vec<String> = env::args().collect();
-        if args.len() != 4 {
-            eprintln!("Usage: {} <matrix_a> <matrix_b> <output>", args[0]);
-            eprintln!("  matrix_a: Path to first matrix file (text format)");
-            eprintln!("  matrix_b: Path to second matrix file (text format)");
-            eprintln!("  output:   Path to output matrix file (text format)");
-            std::process::exit(1);
+        if let Some(k) = options.power {
+            if options.positional.len() != 2 {
+                print_usage(&args[0]);
+                std::process::exit(EXIT_USAGE);
+            }
+
+            let matrix_path = PathBuf::from(&options.positional[0]);
+            let output_path = PathBuf::from(&options.positional[1]);
+
+            println!("[Coordinator] Starting with {} workers", size - 1);
+            println!("[Coordinator] Matrix: {:?}", matrix_path);
+            println!("[Coordinator] Exponent: {}", k);
+            println!("[Coordinator] Output: {:?}", output_path);
+            println!("[Coordinator] Element type: {}", options.dtype);
+            println!("[Coordinator] Accumulation: {}", options.accumulation);
+            if options.reproducible {
+                println!("[Coordinator] Reproducible mode");
+            }
+
+            let coordinator = Coordinator::new(world).with_csv(options.csv);
+            let result = with_dtype!(
+                options.dtype,
+                run_power(&coordinator, &options, &matrix_path, k, &output_path)
+            );
+            if let Err(e) = result {
+                eprintln!("[Coordinator] Error: {}", e);
+                std::process::exit(exit_code(&e));
+            }
+            return;
+        }
+
+        if options.positional.len() != 3 {
+            print_usage(&args[0]);
+            std::process::exit(EXIT_USAGE);
         }
 
-        let matrix_a_path = PathBuf::from(&args[1]);
-        let matrix_b_path = PathBuf::from(&args[2]);
-        let output_path = PathBuf::from(&args[3]);
+        let matrix_a_path = PathBuf::from(&options.positional[0]);
+        let matrix_b_path = PathBuf::from(&options.positional[1]);
+        let output_path = PathBuf::from(&options.positional[2]);
 
         println!("[Coordinator] Starting with {} workers", size - 1);
         println!("[Coordinator] Matrix A: {:?}", matrix_a_path);
         println!("[Coordinator] Matrix B: {:?}", matrix_b_path);
+        if let Some(matrix_c_path) = &options.matrix_c {
+            println!("[Coordinator] Matrix C: {:?}", matrix_c_path);
+        }
         println!("[Coordinator] Output: {:?}", output_path);
+        println!("[Coordinator] Element type: {}", options.dtype);
+        println!("[Coordinator] Accumulation: {}", options.accumulation);
+        if options.reproducible {
+            println!("[Coordinator] Reproducible mode");
+        }
 
-        let coordinator = Coordinator::new(world);
-        if let Err(e) = coordinator.multiply_matrices(&matrix_a_path, &matrix_b_path, &output_path) {
+        let coordinator = Coordinator::new(world)
+            .with_csv(options.csv)
+            .with_checksum_verification(options.verify_checksums);
+        let result = with_dtype!(
+            options.dtype,
+            run_coordinator(
+                &coordinator,
+                &options,
+                &matrix_a_path,
+                &matrix_b_path,
+                &output_path
+            )
+        );
+        if let Err(e) = result {
             eprintln!("[Coordinator] Error: {}", e);
-            std::process::exit(1);
+            std::process::exit(exit_code(&e));
         }
     } else {
         // Worker process (including rank 0 in worker-only mode)
-        let worker = Worker::new(world);
-        if let Err(e) = worker.process_work() {
+        let mut worker = Worker::new(world).with_algorithm(options.algorithm);
+        if let Some(threads) = options.threads {
+            worker = worker.with_threads(threads);
+        }
+        if let Err(e) = with_dtype!(options.dtype, run_worker(&worker)) {
             eprintln!("[Worker {}] Error: {}", worker.rank(), e);
-            std::process::exit(1);
+            std::process::exit(exit_code(&e));
         }
     }
 }
@@ -0,0 +1,173 @@
+//! Memory-mapped binary matrix files
+//!
+//! A [`MappedMatrix`] maps a file in the [binary format](crate::binary)
+//! instead of reading it, so opening a matrix of any size only checks its
+//! header and length. Elements are decoded, in the file's byte order, when a
+//! block of them is copied out, or borrowed in place through
+//! [`MappedMatrix::view`] when the file is in native byte order; the rest of
+//! the file stays on disk or in the page cache, so matrices larger than
+//! memory can be handed out chunk by chunk.
+
+use crate::binary::{format_error, Crc32, Header};
+use crate::element::Element;
+use crate::error::{Error, Result};
+use crate::matrix::Matrix;
+use crate::view::MatrixView;
+use memmap2::Mmap;
+use std::fs::File;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+/// Read-only matrix backed by a memory-mapped binary file
+#[derive(Debug)]
+pub struct MappedMatrix<T = f64> {
+    map: Mmap,
+    header: Header,
+    /// File the map was made from, for error messages
+    path: PathBuf,
+    element: PhantomData<T>,
+}
+
+impl<T: Element> MappedMatrix<T> {
+    /// Map a file in the binary format
+    ///
+    /// The checksum, if any, is not verified here since that reads the whole
+    /// file; see [`MappedMatrix::verify_checksum`]. The file must not be
+    /// modified while it is mapped.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| Error::io(Some(path), e))?;
+        // SAFETY: the map is only ever read, and the file is documented to
+        // stay unmodified while mapped
+        let map = unsafe { Mmap::map(&file) }.map_err(|e| Error::io(Some(path), e))?;
+        MappedMatrix::from_map(map, path).map_err(|e| e.at(path, None))
+    }
+
+    fn from_map(map: Mmap, path: &Path) -> Result<Self> {
+        let header = map
+            .get(..Header::SIZE)
+            .ok_or_else(|| format_error("File is truncated"))?;
+        let header = Header::decode(header.try_into().expect("header-sized slice"))?;
+        if header.dtype != T::DTYPE {
+            return Err(format_error(format!(
+                "File holds {} elements, but {} was requested",
+                header.dtype,
+                T::DTYPE
+            )));
+        }
+        let expected = Header::SIZE + header.data_len()? + 4 * header.checksum as usize;
+        if map.len() < expected {
+            return Err(format_error("File is truncated"));
+        }
+        if map.len() > expected {
+            return Err(format_error("Unexpected data after the matrix"));
+        }
+        Ok(MappedMatrix {
+            map,
+            header,
+            path: path.to_path_buf(),
+            element: PhantomData,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    pub fn rows(&self) -> usize {
+        self.header.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.header.cols
+    }
+
+    /// Get a value at a specific position
+    pub fn get(&self, row: usize, col: usize) -> Result<T> {
+        Ok(self.block(row, col, 1, 1)?.data[0])
+    }
+
+    /// Copy the `num_rows x num_cols` block starting at `(row, col)` into a
+    /// new matrix
+    pub fn block(
+        &self,
+        row: usize,
+        col: usize,
+        num_rows: usize,
+        num_cols: usize,
+    ) -> Result<Matrix<T>> {
+        let (rows, cols) = (self.rows(), self.cols());
+        if row + num_rows > rows || col + num_cols > cols {
+            return Err(Error::OutOfBounds {
+                start: (row, col),
+                size: (num_rows, num_cols),
+                dims: (rows, cols),
+            });
+        }
+        let size = T::DTYPE.size();
+        let mut data = Vec::with_capacity(num_rows * num_cols);
+        for i in row..row + num_rows {
+            let start = Header::SIZE + (i * cols + col) * size;
+            let bytes = &self.map[start..start + num_cols * size];
+            data.extend(
+                bytes
+                    .chunks_exact(size)
+                    .map(|element| T::read(element, self.header.big_endian)),
+            );
+        }
+        Matrix::from_vec(data, num_rows, num_cols)
+    }
+
+    /// Copy rows `[start_row, start_row + num_rows)` into a new matrix
+    pub fn row_chunk(&self, start_row: usize, num_rows: usize) -> Result<Matrix<T>> {
+        self.block(start_row, 0, num_rows, self.cols())
+    }
+
+    /// Copy columns `[start_col, start_col + num_cols)` into a new matrix
+    pub fn col_chunk(&self, start_col: usize, num_cols: usize) -> Result<Matrix<T>> {
+        self.block(0, start_col, self.rows(), num_cols)
+    }
+
+    /// Borrow the elements in place, which needs the file to be in native
+    /// byte order; `None` means blocks have to be copied out instead
+    pub fn view(&self) -> Option<MatrixView<'_, T>> {
+        if self.header.big_endian != cfg!(target_endian = "big") {
+            return None;
+        }
+        let end = Header::SIZE + self.rows() * self.cols() * T::DTYPE.size();
+        let data = T::view_ne(&self.map[Header::SIZE..end])?;
+        MatrixView::row_major(data, self.rows(), self.cols()).ok()
+    }
+
+    /// Copy the whole matrix into memory
+    pub fn to_matrix(&self) -> Matrix<T> {
+        self.row_chunk(0, self.rows())
+            .expect("the whole matrix is in bounds")
+    }
+
+    /// Check the element data against the stored checksum, if there is one
+    pub fn verify_checksum(&self) -> Result<()> {
+        if !self.header.checksum {
+            return Ok(());
+        }
+        let end = self.map.len() - 4;
+        let stored: [u8; 4] = self.map[end..].try_into().expect("four checksum bytes");
+        let stored = if self.header.big_endian {
+            u32::from_be_bytes(stored)
+        } else {
+            u32::from_le_bytes(stored)
+        };
+        let mut crc = Crc32::new();
+        crc.update(&self.map[Header::SIZE..end]);
+        if crc.finish() != stored {
+            return Err(
+                format_error("Checksum mismatch: the element data is corrupt").at(&self.path, None),
+            );
+        }
+        Ok(())
+    }
+}
@@ -1,63 +1,131 @@
+use crate::accumulate::{self, Accumulation};
+use crate::binary;
+use crate::csv::{self, CsvOverrides};
+use crate::element::Element;
+use crate::error::{Error, Result};
+use crate::kernel::{self, Algorithm, GemmSpec, TileConfig};
+use crate::matrix_market::{self, Layout, Symmetry};
+use crate::npy;
+use crate::strassen::Strassen;
+use crate::transpose;
+use crate::view::{MatrixView, MatrixViewMut};
+use rayon::ThreadPool;
 use std::fs::File;
-use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::io::{BufRead, BufReader};
 use std::path::Path;
 
-#[derive(Debug, Clone)]
-pub struct Matrix {
-    pub data: Vec<f64>,
+/// Dense row-major matrix of `T` values (`f64` unless stated otherwise)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<T = f64> {
+    pub data: Vec<T>,
     pub rows: usize,
     pub cols: usize,
 }
 
-impl Matrix {
+impl<T: Element> Matrix<T> {
     /// Create a new matrix with the given dimensions
     pub fn new(rows: usize, cols: usize) -> Self {
         Matrix {
-            data: vec![0.0; rows * cols],
+            data: vec![T::zero(); rows * cols],
             rows,
             cols,
         }
     }
 
+    /// Create the `n x n` identity matrix
+    pub fn identity(n: usize) -> Self {
+        let mut matrix = Matrix::new(n, n);
+        for i in 0..n {
+            matrix.data[i * n + i] = T::one();
+        }
+        matrix
+    }
+
     /// Create a matrix from a vector of data
-    pub fn from_vec(data: Vec<f64>, rows: usize, cols: usize) -> Result<Self, String> {
+    pub fn from_vec(data: Vec<T>, rows: usize, cols: usize) -> Result<Self> {
         if data.len() != rows * cols {
-            return Err(format!(
-                "Data length {} does not match dimensions {}x{}",
-                data.len(),
+            return Err(Error::DataLength {
+                len: data.len(),
                 rows,
-                cols
-            ));
+                cols,
+            });
         }
         Ok(Matrix { data, rows, cols })
     }
 
+    /// Load a matrix file in any supported format, recognized by its content
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Matrix::load_with(path, &CsvOverrides::default())
+    }
+
+    /// [`load`](Matrix::load), reading CSV and TSV files with the layout `csv`
+    /// sets
+    pub fn load_with<P: AsRef<Path>>(path: P, csv: &CsvOverrides) -> Result<Self> {
+        if binary::is_binary_file(&path)? {
+            Matrix::load_binary(path)
+        } else if npy::is_npy_file(&path)? {
+            Matrix::load_npy(path)
+        } else if npy::is_npz_file(&path)? {
+            Matrix::load_npz_single(path)
+        } else if matrix_market::is_matrix_market_file(&path)?
+            || has_extension(path.as_ref(), matrix_market::EXTENSION)
+        {
+            Matrix::load_matrix_market(path)
+        } else if csv::is_delimited_extension(&path) {
+            Matrix::load_csv(&path, &csv.options_for(&path))
+        } else {
+            Matrix::load_from_file(path)
+        }
+    }
+
+    /// Save a matrix in the format its file extension asks for: binary for
+    /// `.bin` (with a checksum), NumPy for `.npy` and `.npz`, a general Matrix
+    /// Market array for `.mtx`, CSV for `.csv`, TSV for `.tsv` and `.tab`,
+    /// text otherwise
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.save_with(path, &CsvOverrides::default())
+    }
+
+    /// [`save`](Matrix::save), writing CSV and TSV files with the layout `csv`
+    /// sets
+    pub fn save_with<P: AsRef<Path>>(&self, path: P, csv: &CsvOverrides) -> Result<()> {
+        let extension = path.as_ref().extension().and_then(|e| e.to_str());
+        match extension {
+            Some(binary::EXTENSION) => self.save_binary(path, true),
+            Some(npy::EXTENSION) => self.save_npy(path, false),
+            Some(npy::NPZ_EXTENSION) => npy::save_npz(path, &[(npy::DEFAULT_NAME, self)], false),
+            Some(matrix_market::EXTENSION) => {
+                self.save_matrix_market(path, Layout::Array, Symmetry::General)
+            }
+            _ if csv::is_delimited_extension(&path) => {
+                self.save_csv(&path, &csv.options_for(&path))
+            }
+            _ => self.save_to_file(path),
+        }
+    }
+
     /// Load a matrix from a text file
     /// Format: space-separated values, one row per line
-    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
-        let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| Error::io(Some(path), e))?;
         let reader = BufReader::new(file);
         let mut rows = Vec::new();
         let mut num_cols = None;
 
         for (line_num, line) in reader.lines().enumerate() {
-            let line = line.map_err(|e| format!("Failed to read line {}: {}", line_num + 1, e))?;
+            let line = line.map_err(|e| Error::io(Some(path), e))?;
             let trimmed = line.trim();
             if trimmed.is_empty() {
                 continue; // Skip empty lines
             }
 
-            let values: Result<Vec<f64>, _> = trimmed
+            let values: Result<Vec<T>> = trimmed
                 .split_whitespace()
-                .map(|s| s.parse::<f64>())
+                .map(|s| T::parse_text(s))
                 .collect();
 
-            let values = values.map_err(|e| {
-                format!(
-                    "Failed to parse value on line {}: {}",
-                    line_num + 1, e
-                )
-            })?;
+            let values = values.map_err(|e| e.at(path, Some(line_num + 1)))?;
 
             if values.is_empty() {
                 continue; // Skip lines with no values
@@ -66,12 +134,16 @@ impl Matrix {
             // Check that all rows have the same number of columns
             match num_cols {
                 Some(n) if n != values.len() => {
-                    return Err(format!(
-                        "Inconsistent column count: expected {}, found {} on line {}",
-                        n,
-                        values.len(),
-                        line_num + 1
-                    ));
+                    return Err(Error::Format {
+                        path: Some(path.to_path_buf()),
+                        line: Some(line_num + 1),
+                        column: None,
+                        message: format!(
+                            "Inconsistent column count: expected {}, found {}",
+                            n,
+                            values.len()
+                        ),
+                    });
                 }
                 None => num_cols = Some(values.len()),
                 _ => {}
@@ -80,13 +152,16 @@ impl Matrix {
             rows.push(values);
         }
 
-        if rows.is_empty() {
-            return Err("Matrix file is empty".to_string());
-        }
-
-        let cols = num_cols.ok_or("No valid rows found")?;
+        let Some(cols) = num_cols else {
+            return Err(Error::Format {
+                path: Some(path.to_path_buf()),
+                line: None,
+                column: None,
+                message: "Matrix file is empty".to_string(),
+            });
+        };
         let rows_count = rows.len();
-        let data: Vec<f64> = rows.into_iter().flatten().collect();
+        let data: Vec<T> = rows.into_iter().flatten().collect();
 
         Ok(Matrix {
             data,
@@ -97,83 +172,101 @@ impl Matrix {
 
     /// Save a matrix to a text file
     /// Format: space-separated values, one row per line
-    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
-        let file = File::create(path).map_err(|e| format!("Failed to create file: {}", e))?;
-        let mut writer = BufWriter::new(file);
-
-        for i in 0..self.rows {
-            let row_start = i * self.cols;
-            let row_end = row_start + self.cols;
-            let row = &self.data[row_start..row_end];
-
-            // Write row values separated by spaces
-            for (j, &value) in row.iter().enumerate() {
-                if j > 0 {
-                    write!(writer, " ").map_err(|e| format!("Failed to write: {}", e))?;
-                }
-                write!(writer, "{}", value).map_err(|e| format!("Failed to write: {}", e))?;
-            }
-            writeln!(writer).map_err(|e| format!("Failed to write newline: {}", e))?;
-        }
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.view().save_to_file(path)
+    }
+
+    /// Borrow the whole matrix as a view
+    pub fn view(&self) -> MatrixView<'_, T> {
+        MatrixView::new(&self.data, self.rows, self.cols, self.cols, 1)
+            .expect("matrix data matches its dimensions")
+    }
 
-        writer
-            .flush()
-            .map_err(|e| format!("Failed to flush file: {}", e))?;
+    /// Mutably borrow the whole matrix as a view
+    pub fn view_mut(&mut self) -> MatrixViewMut<'_, T> {
+        MatrixViewMut::new(&mut self.data, self.rows, self.cols, self.cols, 1)
+            .expect("matrix data matches its dimensions")
+    }
+
+    /// Borrow the `num_rows x num_cols` block starting at `(row, col)`
+    pub fn block(
+        &self,
+        row: usize,
+        col: usize,
+        num_rows: usize,
+        num_cols: usize,
+    ) -> Result<MatrixView<'_, T>> {
+        self.view().block(row, col, num_rows, num_cols)
+    }
 
+    /// Mutably borrow the `num_rows x num_cols` block starting at `(row, col)`
+    pub fn block_mut(
+        &mut self,
+        row: usize,
+        col: usize,
+        num_rows: usize,
+        num_cols: usize,
+    ) -> Result<MatrixViewMut<'_, T>> {
+        self.check_bounds(row, col, num_rows, num_cols)?;
+        let cols = self.cols;
+        let offset = (row * cols + col).min(self.data.len());
+        MatrixViewMut::new(&mut self.data[offset..], num_rows, num_cols, cols, 1)
+    }
+
+    /// Check that the `num_rows x num_cols` block at `(row, col)` lies inside the matrix
+    fn check_bounds(&self, row: usize, col: usize, num_rows: usize, num_cols: usize) -> Result<()> {
+        if row + num_rows > self.rows || col + num_cols > self.cols {
+            return Err(Error::OutOfBounds {
+                start: (row, col),
+                size: (num_rows, num_cols),
+                dims: (self.rows, self.cols),
+            });
+        }
         Ok(())
     }
 
     /// Get a value at a specific position
-    pub fn get(&self, row: usize, col: usize) -> Result<f64, String> {
-        if row >= self.rows || col >= self.cols {
-            return Err(format!(
-                "Index out of bounds: ({}, {}) for matrix {}x{}",
-                row, col, self.rows, self.cols
-            ));
-        }
+    pub fn get(&self, row: usize, col: usize) -> Result<T> {
+        self.check_bounds(row, col, 1, 1)?;
         Ok(self.data[row * self.cols + col])
     }
 
     /// Set a value at a specific position
-    pub fn set(&mut self, row: usize, col: usize, value: f64) -> Result<(), String> {
-        if row >= self.rows || col >= self.cols {
-            return Err(format!(
-                "Index out of bounds: ({}, {}) for matrix {}x{}",
-                row, col, self.rows, self.cols
-            ));
-        }
+    pub fn set(&mut self, row: usize, col: usize, value: T) -> Result<()> {
+        self.check_bounds(row, col, 1, 1)?;
         self.data[row * self.cols + col] = value;
         Ok(())
     }
 
     /// Get a row as a slice
-    pub fn get_row(&self, row: usize) -> Result<&[f64], String> {
-        if row >= self.rows {
-            return Err(format!("Row index {} out of bounds for {} rows", row, self.rows));
-        }
+    pub fn get_row(&self, row: usize) -> Result<&[T]> {
+        self.check_bounds(row, 0, 1, self.cols)?;
         let start = row * self.cols;
         let end = start + self.cols;
         Ok(&self.data[start..end])
     }
 
     /// Get a column as a vector
-    pub fn get_col(&self, col: usize) -> Result<Vec<f64>, String> {
-        if col >= self.cols {
-            return Err(format!("Column index {} out of bounds for {} cols", col, self.cols));
-        }
+    pub fn get_col(&self, col: usize) -> Result<Vec<T>> {
+        self.check_bounds(0, col, self.rows, 1)?;
         Ok((0..self.rows)
             .map(|row| self.data[row * self.cols + col])
             .collect())
     }
 
+    /// Borrow rows `[start_row, start_row + num_rows)` without copying
+    pub fn row_chunk(&self, start_row: usize, num_rows: usize) -> Result<MatrixView<'_, T>> {
+        self.view().row_chunk(start_row, num_rows)
+    }
+
+    /// Borrow columns `[start_col, start_col + num_cols)` without copying
+    pub fn col_chunk(&self, start_col: usize, num_cols: usize) -> Result<MatrixView<'_, T>> {
+        self.view().col_chunk(start_col, num_cols)
+    }
+
     /// Get a submatrix (row chunk)
-    pub fn get_row_chunk(&self, start_row: usize, num_rows: usize) -> Result<Matrix, String> {
-        if start_row + num_rows > self.rows {
-            return Err(format!(
-                "Row chunk out of bounds: start={}, num_rows={}, total_rows={}",
-                start_row, num_rows, self.rows
-            ));
-        }
+    pub fn get_row_chunk(&self, start_row: usize, num_rows: usize) -> Result<Matrix<T>> {
+        self.check_bounds(start_row, 0, num_rows, self.cols)?;
 
         let mut chunk_data = Vec::with_capacity(num_rows * self.cols);
         for row in start_row..start_row + num_rows {
@@ -189,77 +282,284 @@ impl Matrix {
     }
 
     /// Get a submatrix (column chunk)
-    pub fn get_col_chunk(&self, start_col: usize, num_cols: usize) -> Result<Matrix, String> {
-        if start_col + num_cols > self.cols {
-            return Err(format!(
-                "Column chunk out of bounds: start={}, num_cols={}, total_cols={}",
-                start_col, num_cols, self.cols
-            ));
-        }
+    pub fn get_col_chunk(&self, start_col: usize, num_cols: usize) -> Result<Matrix<T>> {
+        Ok(self.col_chunk(start_col, num_cols)?.to_matrix())
+    }
 
-        let mut chunk_data = Vec::with_capacity(self.rows * num_cols);
-        for row in 0..self.rows {
-            for col in start_col..start_col + num_cols {
-                chunk_data.push(self.data[row * self.cols + col]);
-            }
-        }
+    /// Return the transpose as a new matrix, copied tile by tile
+    pub fn transpose(&self) -> Matrix<T> {
+        let mut result = Matrix::new(self.cols, self.rows);
+        transpose::transpose_into(self.view(), &mut result.data);
+        result
+    }
 
-        Ok(Matrix {
-            data: chunk_data,
-            rows: self.rows,
-            cols: num_cols,
-        })
+    /// Transpose in place without allocating a second buffer
+    ///
+    /// Square matrices swap mirrored tiles; rectangular ones are permuted
+    /// cycle by cycle, at the cost of one bit of bookkeeping per element.
+    pub fn transpose_in_place(&mut self) {
+        transpose::transpose_in_place(&mut self.data, self.rows, self.cols);
+        std::mem::swap(&mut self.rows, &mut self.cols);
     }
 
     /// Multiply two matrices (A * B)
     /// Returns a new matrix C where C[i][j] = sum(A[i][k] * B[k][j])
-    pub fn multiply(&self, other: &Matrix) -> Result<Matrix, String> {
-        if self.cols != other.rows {
-            return Err(format!(
-                "Matrix dimensions incompatible: {}x{} * {}x{}",
-                self.rows, self.cols, other.rows, other.cols
-            ));
-        }
+    pub fn multiply(&self, other: &Matrix<T>) -> Result<Matrix<T>> {
+        self.multiply_with_tiles(other, &TileConfig::default())
+    }
 
-        let mut result = Matrix::new(self.rows, other.cols);
+    /// Multiply two matrices (A * B) using the given tile sizes for the blocked kernel
+    pub fn multiply_with_tiles(&self, other: &Matrix<T>, tiles: &TileConfig) -> Result<Matrix<T>> {
+        Matrix::multiply_chunks_with_tiles(self, other, tiles)
+    }
 
-        for i in 0..self.rows {
-            for j in 0..other.cols {
-                let mut sum = 0.0;
-                for k in 0..self.cols {
-                    sum += self.data[i * self.cols + k] * other.data[k * other.cols + j];
-                }
-                result.data[i * other.cols + j] = sum;
-            }
+    /// Raise a square matrix to the power `k` by repeated squaring
+    ///
+    /// Takes about `2 log2(k)` multiplications; `A^0` is the identity.
+    pub fn pow(&self, k: u32) -> Result<Matrix<T>> {
+        if self.rows != self.cols {
+            return Err(Error::NotSquare {
+                operation: "matrix power",
+                rows: self.rows,
+                cols: self.cols,
+            });
+        }
+        if k == 0 {
+            return Ok(Matrix::identity(self.rows));
         }
 
+        // Left to right over the bits of k below the leading one
+        let mut result = self.clone();
+        for bit in (0..u32::BITS - 1 - k.leading_zeros()).rev() {
+            result = result.multiply(&result)?;
+            if k >> bit & 1 == 1 {
+                result = result.multiply(self)?;
+            }
+        }
         Ok(result)
     }
 
     /// Multiply a row chunk with a column chunk
-    /// Used for distributed multiplication
-    pub fn multiply_chunks(row_chunk: &Matrix, col_chunk: &Matrix) -> Result<Matrix, String> {
-        if row_chunk.cols != col_chunk.rows {
-            return Err(format!(
-                "Chunk dimensions incompatible: {}x{} * {}x{}",
-                row_chunk.rows, row_chunk.cols, col_chunk.rows, col_chunk.cols
-            ));
-        }
+    /// Used for distributed multiplication; either operand may be a matrix or a view
+    pub fn multiply_chunks<'a>(
+        row_chunk: impl Into<MatrixView<'a, T>>,
+        col_chunk: impl Into<MatrixView<'a, T>>,
+    ) -> Result<Matrix<T>> {
+        Matrix::multiply_chunks_with_tiles(row_chunk, col_chunk, &TileConfig::default())
+    }
+
+    /// Multiply a row chunk with a column chunk using the given tile sizes
+    pub fn multiply_chunks_with_tiles<'a>(
+        row_chunk: impl Into<MatrixView<'a, T>>,
+        col_chunk: impl Into<MatrixView<'a, T>>,
+        tiles: &TileConfig,
+    ) -> Result<Matrix<T>> {
+        let (a, b) = chunk_operands(row_chunk.into(), col_chunk.into())?;
+
+        let mut result = Matrix::new(a.rows(), b.cols());
+        kernel::gemm(
+            a.rows(),
+            b.cols(),
+            a.cols(),
+            T::one(),
+            a.strided(),
+            b.strided(),
+            &mut result.data,
+            b.cols(),
+            tiles,
+        );
+
+        Ok(result)
+    }
+
+    /// Multiply a row chunk with a column chunk, splitting the rows of the
+    /// chunk across the threads of `pool`
+    pub fn multiply_chunks_parallel<'a>(
+        row_chunk: impl Into<MatrixView<'a, T>>,
+        col_chunk: impl Into<MatrixView<'a, T>>,
+        pool: &ThreadPool,
+    ) -> Result<Matrix<T>> {
+        Matrix::multiply_chunks_with_algorithm(row_chunk, col_chunk, Algorithm::Blocked, Some(pool))
+    }
 
-        let mut result = Matrix::new(row_chunk.rows, col_chunk.cols);
+    /// Multiply two matrices (A * B) with Strassen-Winograd recursion down to
+    /// `crossover`, below which the blocked kernel takes over
+    pub fn multiply_strassen(&self, other: &Matrix<T>, crossover: usize) -> Result<Matrix<T>> {
+        Matrix::multiply_chunks_with_algorithm(self, other, Algorithm::Strassen { crossover }, None)
+    }
+
+    /// Multiply a row chunk with a column chunk using `algorithm`, running the
+    /// blocked kernel on `pool` if one is given
+    pub fn multiply_chunks_with_algorithm<'a>(
+        row_chunk: impl Into<MatrixView<'a, T>>,
+        col_chunk: impl Into<MatrixView<'a, T>>,
+        algorithm: Algorithm,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Matrix<T>> {
+        let (a, b) = chunk_operands(row_chunk.into(), col_chunk.into())?;
+        let mut result = Matrix::new(a.rows(), b.cols());
+        Matrix::gemm_with_algorithm(
+            &GemmSpec::default(),
+            a,
+            b,
+            &mut result.view_mut(),
+            algorithm,
+            pool,
+        )?;
+        Ok(result)
+    }
+
+    /// Compute `alpha * op(A) * op(B) + beta * C` as a new matrix
+    ///
+    /// If `c` is given it is updated in place and returned; otherwise C is
+    /// taken to be zero and `beta` has no effect.
+    pub fn gemm<'a>(
+        spec: &GemmSpec<T>,
+        a: impl Into<MatrixView<'a, T>>,
+        b: impl Into<MatrixView<'a, T>>,
+        c: Option<Matrix<T>>,
+    ) -> Result<Matrix<T>> {
+        let (a, b) = (a.into(), b.into());
+        let mut c = match c {
+            Some(c) => c,
+            None => Matrix::new(spec.trans_a.apply(a).rows(), spec.trans_b.apply(b).cols()),
+        };
+        Matrix::gemm_into(spec, a, b, &mut c.view_mut())?;
+        Ok(c)
+    }
 
-        for i in 0..row_chunk.rows {
-            for j in 0..col_chunk.cols {
-                let mut sum = 0.0;
-                for k in 0..row_chunk.cols {
-                    sum += row_chunk.data[i * row_chunk.cols + k]
-                        * col_chunk.data[k * col_chunk.cols + j];
+    /// Compute `C = alpha * op(A) * op(B) + beta * C` in place
+    pub fn gemm_into<'a>(
+        spec: &GemmSpec<T>,
+        a: impl Into<MatrixView<'a, T>>,
+        b: impl Into<MatrixView<'a, T>>,
+        c: &mut MatrixViewMut<'_, T>,
+    ) -> Result<()> {
+        Matrix::gemm_with_algorithm(spec, a, b, c, Algorithm::Blocked, None)
+    }
+
+    /// Compute `C = alpha * op(A) * op(B) + beta * C` in place using
+    /// `algorithm`, running the blocked kernel on `pool` if one is given
+    ///
+    /// Transposes are applied through the operands' strides and alpha while
+    /// packing, so neither costs an extra copy or pass. Reproducible specs and
+    /// accumulation modes other than naive replace `algorithm` with a
+    /// dot-product kernel.
+    pub fn gemm_with_algorithm<'a>(
+        spec: &GemmSpec<T>,
+        a: impl Into<MatrixView<'a, T>>,
+        b: impl Into<MatrixView<'a, T>>,
+        c: &mut MatrixViewMut<'_, T>,
+        algorithm: Algorithm,
+        pool: Option<&ThreadPool>,
+    ) -> Result<()> {
+        let a = spec.trans_a.apply(a.into());
+        let b = spec.trans_b.apply(b.into());
+        let (a, b) = chunk_operands(a, b)?;
+        if a.rows() != c.rows() || b.cols() != c.cols() {
+            return Err(Error::DimensionMismatch {
+                operation: "accumulate",
+                left: (a.rows(), b.cols()),
+                right: (c.rows(), c.cols()),
+            });
+        }
+
+        c.scale(spec.beta);
+        let (m, k, n) = (a.rows(), a.cols(), b.cols());
+        match c.as_row_major_mut() {
+            Some((c_data, ldc)) => match algorithm {
+                _ if spec.reproducible || spec.accumulation != Accumulation::Naive => {
+                    accumulate::gemm(
+                        spec.accumulation,
+                        m,
+                        n,
+                        k,
+                        spec.alpha,
+                        a.strided(),
+                        b.strided(),
+                        c_data,
+                        ldc,
+                        pool,
+                    )
+                }
+                Algorithm::Blocked => match pool {
+                    Some(pool) => kernel::gemm_parallel(
+                        m,
+                        n,
+                        k,
+                        spec.alpha,
+                        a.strided(),
+                        b.strided(),
+                        c_data,
+                        ldc,
+                        &TileConfig::default(),
+                        pool,
+                    ),
+                    None => kernel::gemm(
+                        m,
+                        n,
+                        k,
+                        spec.alpha,
+                        a.strided(),
+                        b.strided(),
+                        c_data,
+                        ldc,
+                        &TileConfig::default(),
+                    ),
+                },
+                Algorithm::Strassen { crossover } => {
+                    let strassen = Strassen {
+                        crossover: crossover.max(1),
+                        tiles: TileConfig::default(),
+                        pool,
+                    };
+                    strassen.multiply(m, k, n, spec.alpha, a.strided(), b.strided(), c_data, ldc);
+                }
+            },
+            None => {
+                // The kernels need unit-stride rows of C: form the product
+                // densely and add it in
+                let product_spec = GemmSpec::default()
+                    .with_alpha(spec.alpha)
+                    .with_accumulation(spec.accumulation)
+                    .with_reproducible(spec.reproducible);
+                let mut product = Matrix::new(m, n);
+                let mut product_view = product.view_mut();
+                Matrix::gemm_with_algorithm(
+                    &product_spec,
+                    a,
+                    b,
+                    &mut product_view,
+                    algorithm,
+                    pool,
+                )?;
+                for i in 0..m {
+                    for j in 0..n {
+                        *c.at_mut(i, j) += product.data[i * n + j];
+                    }
                 }
-                result.data[i * col_chunk.cols + j] = sum;
             }
         }
 
-        Ok(result)
+        Ok(())
     }
 }
 
+/// Check that two chunks can be multiplied
+fn chunk_operands<'a, T: Element>(
+    row_chunk: MatrixView<'a, T>,
+    col_chunk: MatrixView<'a, T>,
+) -> Result<(MatrixView<'a, T>, MatrixView<'a, T>)> {
+    if row_chunk.cols() != col_chunk.rows() {
+        return Err(Error::DimensionMismatch {
+            operation: "multiply",
+            left: (row_chunk.rows(), row_chunk.cols()),
+            right: (col_chunk.rows(), col_chunk.cols()),
+        });
+    }
+    Ok((row_chunk, col_chunk))
+}
+
+pub(crate) fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some(extension)
+}
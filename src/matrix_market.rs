@@ -0,0 +1,506 @@
+//! Matrix Market (`.mtx`) files
+//!
+//! The format used by the SuiteSparse collection: a `%%MatrixMarket` banner
+//! naming the layout, field and symmetry, `%` comment lines, a size line and
+//! the entries. `array` files list every stored entry in column-major order;
+//! `coordinate` files list `row col value` triples with 1-based indices.
+//! Symmetric, skew-symmetric and Hermitian files store only the lower
+//! triangle, which is mirrored on load.
+
+use crate::binary::{self, format_error, in_file};
+use crate::element::Element;
+use crate::error::{Error, Result};
+use crate::matrix::Matrix;
+use num_complex::Complex;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// First word of every Matrix Market file, in any case
+pub const BANNER: &str = "%%MatrixMarket";
+
+/// File extension that [`Matrix::save`] writes as a Matrix Market array
+pub const EXTENSION: &str = "mtx";
+
+/// Whether the file at `path` starts with the Matrix Market banner
+pub fn is_matrix_market_file<P: AsRef<Path>>(path: P) -> Result<bool> {
+    let start = binary::file_start(path.as_ref(), BANNER.len())?;
+    Ok(start.eq_ignore_ascii_case(BANNER.as_bytes()))
+}
+
+/// How the entries are listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Every stored entry, column by column
+    Array,
+    /// Nonzero entries as `row col value`
+    Coordinate,
+}
+
+/// Type of the entries as declared in the banner
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketField {
+    Real,
+    Integer,
+    /// Real and imaginary part, separated by whitespace
+    Complex,
+    /// Coordinates only; every listed entry is one
+    Pattern,
+}
+
+/// Which part of the matrix is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    General,
+    /// `a[j][i] = a[i][j]`; only the lower triangle is stored
+    Symmetric,
+    /// `a[j][i] = -a[i][j]`; only the strictly lower triangle is stored
+    SkewSymmetric,
+    /// `a[j][i] = conj(a[i][j])`; only the lower triangle is stored
+    Hermitian,
+}
+
+macro_rules! impl_keyword {
+    ($ty:ident, $what:literal, $($variant:ident => $name:literal),*) => {
+        impl $ty {
+            /// Keyword used in the banner
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($ty::$variant => $name),*
+                }
+            }
+
+            /// Parse a banner keyword, ignoring case
+            pub fn parse(name: &str) -> Result<Self> {
+                match name.to_ascii_lowercase().as_str() {
+                    $($name => Ok($ty::$variant),)*
+                    _ => Err(Error::invalid_argument($what, name)),
+                }
+            }
+        }
+
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+    };
+}
+
+impl_keyword!(Layout, "Matrix Market layout", Array => "array", Coordinate => "coordinate");
+impl_keyword!(
+    MarketField,
+    "Matrix Market field",
+    Real => "real",
+    Integer => "integer",
+    Complex => "complex",
+    Pattern => "pattern"
+);
+impl_keyword!(
+    Symmetry,
+    "Matrix Market symmetry",
+    General => "general",
+    Symmetric => "symmetric",
+    SkewSymmetric => "skew-symmetric",
+    Hermitian => "hermitian"
+);
+
+impl MarketField {
+    /// Whitespace-separated values per entry
+    fn width(&self) -> usize {
+        match self {
+            MarketField::Pattern => 0,
+            MarketField::Real | MarketField::Integer => 1,
+            MarketField::Complex => 2,
+        }
+    }
+}
+
+impl Symmetry {
+    /// Value stored at `(j, i)` given the one at `(i, j)`
+    fn mirror<T: Element>(&self, value: T) -> T {
+        match self {
+            Symmetry::General | Symmetry::Symmetric => value,
+            Symmetry::SkewSymmetric => -value,
+            Symmetry::Hermitian => value.conj_entry(),
+        }
+    }
+
+    /// Whether `(row, col)` is stored: the lower triangle, without the
+    /// diagonal for skew-symmetric matrices
+    fn stores(&self, row: usize, col: usize) -> bool {
+        match self {
+            Symmetry::General => true,
+            Symmetry::Symmetric | Symmetry::Hermitian => row >= col,
+            Symmetry::SkewSymmetric => row > col,
+        }
+    }
+}
+
+/// How an element type is read from and written to Matrix Market files
+pub trait MarketElement: Sized {
+    /// MarketField written for this type
+    const FIELD: MarketField;
+
+    /// Parse one entry of `field` from its values
+    fn parse_entry(field: MarketField, values: &[&str]) -> Result<Self>;
+
+    /// Write the values of one entry
+    fn write_entry<W: Write>(self, writer: &mut W) -> io::Result<()>;
+
+    /// Complex conjugate; the identity for real types
+    fn conj_entry(self) -> Self;
+}
+
+macro_rules! impl_market_real {
+    ($ty:ty, $field:expr) => {
+        impl MarketElement for $ty {
+            const FIELD: MarketField = $field;
+
+            fn parse_entry(field: MarketField, values: &[&str]) -> Result<Self> {
+                match field {
+                    MarketField::Pattern => Ok(Self::one()),
+                    MarketField::Complex => Err(Error::Format {
+                        path: None,
+                        line: None,
+                        column: None,
+                        message: format!("Complex entries cannot be read as {}", Self::DTYPE),
+                    }),
+                    MarketField::Real | MarketField::Integer => Self::parse_text(values[0]),
+                }
+            }
+
+            fn write_entry<W: Write>(self, writer: &mut W) -> io::Result<()> {
+                write!(writer, "{}", self)
+            }
+
+            fn conj_entry(self) -> Self {
+                self
+            }
+        }
+    };
+}
+
+impl_market_real!(f32, MarketField::Real);
+impl_market_real!(f64, MarketField::Real);
+impl_market_real!(i32, MarketField::Integer);
+impl_market_real!(i64, MarketField::Integer);
+
+macro_rules! impl_market_complex {
+    ($ty:ty) => {
+        impl MarketElement for Complex<$ty> {
+            const FIELD: MarketField = MarketField::Complex;
+
+            fn parse_entry(field: MarketField, values: &[&str]) -> Result<Self> {
+                let part = |text: &str| text.parse::<$ty>().map_err(|e| Error::parse(text, e));
+                match field {
+                    MarketField::Pattern => Ok(Self::one()),
+                    MarketField::Real | MarketField::Integer => {
+                        Ok(Complex::new(part(values[0])?, 0.0))
+                    }
+                    MarketField::Complex => Ok(Complex::new(part(values[0])?, part(values[1])?)),
+                }
+            }
+
+            fn write_entry<W: Write>(self, writer: &mut W) -> io::Result<()> {
+                write!(writer, "{} {}", self.re, self.im)
+            }
+
+            fn conj_entry(self) -> Self {
+                self.conj()
+            }
+        }
+    };
+}
+
+impl_market_complex!(f32);
+impl_market_complex!(f64);
+
+impl<T: Element> Matrix<T> {
+    /// Load a matrix from a Matrix Market file, expanding symmetric storage
+    pub fn load_matrix_market<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| Error::io(Some(path), e))?;
+        Matrix::read_matrix_market(BufReader::new(file)).map_err(|e| in_file(e, path))
+    }
+
+    /// Read a matrix in the Matrix Market format
+    pub fn read_matrix_market<R: BufRead>(reader: R) -> Result<Self> {
+        let mut lines = reader.lines().enumerate().map(|(index, line)| {
+            line.map(|line| (index + 1, line))
+                .map_err(|e| Error::io(None, e))
+        });
+
+        let (_, banner) = lines
+            .next()
+            .transpose()?
+            .ok_or_else(|| format_error("File is empty"))?;
+        let (layout, field, symmetry) = parse_banner(&banner)?;
+
+        // Comments and blank lines may appear anywhere after the banner
+        let mut data_lines = lines.filter(|line| {
+            !matches!(line, Ok((_, text)) if text.trim().is_empty() || text.starts_with('%'))
+        });
+
+        let (size_line, size) = data_lines
+            .next()
+            .transpose()?
+            .ok_or_else(|| format_error("Missing size line"))?;
+        let size = parse_indices(&size, size_line)?;
+        let (rows, cols, count) = match (layout, size.as_slice()) {
+            (Layout::Array, &[rows, cols]) => (rows, cols, None),
+            (Layout::Coordinate, &[rows, cols, count]) => (rows, cols, Some(count)),
+            _ => {
+                return Err(format_error(format!(
+                    "Invalid size line for {} layout: {:?}",
+                    layout, size
+                ))
+                .at_position(size_line, None))
+            }
+        };
+        if symmetry != Symmetry::General && rows != cols {
+            return Err(format_error(format!(
+                "A {} matrix must be square, not {}x{}",
+                symmetry, rows, cols
+            ))
+            .at_position(size_line, None));
+        }
+
+        // Check the size before allocating, so a bad header is an error
+        // rather than an overflow or an abort
+        rows.checked_mul(cols)
+            .and_then(|len| len.checked_mul(T::DTYPE.size()))
+            .filter(|&bytes| bytes <= isize::MAX as usize)
+            .ok_or_else(|| {
+                format_error(format!("Dimensions {}x{} are too large", rows, cols))
+                    .at_position(size_line, None)
+            })?;
+
+        let mut matrix = Matrix::new(rows, cols);
+        // Positions in the order an array file lists them
+        let mut positions = (0..cols)
+            .flat_map(|col| (0..rows).map(move |row| (row, col)))
+            .filter(|&(row, col)| symmetry.stores(row, col));
+        let mut read = 0;
+
+        for line in data_lines {
+            let (line_num, text) = line?;
+            let tokens: Vec<&str> = text.split_whitespace().collect();
+            let too_many =
+                || format_error("More entries than declared").at_position(line_num, None);
+            let (row, col, values) = match layout {
+                Layout::Array => {
+                    let Some((row, col)) = positions.next() else {
+                        return Err(too_many());
+                    };
+                    (row, col, &tokens[..])
+                }
+                Layout::Coordinate => {
+                    if Some(read) == count {
+                        return Err(too_many());
+                    }
+                    let (row, col) = parse_coordinate(&tokens, rows, cols, line_num)?;
+                    (row, col, tokens.get(2..).unwrap_or_default())
+                }
+            };
+            if values.len() != field.width() {
+                return Err(format_error(format!(
+                    "Expected {} value(s) per {} entry, found {}",
+                    field.width(),
+                    field,
+                    values.len()
+                ))
+                .at_position(line_num, None));
+            }
+            if symmetry == Symmetry::SkewSymmetric && row == col {
+                return Err(
+                    format_error("Skew-symmetric matrices have no diagonal entries")
+                        .at_position(line_num, None),
+                );
+            }
+
+            let value = T::parse_entry(field, values).map_err(|e| e.at_position(line_num, None))?;
+            // Coordinate files may repeat a position; repeated values add up
+            matrix.data[row * cols + col] += value;
+            if row != col && symmetry != Symmetry::General {
+                matrix.data[col * cols + row] += symmetry.mirror(value);
+            }
+            read += 1;
+        }
+
+        let expected = count.unwrap_or_else(|| {
+            (0..cols)
+                .map(|col| (0..rows).filter(|&row| symmetry.stores(row, col)).count())
+                .sum()
+        });
+        if read != expected {
+            return Err(format_error(format!(
+                "Expected {} entries, found {}",
+                expected, read
+            )));
+        }
+        Ok(matrix)
+    }
+
+    /// Save a matrix to a Matrix Market file
+    ///
+    /// Any symmetry other than general stores only the lower triangle and
+    /// requires the matrix to actually have that symmetry.
+    pub fn save_matrix_market<P: AsRef<Path>>(
+        &self,
+        path: P,
+        layout: Layout,
+        symmetry: Symmetry,
+    ) -> Result<()> {
+        self.check_symmetry(symmetry)?;
+        let path = path.as_ref();
+        let write = || -> io::Result<()> {
+            let mut writer = BufWriter::new(File::create(path)?);
+            self.write_entries(&mut writer, layout, symmetry)?;
+            writer.flush()
+        };
+        write().map_err(|e| Error::io(Some(path), e))
+    }
+
+    /// Write a matrix in the Matrix Market format
+    pub fn write_matrix_market<W: Write>(
+        &self,
+        writer: &mut W,
+        layout: Layout,
+        symmetry: Symmetry,
+    ) -> Result<()> {
+        self.check_symmetry(symmetry)?;
+        self.write_entries(writer, layout, symmetry)
+            .map_err(|e| Error::io(None, e))
+    }
+
+    fn check_symmetry(&self, symmetry: Symmetry) -> Result<()> {
+        if symmetry == Symmetry::General {
+            return Ok(());
+        }
+        if self.rows != self.cols {
+            return Err(Error::NotSquare {
+                operation: "symmetric Matrix Market storage",
+                rows: self.rows,
+                cols: self.cols,
+            });
+        }
+        let n = self.cols;
+        for i in 0..n {
+            let diagonal = self.data[i * n + i];
+            let holds = match symmetry {
+                Symmetry::SkewSymmetric => diagonal == T::zero(),
+                Symmetry::Hermitian => diagonal == diagonal.conj_entry(),
+                _ => true,
+            } && (0..i)
+                .all(|j| self.data[j * n + i] == symmetry.mirror(self.data[i * n + j]));
+            if !holds {
+                return Err(Error::invalid_argument(
+                    "Matrix Market symmetry",
+                    format!("the matrix is not {}", symmetry),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn write_entries<W: Write>(
+        &self,
+        writer: &mut W,
+        layout: Layout,
+        symmetry: Symmetry,
+    ) -> io::Result<()> {
+        writeln!(
+            writer,
+            "%%MatrixMarket matrix {} {} {}",
+            layout,
+            T::FIELD,
+            symmetry
+        )?;
+        let stored = (0..self.cols)
+            .flat_map(|col| (0..self.rows).map(move |row| (row, col)))
+            .filter(|&(row, col)| symmetry.stores(row, col));
+
+        match layout {
+            Layout::Array => {
+                writeln!(writer, "{} {}", self.rows, self.cols)?;
+                for (row, col) in stored {
+                    self.data[row * self.cols + col].write_entry(writer)?;
+                    writeln!(writer)?;
+                }
+            }
+            Layout::Coordinate => {
+                let nonzero: Vec<(usize, usize)> = stored
+                    .filter(|&(row, col)| self.data[row * self.cols + col] != T::zero())
+                    .collect();
+                writeln!(writer, "{} {} {}", self.rows, self.cols, nonzero.len())?;
+                for (row, col) in nonzero {
+                    write!(writer, "{} {} ", row + 1, col + 1)?;
+                    self.data[row * self.cols + col].write_entry(writer)?;
+                    writeln!(writer)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_banner(banner: &str) -> Result<(Layout, MarketField, Symmetry)> {
+    let tokens: Vec<&str> = banner.split_whitespace().collect();
+    let [magic, object, layout, field, symmetry] = tokens[..] else {
+        return Err(format_error("Expected a %%MatrixMarket banner").at_position(1, None));
+    };
+    if !magic.eq_ignore_ascii_case(BANNER) {
+        return Err(format_error("Expected a %%MatrixMarket banner").at_position(1, None));
+    }
+    if !object.eq_ignore_ascii_case("matrix") {
+        return Err(format_error(format!("Unsupported object {:?}", object)).at_position(1, None));
+    }
+    let banner_error = |e: Error| format_error(e.to_string()).at_position(1, None);
+    let layout = Layout::parse(layout).map_err(banner_error)?;
+    let field = MarketField::parse(field).map_err(banner_error)?;
+    let symmetry = Symmetry::parse(symmetry).map_err(banner_error)?;
+    if field == MarketField::Pattern && layout == Layout::Array {
+        return Err(
+            format_error("Pattern matrices must use the coordinate layout").at_position(1, None),
+        );
+    }
+    Ok((layout, field, symmetry))
+}
+
+fn parse_indices(text: &str, line: usize) -> Result<Vec<usize>> {
+    text.split_whitespace()
+        .map(|token| {
+            token
+                .parse::<usize>()
+                .map_err(|e| Error::parse(token, e).at_position(line, None))
+        })
+        .collect()
+}
+
+/// 0-based position of a coordinate entry
+fn parse_coordinate(
+    tokens: &[&str],
+    rows: usize,
+    cols: usize,
+    line: usize,
+) -> Result<(usize, usize)> {
+    let [row, col, ..] = tokens[..] else {
+        return Err(format_error("Expected a row and a column index").at_position(line, None));
+    };
+    let index = |token: &str| {
+        token
+            .parse::<usize>()
+            .map_err(|e| Error::parse(token, e).at_position(line, None))
+    };
+    let (row, col) = (index(row)?, index(col)?);
+    if !(1..=rows).contains(&row) || !(1..=cols).contains(&col) {
+        return Err(format_error(format!(
+            "Entry ({}, {}) lies outside the {}x{} matrix",
+            row, col, rows, cols
+        ))
+        .at_position(line, None));
+    }
+    Ok((row - 1, col - 1))
+}
@@ -1,4 +1,9 @@
+use crate::accumulate::Accumulation;
+use crate::element::Element;
+use crate::error::{Error, Result};
+use crate::kernel::Transpose;
 use crate::matrix::Matrix;
+use crate::view::MatrixView;
 use mpi::traits::*;
 
 // MPI message tags
@@ -6,6 +11,91 @@ pub const TAG_MATRIX_DIMENSIONS: i32 = 1;
 pub const TAG_MATRIX_DATA: i32 = 2;
 pub const TAG_RESULT_DATA: i32 = 3;
 pub const TAG_WORK_ASSIGNMENT: i32 = 4;
+pub const TAG_SCALARS: i32 = 5;
+
+/// Work handed to one worker: a block of the result and how to compute it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WorkAssignment {
+    pub row_start: usize,
+    pub row_end: usize,
+    pub col_start: usize,
+    pub col_end: usize,
+    /// The A chunk arrives as stored and enters the product transposed
+    pub trans_a: Transpose,
+    /// B arrives as stored and enters the product transposed
+    pub trans_b: Transpose,
+    /// A block of C follows the operands and is accumulated into
+    pub accumulate: bool,
+    /// No A chunk is sent; the worker's result from the previous round is used
+    pub reuse_a: bool,
+    /// Another assignment follows once this one is done
+    pub more: bool,
+    /// How the worker sums its dot products
+    pub accumulation: Accumulation,
+    /// Compute with the reproducible dot-product kernel
+    pub reproducible: bool,
+}
+
+impl WorkAssignment {
+    /// Assignment for the result block `[row_start, row_end) x [col_start, col_end)`
+    pub fn new(row_start: usize, row_end: usize, col_start: usize, col_end: usize) -> Self {
+        WorkAssignment {
+            row_start,
+            row_end,
+            col_start,
+            col_end,
+            ..WorkAssignment::default()
+        }
+    }
+
+    /// Whether the worker has nothing to compute
+    pub fn is_empty(&self) -> bool {
+        self.row_start >= self.row_end || self.col_start >= self.col_end
+    }
+
+    /// The assignment as it is sent over MPI
+    pub fn encode(&self) -> [i32; 11] {
+        [
+            self.row_start as i32,
+            self.row_end as i32,
+            self.col_start as i32,
+            self.col_end as i32,
+            self.trans_a.is_transposed() as i32,
+            self.trans_b.is_transposed() as i32,
+            self.accumulate as i32,
+            self.reuse_a as i32,
+            self.more as i32,
+            self.accumulation as i32,
+            self.reproducible as i32,
+        ]
+    }
+
+    /// Rebuild an assignment from its [`encode`](WorkAssignment::encode)d
+    /// form, as received from rank `source`
+    pub fn decode(msg: &[i32; 11], source: i32) -> Result<Self> {
+        let accumulation = usize::try_from(msg[9])
+            .ok()
+            .and_then(|index| Accumulation::ALL.get(index))
+            .copied()
+            .ok_or_else(|| Error::Mpi {
+                rank: source,
+                message: format!("Work assignment has unknown accumulation mode {}", msg[9]),
+            })?;
+        Ok(WorkAssignment {
+            row_start: msg[0] as usize,
+            row_end: msg[1] as usize,
+            col_start: msg[2] as usize,
+            col_end: msg[3] as usize,
+            trans_a: Transpose::from_flag(msg[4] != 0),
+            trans_b: Transpose::from_flag(msg[5] != 0),
+            accumulate: msg[6] != 0,
+            reuse_a: msg[7] != 0,
+            more: msg[8] != 0,
+            accumulation,
+            reproducible: msg[10] != 0,
+        })
+    }
+}
 
 /// Send matrix dimensions to a destination
 pub fn send_matrix_dimensions(
@@ -13,7 +103,7 @@ pub fn send_matrix_dimensions(
     dest: i32,
     rows: usize,
     cols: usize,
-) -> Result<(), String> {
+) -> Result<()> {
     let dims = [rows as i32, cols as i32];
     let dest_process = world.process_at_rank(dest);
     dest_process.send_with_tag(&dims[..], TAG_MATRIX_DIMENSIONS);
@@ -21,10 +111,7 @@ pub fn send_matrix_dimensions(
 }
 
 /// Receive matrix dimensions from a source
-pub fn receive_matrix_dimensions(
-    world: &dyn Communicator,
-    source: i32,
-) -> Result<(usize, usize), String> {
+pub fn receive_matrix_dimensions(world: &dyn Communicator, source: i32) -> Result<(usize, usize)> {
     let source_process = world.process_at_rank(source);
     let mut msg = [0i32; 2];
     source_process.receive_into_with_tag(&mut msg[..], TAG_MATRIX_DIMENSIONS);
@@ -33,32 +120,45 @@ pub fn receive_matrix_dimensions(
 }
 
 /// Send a matrix to a destination
-pub fn send_matrix(
+pub fn send_matrix<T: Element>(
     world: &dyn Communicator,
     dest: i32,
-    matrix: &Matrix,
-) -> Result<(), String> {
+    matrix: &Matrix<T>,
+) -> Result<()> {
+    send_view(world, dest, matrix.view())
+}
+
+/// Send a view to a destination; the receiver gets it as a dense matrix via
+/// [`receive_matrix`]
+///
+/// Contiguous views (such as row chunks) are sent straight from the borrowed
+/// buffer; strided ones are packed into a temporary first.
+pub fn send_view<T: Element>(
+    world: &dyn Communicator,
+    dest: i32,
+    view: MatrixView<'_, T>,
+) -> Result<()> {
     // First send dimensions
-    send_matrix_dimensions(world, dest, matrix.rows, matrix.cols)?;
+    send_matrix_dimensions(world, dest, view.rows(), view.cols())?;
 
     // Then send data
     let dest_process = world.process_at_rank(dest);
-    dest_process.send_with_tag(&matrix.data[..], TAG_MATRIX_DATA);
+    match view.as_slice() {
+        Some(data) => dest_process.send_with_tag(data, TAG_MATRIX_DATA),
+        None => dest_process.send_with_tag(&view.to_matrix().data[..], TAG_MATRIX_DATA),
+    }
 
     Ok(())
 }
 
 /// Receive a matrix from a source
-pub fn receive_matrix(
-    world: &dyn Communicator,
-    source: i32,
-) -> Result<Matrix, String> {
+pub fn receive_matrix<T: Element>(world: &dyn Communicator, source: i32) -> Result<Matrix<T>> {
     // First receive dimensions
     let (rows, cols) = receive_matrix_dimensions(world, source)?;
 
     // Then receive data
     let source_process = world.process_at_rank(source);
-    let mut data = vec![0.0f64; rows * cols];
+    let mut data = vec![T::zero(); rows * cols];
     source_process.receive_into_with_tag(&mut data[..], TAG_MATRIX_DATA);
 
     Ok(Matrix {
@@ -74,7 +174,7 @@ pub fn broadcast_dimensions(
     root: i32,
     rows: usize,
     cols: usize,
-) -> Result<(usize, usize), String> {
+) -> Result<(usize, usize)> {
     let root_process = world.process_at_rank(root);
     let mut dims = if world.rank() == root {
         vec![rows as i32, cols as i32]
@@ -87,57 +187,55 @@ pub fn broadcast_dimensions(
     Ok((dims[0] as usize, dims[1] as usize))
 }
 
-/// Send work assignment (row range and column range) to a worker
+/// Send work assignment to a worker
 pub fn send_work_assignment(
     world: &dyn Communicator,
     dest: i32,
-    row_start: usize,
-    row_end: usize,
-    col_start: usize,
-    col_end: usize,
-) -> Result<(), String> {
-    let assignment = [
-        row_start as i32,
-        row_end as i32,
-        col_start as i32,
-        col_end as i32,
-    ];
+    assignment: &WorkAssignment,
+) -> Result<()> {
     let dest_process = world.process_at_rank(dest);
-    dest_process.send_with_tag(&assignment[..], TAG_WORK_ASSIGNMENT);
+    dest_process.send_with_tag(&assignment.encode()[..], TAG_WORK_ASSIGNMENT);
     Ok(())
 }
 
 /// Receive work assignment from coordinator
-pub fn receive_work_assignment(
+pub fn receive_work_assignment(world: &dyn Communicator, source: i32) -> Result<WorkAssignment> {
+    let source_process = world.process_at_rank(source);
+    let mut msg = [0i32; 11];
+    source_process.receive_into_with_tag(&mut msg[..], TAG_WORK_ASSIGNMENT);
+
+    WorkAssignment::decode(&msg, source)
+}
+
+/// Send scalar parameters (such as GEMM's alpha and beta) to a destination
+pub fn send_scalars<T: Element>(world: &dyn Communicator, dest: i32, scalars: &[T]) -> Result<()> {
+    let dest_process = world.process_at_rank(dest);
+    dest_process.send_with_tag(scalars, TAG_SCALARS);
+    Ok(())
+}
+
+/// Receive `N` scalar parameters from a source
+pub fn receive_scalars<T: Element, const N: usize>(
     world: &dyn Communicator,
     source: i32,
-) -> Result<(usize, usize, usize, usize), String> {
+) -> Result<[T; N]> {
     let source_process = world.process_at_rank(source);
-    let mut assignment = [0i32; 4];
-    source_process.receive_into_with_tag(&mut assignment[..], TAG_WORK_ASSIGNMENT);
-
-    Ok((
-        assignment[0] as usize,
-        assignment[1] as usize,
-        assignment[2] as usize,
-        assignment[3] as usize,
-    ))
+    let mut scalars = [T::zero(); N];
+    source_process.receive_into_with_tag(&mut scalars[..], TAG_SCALARS);
+    Ok(scalars)
 }
 
 /// Send result matrix chunk to coordinator
-pub fn send_result(
+pub fn send_result<T: Element>(
     world: &dyn Communicator,
     dest: i32,
-    result: &Matrix,
-) -> Result<(), String> {
+    result: &Matrix<T>,
+) -> Result<()> {
     send_matrix(world, dest, result)
 }
 
 /// Receive result matrix chunk from worker
-pub fn receive_result(
-    world: &dyn Communicator,
-    source: i32,
-) -> Result<Matrix, String> {
+pub fn receive_result<T: Element>(world: &dyn Communicator, source: i32) -> Result<Matrix<T>> {
     receive_matrix(world, source)
 }
 
@@ -0,0 +1,443 @@
+//! NumPy `.npy` and `.npz` files
+//!
+//! A `.npy` file is the magic string `\x93NUMPY`, a two-byte version, the
+//! length of the header, the header itself and the raw array data. The
+//! header is a Python dict literal such as
+//! `{'descr': '<f8', 'fortran_order': False, 'shape': (3, 4), }`, padded with
+//! spaces and a newline so that the data starts on a 64-byte boundary.
+//!
+//! Arrays of `<f4`, `<f8`, `<i4`, `<i8`, `<c8` and `<c16` (either byte order)
+//! are read in C or Fortran order. Two-dimensional arrays map to matrices as
+//! they are; one-dimensional arrays are read as a single column. Writers use
+//! version 1.0 and the native byte order.
+//!
+//! A `.npz` file is a zip archive of `.npy` files, one per named array, as
+//! written by `numpy.savez` (stored) or `numpy.savez_compressed` (deflated).
+
+use crate::binary::{self, format_error, in_file, read_exact};
+use crate::element::{Dtype, Element};
+use crate::error::{Error, Result};
+use crate::matrix::Matrix;
+use crate::view::MatrixView;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+use zip::result::ZipError;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// First bytes of every `.npy` file
+pub const MAGIC: [u8; 6] = *b"\x93NUMPY";
+
+/// First bytes of a zip archive, and so of every `.npz` file
+pub const NPZ_MAGIC: [u8; 4] = *b"PK\x03\x04";
+
+/// File extension that [`Matrix::save`] writes as `.npy`
+pub const EXTENSION: &str = "npy";
+
+/// File extension that [`Matrix::save`] writes as a one-array `.npz`
+pub const NPZ_EXTENSION: &str = "npz";
+
+/// Name `numpy.savez` gives its first positional array
+pub const DEFAULT_NAME: &str = "arr_0";
+
+/// Alignment of the start of the data
+const ALIGNMENT: usize = 64;
+
+/// Whether the file at `path` starts with the `.npy` magic string
+pub fn is_npy_file<P: AsRef<Path>>(path: P) -> Result<bool> {
+    binary::file_starts_with(path.as_ref(), &MAGIC)
+}
+
+/// Whether the file at `path` starts like a zip archive
+pub fn is_npz_file<P: AsRef<Path>>(path: P) -> Result<bool> {
+    binary::file_starts_with(path.as_ref(), &NPZ_MAGIC)
+}
+
+/// Names of the arrays in a `.npz` archive, in archive order
+pub fn npz_names<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
+    let path = path.as_ref();
+    Ok(names(&open_archive(path)?))
+}
+
+/// Save matrices to a `.npz` archive under the given names, deflating them
+/// if `compressed` is set
+pub fn save_npz<T: Element, P: AsRef<Path>>(
+    path: P,
+    arrays: &[(&str, &Matrix<T>)],
+    compressed: bool,
+) -> Result<()> {
+    let path = path.as_ref();
+    let file = File::create(path).map_err(|e| Error::io(Some(path), e))?;
+    write_npz(BufWriter::new(file), arrays, compressed).map_err(|e| in_file(e, path))
+}
+
+/// Write matrices as a `.npz` archive
+pub fn write_npz<T: Element, W: Write + Seek>(
+    writer: W,
+    arrays: &[(&str, &Matrix<T>)],
+    compressed: bool,
+) -> Result<()> {
+    let method = if compressed {
+        CompressionMethod::Deflated
+    } else {
+        CompressionMethod::Stored
+    };
+    let mut zip = ZipWriter::new(writer);
+    for (name, matrix) in arrays {
+        let len = matrix.data.len() * T::DTYPE.size() + ALIGNMENT;
+        let options = SimpleFileOptions::default()
+            .compression_method(method)
+            .large_file(len >= u32::MAX as usize);
+        zip.start_file(format!("{}.{}", name, EXTENSION), options)
+            .map_err(zip_error)?;
+        matrix
+            .view()
+            .write_npy(&mut zip, false)
+            .map_err(|e| Error::io(None, e))?;
+    }
+    zip.finish()
+        .map_err(zip_error)?
+        .flush()
+        .map_err(|e| Error::io(None, e))
+}
+
+impl<T: Element> Matrix<T> {
+    /// Load a matrix from a `.npy` file
+    pub fn load_npy<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| Error::io(Some(path), e))?;
+        Matrix::read_npy(BufReader::new(file)).map_err(|e| in_file(e, path))
+    }
+
+    /// Read a matrix in the `.npy` format
+    pub fn read_npy<R: Read>(mut reader: R) -> Result<Self> {
+        let header = read_header(&mut reader)?;
+        if header.dtype != T::DTYPE {
+            return Err(format_error(format!(
+                "File holds {} elements, but {} was requested",
+                header.dtype,
+                T::DTYPE
+            )));
+        }
+        let (rows, cols) = match header.shape[..] {
+            [rows, cols] => (rows, cols),
+            [rows] => (rows, 1),
+            _ => {
+                return Err(format_error(format!(
+                    "Expected a 1-D or 2-D array, found shape {:?}",
+                    header.shape
+                )))
+            }
+        };
+
+        let len = rows
+            .checked_mul(cols)
+            .and_then(|len| len.checked_mul(T::DTYPE.size()))
+            .ok_or_else(|| format_error(format!("Shape {:?} is too large", header.shape)))?;
+        let mut bytes = Vec::new();
+        (&mut reader)
+            .take(len as u64)
+            .read_to_end(&mut bytes)
+            .map_err(|e| Error::io(None, e))?;
+        if bytes.len() != len {
+            return Err(format_error("File is truncated"));
+        }
+        let mut rest = [0u8; 1];
+        if reader.read(&mut rest).map_err(|e| Error::io(None, e))? != 0 {
+            return Err(format_error("Unexpected data after the array"));
+        }
+
+        let data = bytes
+            .chunks_exact(T::DTYPE.size())
+            .map(|element| T::read(element, header.big_endian))
+            .collect();
+        if header.fortran_order {
+            // Column-major data is the row-major transpose
+            let mut matrix = Matrix::from_vec(data, cols, rows)?;
+            matrix.transpose_in_place();
+            Ok(matrix)
+        } else {
+            Matrix::from_vec(data, rows, cols)
+        }
+    }
+
+    /// Save a matrix to a `.npy` file, in Fortran (column-major) order if
+    /// `fortran_order` is set
+    pub fn save_npy<P: AsRef<Path>>(&self, path: P, fortran_order: bool) -> Result<()> {
+        self.view().save_npy(path, fortran_order)
+    }
+
+    /// Load the array called `name` from a `.npz` archive
+    pub fn load_npz<P: AsRef<Path>>(path: P, name: &str) -> Result<Self> {
+        let path = path.as_ref();
+        let mut archive = open_archive(path)?;
+        let available = names(&archive);
+        let entry = match archive.by_name(&format!("{}.{}", name, EXTENSION)) {
+            Ok(entry) => entry,
+            Err(ZipError::FileNotFound) => {
+                return Err(format_error(format!(
+                    "No array named {:?}; the archive holds {:?}",
+                    name, available
+                ))
+                .at(path, None))
+            }
+            Err(e) => return Err(in_file(zip_error(e), path)),
+        };
+        Matrix::read_npy(entry).map_err(|e| in_file(e, path))
+    }
+
+    /// Load the only array of a `.npz` archive
+    pub fn load_npz_single<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        match &npz_names(path)?[..] {
+            [name] => Matrix::load_npz(path, name),
+            names => Err(format_error(format!(
+                "Expected exactly one array, the archive holds {:?}",
+                names
+            ))
+            .at(path, None)),
+        }
+    }
+}
+
+impl<T: Element> MatrixView<'_, T> {
+    /// Save the viewed elements to a `.npy` file
+    pub fn save_npy<P: AsRef<Path>>(&self, path: P, fortran_order: bool) -> Result<()> {
+        let path = path.as_ref();
+        let write = || -> io::Result<()> {
+            let mut writer = BufWriter::new(File::create(path)?);
+            self.write_npy(&mut writer, fortran_order)?;
+            writer.flush()
+        };
+        write().map_err(|e| Error::io(Some(path), e))
+    }
+
+    /// Write the viewed elements in the `.npy` format
+    pub fn write_npy<W: Write>(&self, writer: &mut W, fortran_order: bool) -> io::Result<()> {
+        let order = if cfg!(target_endian = "big") {
+            '>'
+        } else {
+            '<'
+        };
+        let dict = format!(
+            "{{'descr': '{}{}', 'fortran_order': {}, 'shape': ({}, {}), }}",
+            order,
+            descr(T::DTYPE),
+            if fortran_order { "True" } else { "False" },
+            self.rows(),
+            self.cols()
+        );
+        // Magic, version and length take 10 bytes; the header ends in '\n'
+        let padded = (10 + dict.len() + 1).next_multiple_of(ALIGNMENT) - 10;
+        let header = format!("{:<width$}\n", dict, width = padded - 1);
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[1, 0])?;
+        writer.write_all(&(padded as u16).to_le_bytes())?;
+        writer.write_all(header.as_bytes())?;
+
+        // Fortran order lists the columns, i.e. the rows of the transpose
+        let stored = if fortran_order {
+            self.transpose()
+        } else {
+            *self
+        };
+        let mut row = Vec::with_capacity(stored.cols() * T::DTYPE.size());
+        for i in 0..stored.rows() {
+            row.clear();
+            for j in 0..stored.cols() {
+                stored.at(i, j).write_ne(&mut row);
+            }
+            writer.write_all(&row)?;
+        }
+        Ok(())
+    }
+}
+
+/// Decoded `.npy` header
+struct Header {
+    dtype: Dtype,
+    big_endian: bool,
+    fortran_order: bool,
+    shape: Vec<usize>,
+}
+
+/// Type code of an element type, without the byte order
+fn descr(dtype: Dtype) -> &'static str {
+    match dtype {
+        Dtype::F32 => "f4",
+        Dtype::F64 => "f8",
+        Dtype::I32 => "i4",
+        Dtype::I64 => "i8",
+        Dtype::C64 => "c8",
+        Dtype::C128 => "c16",
+    }
+}
+
+fn read_header<R: Read>(reader: &mut R) -> Result<Header> {
+    let mut preamble = [0u8; 8];
+    read_exact(reader, &mut preamble)?;
+    if preamble[..6] != MAGIC {
+        return Err(format_error("Not a .npy file (bad magic string)"));
+    }
+    let len = match preamble[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            read_exact(reader, &mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            read_exact(reader, &mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        major => {
+            return Err(format_error(format!(
+                "Unsupported format version {}.{}",
+                major, preamble[7]
+            )))
+        }
+    };
+    let mut text = Vec::new();
+    reader
+        .take(len as u64)
+        .read_to_end(&mut text)
+        .map_err(|e| Error::io(None, e))?;
+    if text.len() != len {
+        return Err(format_error("File is truncated"));
+    }
+    let text = std::str::from_utf8(&text).map_err(|_| format_error("Header is not valid text"))?;
+    parse_header(text)
+}
+
+/// Parse the header dict, which holds exactly the keys `descr`,
+/// `fortran_order` and `shape`
+fn parse_header(text: &str) -> Result<Header> {
+    let invalid = || format_error(format!("Invalid header {:?}", text.trim_end()));
+    let mut parser = Literal(text.trim());
+    let (mut descr, mut fortran_order, mut shape) = (None, None, None);
+
+    parser.expect('{').ok_or_else(invalid)?;
+    while !parser.eat('}') {
+        let key = parser.string().ok_or_else(invalid)?;
+        parser.expect(':').ok_or_else(invalid)?;
+        match key {
+            "descr" => descr = Some(parser.string().ok_or_else(invalid)?),
+            "fortran_order" => fortran_order = Some(parser.boolean().ok_or_else(invalid)?),
+            "shape" => shape = Some(parser.tuple().ok_or_else(invalid)?),
+            _ => return Err(invalid()),
+        }
+        if !parser.eat(',') {
+            parser.expect('}').ok_or_else(invalid)?;
+            break;
+        }
+    }
+    if !parser.0.is_empty() {
+        return Err(invalid());
+    }
+
+    let (Some(descr), Some(fortran_order), Some(shape)) = (descr, fortran_order, shape) else {
+        return Err(invalid());
+    };
+    let mut chars = descr.chars();
+    let order = chars.next();
+    let code = chars.as_str();
+    let big_endian = match order {
+        Some('<' | '|') => false,
+        Some('>') => true,
+        Some('=') => cfg!(target_endian = "big"),
+        _ => return Err(format_error(format!("Unsupported dtype {:?}", descr))),
+    };
+    let dtype = Dtype::ALL
+        .into_iter()
+        .find(|&dtype| self::descr(dtype) == code)
+        .ok_or_else(|| format_error(format!("Unsupported dtype {:?}", descr)))?;
+    Ok(Header {
+        dtype,
+        big_endian,
+        fortran_order,
+        shape,
+    })
+}
+
+/// Cursor over the Python literal subset used by `.npy` headers
+struct Literal<'a>(&'a str);
+
+impl<'a> Literal<'a> {
+    /// Skip whitespace, then consume `c` if it comes next
+    fn eat(&mut self, c: char) -> bool {
+        self.0 = self.0.trim_start();
+        match self.0.strip_prefix(c) {
+            Some(rest) => {
+                self.0 = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Option<()> {
+        self.eat(c).then_some(())
+    }
+
+    /// Quoted string without escapes
+    fn string(&mut self) -> Option<&'a str> {
+        let quote = ['\'', '"'].into_iter().find(|&quote| self.eat(quote))?;
+        let (value, rest) = self.0.split_once(quote)?;
+        self.0 = rest;
+        Some(value)
+    }
+
+    fn boolean(&mut self) -> Option<bool> {
+        self.0 = self.0.trim_start();
+        for (word, value) in [("True", true), ("False", false)] {
+            if let Some(rest) = self.0.strip_prefix(word) {
+                self.0 = rest;
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    /// Tuple of non-negative integers, such as `()`, `(3,)` or `(3, 4)`
+    fn tuple(&mut self) -> Option<Vec<usize>> {
+        self.expect('(')?;
+        let mut values = Vec::new();
+        while !self.eat(')') {
+            self.0 = self.0.trim_start();
+            let end = self
+                .0
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(self.0.len());
+            values.push(self.0[..end].parse().ok()?);
+            // Python 2 wrote long integers with an `L` suffix
+            self.0 = self.0[end..].strip_prefix('L').unwrap_or(&self.0[end..]);
+            if !self.eat(',') {
+                self.expect(')')?;
+                break;
+            }
+        }
+        Some(values)
+    }
+}
+
+fn open_archive(path: &Path) -> Result<ZipArchive<BufReader<File>>> {
+    let file = File::open(path).map_err(|e| Error::io(Some(path), e))?;
+    ZipArchive::new(BufReader::new(file)).map_err(|e| in_file(zip_error(e), path))
+}
+
+/// Array names: the `.npy` entries without their extension
+fn names<R: Read + Seek>(archive: &ZipArchive<R>) -> Vec<String> {
+    archive
+        .file_names()
+        .filter_map(|name| name.strip_suffix(".npy"))
+        .map(str::to_string)
+        .collect()
+}
+
+fn zip_error(error: ZipError) -> Error {
+    match error {
+        ZipError::Io(e) => Error::io(None, e),
+        e => format_error(e.to_string()),
+    }
+}
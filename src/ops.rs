@@ -0,0 +1,215 @@
+//! `std::ops` implementations for [`Matrix`]
+//!
+//! Element-wise `+`/`-`, the matrix product `*`, negation, scaling by an
+//! element and `m[(row, col)]` indexing, for owned and borrowed operands.
+//! Owned operands have their buffer reused for the result. Shape mismatches
+//! and out-of-bounds indices panic; use [`Matrix::get`], [`Matrix::set`] and
+//! [`Matrix::multiply`] for fallible access.
+
+use crate::element::Element;
+use crate::matrix::Matrix;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
+
+fn check_same_shape<T: Element>(op: &str, lhs: &Matrix<T>, rhs: &Matrix<T>) {
+    if lhs.rows != rhs.rows || lhs.cols != rhs.cols {
+        panic!(
+            "Cannot {} matrices of different shapes: {}x{} and {}x{}",
+            op, lhs.rows, lhs.cols, rhs.rows, rhs.cols
+        );
+    }
+}
+
+fn check_index<T: Element>(matrix: &Matrix<T>, row: usize, col: usize) {
+    if row >= matrix.rows || col >= matrix.cols {
+        panic!(
+            "Index out of bounds: ({}, {}) for matrix {}x{}",
+            row, col, matrix.rows, matrix.cols
+        );
+    }
+}
+
+impl<T: Element> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        check_index(self, row, col);
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl<T: Element> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        check_index(self, row, col);
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+/// Element-wise operator for every owned/borrowed operand combination
+macro_rules! impl_elementwise {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $verb:literal) => {
+        impl<T: Element> $assign_trait<&Matrix<T>> for Matrix<T> {
+            fn $assign_method(&mut self, rhs: &Matrix<T>) {
+                check_same_shape($verb, self, rhs);
+                for (x, &y) in self.data.iter_mut().zip(&rhs.data) {
+                    x.$assign_method(y);
+                }
+            }
+        }
+
+        impl<T: Element> $assign_trait<Matrix<T>> for Matrix<T> {
+            fn $assign_method(&mut self, rhs: Matrix<T>) {
+                self.$assign_method(&rhs);
+            }
+        }
+
+        impl<T: Element> $trait<&Matrix<T>> for Matrix<T> {
+            type Output = Matrix<T>;
+
+            fn $method(mut self, rhs: &Matrix<T>) -> Matrix<T> {
+                self.$assign_method(rhs);
+                self
+            }
+        }
+
+        impl<T: Element> $trait<Matrix<T>> for Matrix<T> {
+            type Output = Matrix<T>;
+
+            fn $method(self, rhs: Matrix<T>) -> Matrix<T> {
+                self.$method(&rhs)
+            }
+        }
+
+        impl<T: Element> $trait<&Matrix<T>> for &Matrix<T> {
+            type Output = Matrix<T>;
+
+            fn $method(self, rhs: &Matrix<T>) -> Matrix<T> {
+                self.clone().$method(rhs)
+            }
+        }
+
+        impl<T: Element> $trait<Matrix<T>> for &Matrix<T> {
+            type Output = Matrix<T>;
+
+            fn $method(self, mut rhs: Matrix<T>) -> Matrix<T> {
+                // Reuse the right-hand buffer: rhs[i] = self[i] op rhs[i]
+                check_same_shape($verb, self, &rhs);
+                for (&x, y) in self.data.iter().zip(rhs.data.iter_mut()) {
+                    *y = x.$method(*y);
+                }
+                rhs
+            }
+        }
+    };
+}
+
+impl_elementwise!(Add, add, AddAssign, add_assign, "add");
+impl_elementwise!(Sub, sub, SubAssign, sub_assign, "subtract");
+
+fn product<T: Element>(lhs: &Matrix<T>, rhs: &Matrix<T>) -> Matrix<T> {
+    lhs.multiply(rhs).unwrap_or_else(|e| panic!("{}", e))
+}
+
+impl<T: Element> Mul<Matrix<T>> for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Matrix<T>) -> Matrix<T> {
+        product(&self, &rhs)
+    }
+}
+
+impl<T: Element> Mul<&Matrix<T>> for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: &Matrix<T>) -> Matrix<T> {
+        product(&self, rhs)
+    }
+}
+
+impl<T: Element> Mul<Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Matrix<T>) -> Matrix<T> {
+        product(self, &rhs)
+    }
+}
+
+impl<T: Element> Mul<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: &Matrix<T>) -> Matrix<T> {
+        product(self, rhs)
+    }
+}
+
+impl<T: Element> Neg for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn neg(mut self) -> Matrix<T> {
+        self.data.iter_mut().for_each(|x| *x = -*x);
+        self
+    }
+}
+
+impl<T: Element> Neg for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn neg(self) -> Matrix<T> {
+        -self.clone()
+    }
+}
+
+impl<T: Element> MulAssign<T> for Matrix<T> {
+    fn mul_assign(&mut self, scalar: T) {
+        self.data.iter_mut().for_each(|x| *x *= scalar);
+    }
+}
+
+impl<T: Element> Mul<T> for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(mut self, scalar: T) -> Matrix<T> {
+        self *= scalar;
+        self
+    }
+}
+
+impl<T: Element> Mul<T> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, scalar: T) -> Matrix<T> {
+        self.clone() * scalar
+    }
+}
+
+/// `scalar * matrix`; the scalar is the foreign left-hand type, so this needs
+/// one impl per element type
+macro_rules! impl_scalar_lhs {
+    ($($ty:ty),*) => {
+        $(
+            impl Mul<Matrix<$ty>> for $ty {
+                type Output = Matrix<$ty>;
+
+                fn mul(self, matrix: Matrix<$ty>) -> Matrix<$ty> {
+                    matrix * self
+                }
+            }
+
+            impl Mul<&Matrix<$ty>> for $ty {
+                type Output = Matrix<$ty>;
+
+                fn mul(self, matrix: &Matrix<$ty>) -> Matrix<$ty> {
+                    matrix * self
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar_lhs!(
+    f32,
+    f64,
+    i32,
+    i64,
+    num_complex::Complex<f32>,
+    num_complex::Complex<f64>
+);
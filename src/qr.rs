@@ -0,0 +1,462 @@
+//! Householder QR decomposition and least-squares solves
+//!
+//! Factorizes `A = Q R` (or `A P = Q R` with column pivoting) for any shape.
+//! Q is kept implicitly as Householder reflectors `H = I - tau v v^H`, stored
+//! below the diagonal of R with their leading 1 implied, as LAPACK does.
+//!
+//! The unpivoted factorization works on panels of columns: each panel is
+//! factored column by column, its reflectors are combined into the compact WY
+//! form `I - V T V^H`, and the trailing matrix is updated with three products
+//! through the blocked GEMM kernel. Column pivoting picks the column with the
+//! largest remaining norm at every step, so it runs unblocked.
+
+use crate::element::{Element, Field, Real};
+use crate::error::{Error, Result};
+use crate::kernel::{self, axpy, Strided, TileConfig};
+use crate::matrix::Matrix;
+use std::ops::Range;
+
+/// Columns per panel in the blocked factorization
+const BLOCK: usize = 32;
+
+/// QR factorization `A = Q R`, or `A P = Q R` if column-pivoted
+#[derive(Debug, Clone)]
+pub struct Qr<T = f64> {
+    /// R on and above the diagonal, reflectors below it
+    factors: Matrix<T>,
+    /// Scalar factor of each reflector
+    tau: Vec<T>,
+    /// Column `j` of `A P` is column `perm[j]` of A, if pivoted
+    perm: Option<Vec<usize>>,
+}
+
+impl<T: Field> Matrix<T> {
+    /// Householder QR factorization `A = Q R`
+    pub fn qr(&self) -> Qr<T> {
+        Qr::new(self)
+    }
+
+    /// Householder QR factorization with column pivoting, `A P = Q R`
+    ///
+    /// The diagonal of R is non-increasing in magnitude, which reveals the
+    /// numerical rank.
+    pub fn qr_pivoted(&self) -> Qr<T> {
+        Qr::new_pivoted(self)
+    }
+
+    /// Least-squares solution of `A X = B` through column-pivoted QR
+    ///
+    /// For full column rank this minimizes `||A X - B||`. Rank-deficient and
+    /// underdetermined systems get a basic solution, with one zero row of X
+    /// per column of A beyond the numerical rank.
+    pub fn lstsq(&self, b: &Matrix<T>) -> Result<Matrix<T>> {
+        self.qr_pivoted().lstsq(b)
+    }
+}
+
+impl<T: Field> Qr<T> {
+    /// Factorize `a` without pivoting
+    pub fn new(a: &Matrix<T>) -> Self {
+        let (m, n) = (a.rows, a.cols);
+        let k = m.min(n);
+        let mut factors = a.clone();
+        let mut tau = Vec::with_capacity(k);
+        let tiles = TileConfig::default();
+
+        for kb in (0..k).step_by(BLOCK) {
+            let ke = (kb + BLOCK).min(k);
+            for j in kb..ke {
+                let t = make_reflector(&mut factors.data, n, m, j);
+                let v = reflector(&factors, j);
+                reflect(&v, t.conj(), &mut factors.data[j * n..], n, j + 1..ke);
+                tau.push(t);
+            }
+            if ke < n {
+                update_trailing(&mut factors, &tau[kb..ke], kb, ke, &tiles);
+            }
+        }
+
+        Qr {
+            factors,
+            tau,
+            perm: None,
+        }
+    }
+
+    /// Factorize `a` with column pivoting
+    pub fn new_pivoted(a: &Matrix<T>) -> Self {
+        let (m, n) = (a.rows, a.cols);
+        let k = m.min(n);
+        let mut factors = a.clone();
+        let mut tau = Vec::with_capacity(k);
+        let mut perm: Vec<usize> = (0..n).collect();
+
+        let mut norms: Vec<T::Real> = (0..n).map(|c| column_norm(&factors, 0, c)).collect();
+        let mut reference = norms.clone();
+        let zero = T::Real::zero();
+        let threshold = T::Real::EPSILON.sqrt();
+
+        for j in 0..k {
+            let mut p = j;
+            for c in j + 1..n {
+                if norms[c] > norms[p] {
+                    p = c;
+                }
+            }
+            if p != j {
+                for row in factors.data.chunks_exact_mut(n) {
+                    row.swap(j, p);
+                }
+                norms.swap(j, p);
+                reference.swap(j, p);
+                perm.swap(j, p);
+            }
+
+            let t = make_reflector(&mut factors.data, n, m, j);
+            let v = reflector(&factors, j);
+            reflect(&v, t.conj(), &mut factors.data[j * n..], n, j + 1..n);
+            tau.push(t);
+
+            // Downdate the remaining column norms by the entry moved into row
+            // j of R, recomputing them when cancellation makes that inaccurate
+            for c in j + 1..n {
+                if norms[c] == zero {
+                    continue;
+                }
+                let ratio = factors.data[j * n + c].abs() / norms[c];
+                let mut remaining = T::Real::one() - ratio * ratio;
+                if remaining < zero {
+                    remaining = zero;
+                }
+                let drift = norms[c] / reference[c];
+                if remaining * drift * drift <= threshold {
+                    norms[c] = column_norm(&factors, j + 1, c);
+                    reference[c] = norms[c];
+                } else {
+                    norms[c] *= remaining.sqrt();
+                }
+            }
+        }
+
+        Qr {
+            factors,
+            tau,
+            perm: Some(perm),
+        }
+    }
+
+    /// Shape of the factorized matrix
+    pub fn dims(&self) -> (usize, usize) {
+        (self.factors.rows, self.factors.cols)
+    }
+
+    /// Column permutation of a pivoted factorization: column `j` of `A P` is
+    /// column `permutation()[j]` of A
+    pub fn permutation(&self) -> Option<&[usize]> {
+        self.perm.as_deref()
+    }
+
+    /// Householder reflectors in LAPACK layout: vector `j` lies below the
+    /// diagonal in column `j` with an implied leading 1, scaled by `tau[j]`
+    pub fn reflectors(&self) -> (&Matrix<T>, &[T]) {
+        (&self.factors, &self.tau)
+    }
+
+    /// Upper-trapezoidal factor R, `min(m, n) x n`
+    pub fn r(&self) -> Matrix<T> {
+        let (m, n) = self.dims();
+        let k = m.min(n);
+        let mut r = Matrix::new(k, n);
+        for i in 0..k {
+            r.data[i * n + i..(i + 1) * n]
+                .copy_from_slice(&self.factors.data[i * n + i..(i + 1) * n]);
+        }
+        r
+    }
+
+    /// Thin orthonormal factor Q, `m x min(m, n)`
+    pub fn q(&self) -> Matrix<T> {
+        let (m, n) = self.dims();
+        let k = m.min(n);
+        let mut q = Matrix::new(m, k);
+        for i in 0..k {
+            q.data[i * k + i] = T::one();
+        }
+        // Columns before j are still unit vectors with zeros from row j on,
+        // so H_j only touches the later ones
+        for j in (0..k).rev() {
+            let v = reflector(&self.factors, j);
+            reflect(&v, self.tau[j], &mut q.data[j * k..], k, j..k);
+        }
+        q
+    }
+
+    /// Compute `Q B`, where Q is the full `m x m` orthonormal factor
+    pub fn apply_q(&self, b: &Matrix<T>) -> Result<Matrix<T>> {
+        let mut x = self.check_rhs(b)?.clone();
+        for j in (0..self.tau.len()).rev() {
+            let v = reflector(&self.factors, j);
+            reflect(
+                &v,
+                self.tau[j],
+                &mut x.data[j * b.cols..],
+                b.cols,
+                0..b.cols,
+            );
+        }
+        Ok(x)
+    }
+
+    /// Compute `Q^H B`
+    pub fn apply_qh(&self, b: &Matrix<T>) -> Result<Matrix<T>> {
+        let mut x = self.check_rhs(b)?.clone();
+        for j in 0..self.tau.len() {
+            let v = reflector(&self.factors, j);
+            reflect(
+                &v,
+                self.tau[j].conj(),
+                &mut x.data[j * b.cols..],
+                b.cols,
+                0..b.cols,
+            );
+        }
+        Ok(x)
+    }
+
+    /// Numerical rank: the number of diagonal entries of R above
+    /// `max(m, n) * eps * max|r_ii|`
+    pub fn rank(&self) -> usize {
+        let tolerance = self.tolerance();
+        self.diagonal().filter(|d| *d > tolerance).count()
+    }
+
+    /// Least-squares solution of `A X = B`
+    ///
+    /// A pivoted factorization truncates to its numerical rank, as
+    /// [`Matrix::lstsq`] describes; an unpivoted one fails with
+    /// [`Error::Singular`] if R has a negligible diagonal entry.
+    pub fn lstsq(&self, b: &Matrix<T>) -> Result<Matrix<T>> {
+        let (m, n) = self.dims();
+        let k = m.min(n);
+        let qhb = self.apply_qh(b)?;
+
+        let tolerance = self.tolerance();
+        let first_negligible = self.diagonal().position(|d| d <= tolerance);
+        let rank = match (&self.perm, first_negligible) {
+            (_, None) => k,
+            (Some(_), Some(rank)) => rank,
+            (None, Some(pivot)) => {
+                return Err(Error::Singular {
+                    operation: "QR least squares",
+                    pivot,
+                })
+            }
+        };
+
+        // Back substitution with the leading rank x rank block of R
+        let nrhs = b.cols;
+        let mut y = Matrix::from_vec(qhb.data[..rank * nrhs].to_vec(), rank, nrhs)?;
+        for i in (0..rank).rev() {
+            let (head, done) = y.data.split_at_mut((i + 1) * nrhs);
+            let row = &mut head[i * nrhs..];
+            for j in i + 1..rank {
+                let rij = self.factors.data[i * n + j];
+                axpy(row, -rij, &done[(j - i - 1) * nrhs..(j - i) * nrhs]);
+            }
+            let pivot = self.factors.data[i * n + i];
+            row.iter_mut().for_each(|v| *v /= pivot);
+        }
+
+        let mut x = Matrix::new(n, nrhs);
+        for i in 0..rank {
+            let dest = self.perm.as_ref().map_or(i, |perm| perm[i]);
+            x.data[dest * nrhs..(dest + 1) * nrhs]
+                .copy_from_slice(&y.data[i * nrhs..(i + 1) * nrhs]);
+        }
+        Ok(x)
+    }
+
+    fn diagonal(&self) -> impl Iterator<Item = T::Real> + '_ {
+        let (m, n) = self.dims();
+        (0..m.min(n)).map(move |i| self.factors.data[i * n + i].abs())
+    }
+
+    fn tolerance(&self) -> T::Real {
+        let (m, n) = self.dims();
+        let largest = self.diagonal().fold(T::Real::zero(), Real::max);
+        largest * T::Real::EPSILON * T::Real::from_f64(m.max(n) as f64)
+    }
+
+    fn check_rhs<'b>(&self, b: &'b Matrix<T>) -> Result<&'b Matrix<T>> {
+        let (m, n) = self.dims();
+        if b.rows != m {
+            return Err(Error::DimensionMismatch {
+                operation: "QR solve",
+                left: (m, n),
+                right: (b.rows, b.cols),
+            });
+        }
+        Ok(b)
+    }
+}
+
+/// Turn column `k` of the row-major `m x n` matrix in `data`, from row `k`
+/// down, into `beta e_1`, storing the reflector below the diagonal and
+/// returning its `tau`
+///
+/// Follows LAPACK's `larfg`: `H^H (alpha, x) = (beta, 0)` with
+/// `H = I - tau v v^H`, `v = (1, x / (alpha - beta))` and real `beta`.
+fn make_reflector<T: Field>(data: &mut [T], n: usize, m: usize, k: usize) -> T {
+    let alpha = data[k * n + k];
+    let zero = T::Real::zero();
+    let mut tail = zero;
+    for i in k + 1..m {
+        let x = data[i * n + k].abs();
+        tail += x * x;
+    }
+    if tail == zero && alpha == T::from_real(alpha.real()) {
+        return T::zero();
+    }
+
+    let norm = (alpha.abs() * alpha.abs() + tail).sqrt();
+    let beta = T::from_real(if alpha.real() >= zero { -norm } else { norm });
+    let scale = T::one() / (alpha - beta);
+    for i in k + 1..m {
+        data[i * n + k] *= scale;
+    }
+    data[k * n + k] = beta;
+    (beta - alpha) / beta
+}
+
+/// Reflector `j` as a dense vector over rows `j..m`, leading 1 included
+fn reflector<T: Field>(factors: &Matrix<T>, j: usize) -> Vec<T> {
+    let n = factors.cols;
+    let mut v = Vec::with_capacity(factors.rows - j);
+    v.push(T::one());
+    v.extend((j + 1..factors.rows).map(|i| factors.data[i * n + j]));
+    v
+}
+
+/// `C -= tau v (v^H C)` over columns `cols` of the row-major `c`, whose first
+/// row lines up with `v[0]`
+fn reflect<T: Field>(v: &[T], tau: T, c: &mut [T], ldc: usize, cols: Range<usize>) {
+    if tau == T::zero() || cols.is_empty() {
+        return;
+    }
+    let mut w = vec![T::zero(); cols.len()];
+    for (r, &vr) in v.iter().enumerate() {
+        let start = r * ldc;
+        axpy(&mut w, vr.conj(), &c[start + cols.start..start + cols.end]);
+    }
+    for (r, &vr) in v.iter().enumerate() {
+        let start = r * ldc;
+        axpy(
+            &mut c[start + cols.start..start + cols.end],
+            -(tau * vr),
+            &w,
+        );
+    }
+}
+
+/// Apply the panel's reflectors `H_kb ... H_{ke-1}`, conjugate-transposed, to
+/// columns `ke..n` through the compact WY form `I - V T^H V^H`
+fn update_trailing<T: Field>(
+    factors: &mut Matrix<T>,
+    tau: &[T],
+    kb: usize,
+    ke: usize,
+    tiles: &TileConfig,
+) {
+    let (m, n) = (factors.rows, factors.cols);
+    let (mb, nb, nc) = (m - kb, ke - kb, n - ke);
+
+    // V: unit lower-trapezoidal mb x nb, and its conjugate transpose
+    let mut v = vec![T::zero(); mb * nb];
+    let mut vh = vec![T::zero(); nb * mb];
+    for r in 0..mb {
+        for p in 0..nb.min(r + 1) {
+            let value = if r == p {
+                T::one()
+            } else {
+                factors.data[(kb + r) * n + kb + p]
+            };
+            v[r * nb + p] = value;
+            vh[p * mb + r] = value.conj();
+        }
+    }
+
+    // T: upper triangular with H_kb ... H_{ke-1} = I - V T V^H
+    let mut t = vec![T::zero(); nb * nb];
+    for i in 0..nb {
+        t[i * nb + i] = tau[i];
+        // z = V[:, 0..i]^H v_i, then T[0..i, i] = -tau_i T[0..i, 0..i] z
+        let z: Vec<T> = (0..i)
+            .map(|p| (i..mb).fold(T::zero(), |acc, r| acc + vh[p * mb + r] * v[r * nb + i]))
+            .collect();
+        for row in 0..i {
+            let mut sum = T::zero();
+            for col in row..i {
+                sum += t[row * nb + col] * z[col];
+            }
+            t[row * nb + i] = -tau[i] * sum;
+        }
+    }
+    let mut th = vec![T::zero(); nb * nb];
+    for row in 0..nb {
+        for col in 0..nb {
+            th[col * nb + row] = t[row * nb + col].conj();
+        }
+    }
+
+    // C -= V (T^H (V^H C))
+    let mut w = vec![T::zero(); nb * nc];
+    let c = Strided {
+        data: &factors.data[kb * n + ke..],
+        row_stride: n,
+        col_stride: 1,
+    };
+    kernel::gemm(
+        nb,
+        nc,
+        mb,
+        T::one(),
+        Strided::row_major(&vh, mb),
+        c,
+        &mut w,
+        nc,
+        tiles,
+    );
+    let mut w2 = vec![T::zero(); nb * nc];
+    kernel::gemm(
+        nb,
+        nc,
+        nb,
+        T::one(),
+        Strided::row_major(&th, nb),
+        Strided::row_major(&w, nc),
+        &mut w2,
+        nc,
+        tiles,
+    );
+    kernel::gemm(
+        mb,
+        nc,
+        nb,
+        -T::one(),
+        Strided::row_major(&v, nb),
+        Strided::row_major(&w2, nc),
+        &mut factors.data[kb * n + ke..],
+        n,
+        tiles,
+    );
+}
+
+/// Euclidean norm of column `c` from row `start` down
+fn column_norm<T: Field>(matrix: &Matrix<T>, start: usize, c: usize) -> T::Real {
+    let mut sum = T::Real::zero();
+    for i in start..matrix.rows {
+        let x = matrix.data[i * matrix.cols + c].abs();
+        sum += x * x;
+    }
+    sum.sqrt()
+}
@@ -0,0 +1,308 @@
+//! SIMD microkernels for the blocked GEMM kernel
+//!
+//! The binary is built for baseline x86-64, so wider instruction sets are
+//! picked at runtime: the best level supported by the CPU is detected once and
+//! can be lowered (never raised) with the `MATRIX_SIMD` environment variable.
+//! [`scalar_microkernel`] is the portable fallback for every element type and
+//! the reference the `f64` and `f32` SIMD variants are tested against.
+
+use crate::element::Element;
+use crate::kernel::{MR, NR};
+use std::env;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Environment variable that caps the SIMD level (`scalar`, `sse2`, `avx2`, `avx512`)
+pub const SIMD_ENV_VAR: &str = "MATRIX_SIMD";
+
+/// Instruction set used by the microkernel, ordered from narrowest to widest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SimdLevel {
+    Scalar,
+    Sse2,
+    /// AVX2 together with FMA
+    Avx2,
+    Avx512,
+}
+
+impl SimdLevel {
+    /// Widest level supported by the running CPU
+    pub fn detect() -> SimdLevel {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx512f") {
+                return SimdLevel::Avx512;
+            }
+            if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+                return SimdLevel::Avx2;
+            }
+            if is_x86_feature_detected!("sse2") {
+                return SimdLevel::Sse2;
+            }
+        }
+        SimdLevel::Scalar
+    }
+
+    /// Level used by the kernel: the detected level, capped by `MATRIX_SIMD`
+    pub fn active() -> SimdLevel {
+        static ACTIVE: OnceLock<SimdLevel> = OnceLock::new();
+        *ACTIVE.get_or_init(|| {
+            let detected = SimdLevel::detect();
+            env::var(SIMD_ENV_VAR)
+                .ok()
+                .and_then(|value| SimdLevel::parse(&value))
+                .map_or(detected, |requested| requested.min(detected))
+        })
+    }
+
+    /// Parse a level name as accepted by `MATRIX_SIMD`
+    pub fn parse(name: &str) -> Option<SimdLevel> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "scalar" | "none" => Some(SimdLevel::Scalar),
+            "sse2" => Some(SimdLevel::Sse2),
+            "avx2" | "fma" => Some(SimdLevel::Avx2),
+            "avx512" | "avx512f" => Some(SimdLevel::Avx512),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SimdLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SimdLevel::Scalar => "scalar",
+            SimdLevel::Sse2 => "SSE2",
+            SimdLevel::Avx2 => "AVX2+FMA",
+            SimdLevel::Avx512 => "AVX-512",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Microkernel signature: `C[0..mr, 0..nr] += A_panel * B_panel`
+///
+/// `a` holds `kb` columns of `MR` values, `b` holds `kb` rows of `NR` values.
+pub type Microkernel<T> = fn(usize, &[T], &[T], &mut [T], usize, usize, usize);
+
+/// Microkernel for element type `T` at `level`
+pub fn microkernel<T: Element>(level: SimdLevel) -> Microkernel<T> {
+    T::microkernel(level)
+}
+
+/// `f64` microkernel for `level`, clamped to what the CPU actually supports
+pub fn f64_microkernel(level: SimdLevel) -> Microkernel<f64> {
+    match level.min(SimdLevel::detect()) {
+        SimdLevel::Scalar => scalar_microkernel::<f64>,
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Sse2 => x86::f64_sse2,
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => x86::f64_avx2,
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx512 => x86::f64_avx512,
+        #[cfg(not(target_arch = "x86_64"))]
+        _ => scalar_microkernel::<f64>,
+    }
+}
+
+/// `f32` microkernel for `level`, clamped to what the CPU actually supports
+///
+/// A tile row of `NR` floats fills one AVX2 register, so AVX-512 machines use
+/// the AVX2 kernel.
+pub fn f32_microkernel(level: SimdLevel) -> Microkernel<f32> {
+    match level.min(SimdLevel::detect()) {
+        SimdLevel::Scalar => scalar_microkernel::<f32>,
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Sse2 => x86::f32_sse2,
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 | SimdLevel::Avx512 => x86::f32_avx2,
+        #[cfg(not(target_arch = "x86_64"))]
+        _ => scalar_microkernel::<f32>,
+    }
+}
+
+/// Portable reference microkernel
+pub fn scalar_microkernel<T: Element>(
+    kb: usize,
+    a: &[T],
+    b: &[T],
+    c: &mut [T],
+    ldc: usize,
+    mr: usize,
+    nr: usize,
+) {
+    let mut acc = [[T::zero(); NR]; MR];
+    for p in 0..kb {
+        let a_col = &a[p * MR..p * MR + MR];
+        let b_row = &b[p * NR..p * NR + NR];
+        for i in 0..MR {
+            for j in 0..NR {
+                acc[i][j] += a_col[i] * b_row[j];
+            }
+        }
+    }
+    write_back(&acc, c, ldc, mr, nr);
+}
+
+/// Add the `mr x nr` corner of an accumulator tile into C
+fn write_back<T: Element>(acc: &[[T; NR]; MR], c: &mut [T], ldc: usize, mr: usize, nr: usize) {
+    for (i, acc_row) in acc.iter().enumerate().take(mr) {
+        let c_row = &mut c[i * ldc..i * ldc + nr];
+        for (c_val, &acc_val) in c_row.iter_mut().zip(acc_row) {
+            *c_val += acc_val;
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use super::{write_back, MR, NR};
+    use std::arch::x86_64::*;
+
+    /// Safe entry point around an `unsafe` tile function
+    macro_rules! safe_kernel {
+        ($name:ident, $ty:ty, $tile:ident) => {
+            pub(super) fn $name(
+                kb: usize,
+                a: &[$ty],
+                b: &[$ty],
+                c: &mut [$ty],
+                ldc: usize,
+                mr: usize,
+                nr: usize,
+            ) {
+                assert!(a.len() >= kb * MR && b.len() >= kb * NR);
+                let mut acc = [[0.0; NR]; MR];
+                // SAFETY: panel lengths are checked above, and the dispatchers only
+                // return this kernel when the CPU supports the required features
+                unsafe { $tile(kb, a.as_ptr(), b.as_ptr(), &mut acc) };
+                write_back(&acc, c, ldc, mr, nr);
+            }
+        };
+    }
+
+    safe_kernel!(f64_sse2, f64, f64_sse2_tile);
+    safe_kernel!(f64_avx2, f64, f64_avx2_tile);
+    safe_kernel!(f64_avx512, f64, f64_avx512_tile);
+    safe_kernel!(f32_sse2, f32, f32_sse2_tile);
+    safe_kernel!(f32_avx2, f32, f32_avx2_tile);
+
+    /// Two doubles per register: each row of the tile needs four accumulators
+    #[target_feature(enable = "sse2")]
+    unsafe fn f64_sse2_tile(kb: usize, a: *const f64, b: *const f64, acc: &mut [[f64; NR]; MR]) {
+        let mut c = [[_mm_setzero_pd(); NR / 2]; MR];
+        for p in 0..kb {
+            let b_row = b.add(p * NR);
+            let b_vec = [
+                _mm_loadu_pd(b_row),
+                _mm_loadu_pd(b_row.add(2)),
+                _mm_loadu_pd(b_row.add(4)),
+                _mm_loadu_pd(b_row.add(6)),
+            ];
+            for (i, c_row) in c.iter_mut().enumerate() {
+                let a_val = _mm_set1_pd(*a.add(p * MR + i));
+                for (c_vec, &b_val) in c_row.iter_mut().zip(&b_vec) {
+                    *c_vec = _mm_add_pd(*c_vec, _mm_mul_pd(a_val, b_val));
+                }
+            }
+        }
+        for (acc_row, c_row) in acc.iter_mut().zip(&c) {
+            for (j, &c_vec) in c_row.iter().enumerate() {
+                _mm_storeu_pd(acc_row.as_mut_ptr().add(2 * j), c_vec);
+            }
+        }
+    }
+
+    /// Four doubles per register: two fused multiply-adds per row and step
+    #[target_feature(enable = "avx2,fma")]
+    unsafe fn f64_avx2_tile(kb: usize, a: *const f64, b: *const f64, acc: &mut [[f64; NR]; MR]) {
+        let mut c = [[_mm256_setzero_pd(); 2]; MR];
+        for p in 0..kb {
+            let b_row = b.add(p * NR);
+            let b_lo = _mm256_loadu_pd(b_row);
+            let b_hi = _mm256_loadu_pd(b_row.add(4));
+            for (i, c_row) in c.iter_mut().enumerate() {
+                let a_val = _mm256_broadcast_sd(&*a.add(p * MR + i));
+                c_row[0] = _mm256_fmadd_pd(a_val, b_lo, c_row[0]);
+                c_row[1] = _mm256_fmadd_pd(a_val, b_hi, c_row[1]);
+            }
+        }
+        for (acc_row, c_row) in acc.iter_mut().zip(&c) {
+            _mm256_storeu_pd(acc_row.as_mut_ptr(), c_row[0]);
+            _mm256_storeu_pd(acc_row.as_mut_ptr().add(4), c_row[1]);
+        }
+    }
+
+    /// A whole tile row fits in one register, so even and odd steps of `p`
+    /// use separate accumulators to keep enough FMAs in flight
+    #[target_feature(enable = "avx512f")]
+    unsafe fn f64_avx512_tile(kb: usize, a: *const f64, b: *const f64, acc: &mut [[f64; NR]; MR]) {
+        let mut even = [_mm512_setzero_pd(); MR];
+        let mut odd = [_mm512_setzero_pd(); MR];
+        let mut p = 0;
+        while p + 1 < kb {
+            let b_even = _mm512_loadu_pd(b.add(p * NR));
+            let b_odd = _mm512_loadu_pd(b.add((p + 1) * NR));
+            for i in 0..MR {
+                even[i] = _mm512_fmadd_pd(_mm512_set1_pd(*a.add(p * MR + i)), b_even, even[i]);
+                odd[i] = _mm512_fmadd_pd(_mm512_set1_pd(*a.add((p + 1) * MR + i)), b_odd, odd[i]);
+            }
+            p += 2;
+        }
+        if p < kb {
+            let b_last = _mm512_loadu_pd(b.add(p * NR));
+            for (i, acc_vec) in even.iter_mut().enumerate() {
+                *acc_vec = _mm512_fmadd_pd(_mm512_set1_pd(*a.add(p * MR + i)), b_last, *acc_vec);
+            }
+        }
+        for ((acc_row, &e), &o) in acc.iter_mut().zip(&even).zip(&odd) {
+            _mm512_storeu_pd(acc_row.as_mut_ptr(), _mm512_add_pd(e, o));
+        }
+    }
+
+    /// Four floats per register: each row of the tile needs two accumulators
+    #[target_feature(enable = "sse")]
+    unsafe fn f32_sse2_tile(kb: usize, a: *const f32, b: *const f32, acc: &mut [[f32; NR]; MR]) {
+        let mut c = [[_mm_setzero_ps(); 2]; MR];
+        for p in 0..kb {
+            let b_row = b.add(p * NR);
+            let b_lo = _mm_loadu_ps(b_row);
+            let b_hi = _mm_loadu_ps(b_row.add(4));
+            for (i, c_row) in c.iter_mut().enumerate() {
+                let a_val = _mm_set1_ps(*a.add(p * MR + i));
+                c_row[0] = _mm_add_ps(c_row[0], _mm_mul_ps(a_val, b_lo));
+                c_row[1] = _mm_add_ps(c_row[1], _mm_mul_ps(a_val, b_hi));
+            }
+        }
+        for (acc_row, c_row) in acc.iter_mut().zip(&c) {
+            _mm_storeu_ps(acc_row.as_mut_ptr(), c_row[0]);
+            _mm_storeu_ps(acc_row.as_mut_ptr().add(4), c_row[1]);
+        }
+    }
+
+    /// A tile row fills one register, so even and odd steps of `p` use
+    /// separate accumulators as in the `f64` AVX-512 kernel
+    #[target_feature(enable = "avx2,fma")]
+    unsafe fn f32_avx2_tile(kb: usize, a: *const f32, b: *const f32, acc: &mut [[f32; NR]; MR]) {
+        let mut even = [_mm256_setzero_ps(); MR];
+        let mut odd = [_mm256_setzero_ps(); MR];
+        let mut p = 0;
+        while p + 1 < kb {
+            let b_even = _mm256_loadu_ps(b.add(p * NR));
+            let b_odd = _mm256_loadu_ps(b.add((p + 1) * NR));
+            for i in 0..MR {
+                even[i] = _mm256_fmadd_ps(_mm256_set1_ps(*a.add(p * MR + i)), b_even, even[i]);
+                odd[i] = _mm256_fmadd_ps(_mm256_set1_ps(*a.add((p + 1) * MR + i)), b_odd, odd[i]);
+            }
+            p += 2;
+        }
+        if p < kb {
+            let b_last = _mm256_loadu_ps(b.add(p * NR));
+            for (i, acc_vec) in even.iter_mut().enumerate() {
+                *acc_vec = _mm256_fmadd_ps(_mm256_set1_ps(*a.add(p * MR + i)), b_last, *acc_vec);
+            }
+        }
+        for ((acc_row, &e), &o) in acc.iter_mut().zip(&even).zip(&odd) {
+            _mm256_storeu_ps(acc_row.as_mut_ptr(), _mm256_add_ps(e, o));
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Strassen-Winograd multiplication
+//!
+//! Each level splits A, B and C into quadrants and forms the product with 7
+//! half-size multiplications and 15 additions. Odd dimensions are handled by
+//! dynamic peeling: the even-sized core is recursed on and the leftover row,
+//! column and rank-1 update go through the blocked kernel. Recursion stops once
+//! any dimension drops to the crossover size.
+
+use crate::element::Element;
+use crate::kernel::{self, Strided, TileConfig};
+use rayon::ThreadPool;
+
+/// Settings shared by every level of the recursion
+pub struct Strassen<'p> {
+    pub crossover: usize,
+    pub tiles: TileConfig,
+    /// Pool used by the blocked kernel at the leaves, if any
+    pub pool: Option<&'p ThreadPool>,
+}
+
+impl Strassen<'_> {
+    /// Compute `C += alpha * A * B` where A is `m x k`, B is `k x n` and C is
+    /// row-major with leading dimension `ldc`
+    #[allow(clippy::too_many_arguments)]
+    pub fn multiply<T: Element>(
+        &self,
+        m: usize,
+        k: usize,
+        n: usize,
+        alpha: T,
+        a: Strided<'_, T>,
+        b: Strided<'_, T>,
+        c: &mut [T],
+        ldc: usize,
+    ) {
+        let (m2, k2, n2) = (m & !1, k & !1, n & !1);
+        if m.min(k).min(n) <= self.crossover || m2 == 0 || k2 == 0 || n2 == 0 {
+            self.base(m, k, n, alpha, a, b, c, ldc);
+            return;
+        }
+
+        self.winograd_step(m2, k2, n2, alpha, a, b, c, ldc);
+
+        // Peel off whatever the even-sized core did not cover
+        if k2 < k {
+            self.base(m2, 1, n2, alpha, a.block(0, k2), b.block(k2, 0), c, ldc);
+        }
+        if n2 < n {
+            self.base(m2, k, 1, alpha, a, b.block(0, n2), &mut c[n2..], ldc);
+        }
+        if m2 < m {
+            self.base(1, k, n, alpha, a.block(m2, 0), b, &mut c[m2 * ldc..], ldc);
+        }
+    }
+
+    /// One level of Strassen-Winograd on even `m`, `k` and `n`; every product
+    /// carries `alpha`, so the combination step needs no extra scaling
+    #[allow(clippy::too_many_arguments)]
+    fn winograd_step<T: Element>(
+        &self,
+        m: usize,
+        k: usize,
+        n: usize,
+        alpha: T,
+        a: Strided<'_, T>,
+        b: Strided<'_, T>,
+        c: &mut [T],
+        ldc: usize,
+    ) {
+        let (mh, kh, nh) = (m / 2, k / 2, n / 2);
+        let (a11, a12, a21, a22) = (a, a.block(0, kh), a.block(mh, 0), a.block(mh, kh));
+        let (b11, b12, b21, b22) = (b, b.block(0, nh), b.block(kh, 0), b.block(kh, nh));
+
+        let s1 = add(mh, kh, a21, a22);
+        let s2 = sub(mh, kh, dense(&s1, kh), a11);
+        let s3 = sub(mh, kh, a11, a21);
+        let s4 = sub(mh, kh, a12, dense(&s2, kh));
+        let t1 = sub(kh, nh, b12, b11);
+        let t2 = sub(kh, nh, b22, dense(&t1, nh));
+        let t3 = sub(kh, nh, b22, b12);
+        let t4 = sub(kh, nh, dense(&t2, nh), b21);
+
+        let product = |x: Strided<'_, T>, y: Strided<'_, T>| {
+            let mut p = vec![T::zero(); mh * nh];
+            self.multiply(mh, kh, nh, alpha, x, y, &mut p, nh);
+            p
+        };
+        let p1 = product(a11, b11);
+        let p2 = product(a12, b21);
+        let p3 = product(dense(&s4, kh), b22);
+        let p4 = product(a22, dense(&t4, nh));
+        let p5 = product(dense(&s1, kh), dense(&t1, nh));
+        let p6 = product(dense(&s2, kh), dense(&t2, nh));
+        let p7 = product(dense(&s3, kh), dense(&t3, nh));
+
+        for i in 0..mh {
+            for j in 0..nh {
+                let x = i * nh + j;
+                let u2 = p1[x] + p6[x];
+                let u3 = u2 + p7[x];
+                let u4 = u2 + p5[x];
+                c[i * ldc + j] += p1[x] + p2[x];
+                c[i * ldc + nh + j] += u4 + p3[x];
+                c[(mh + i) * ldc + j] += u3 - p4[x];
+                c[(mh + i) * ldc + nh + j] += u3 + p5[x];
+            }
+        }
+    }
+
+    /// Hand a product to the blocked kernel
+    #[allow(clippy::too_many_arguments)]
+    fn base<T: Element>(
+        &self,
+        m: usize,
+        k: usize,
+        n: usize,
+        alpha: T,
+        a: Strided<'_, T>,
+        b: Strided<'_, T>,
+        c: &mut [T],
+        ldc: usize,
+    ) {
+        match self.pool {
+            Some(pool) => kernel::gemm_parallel(m, n, k, alpha, a, b, c, ldc, &self.tiles, pool),
+            None => kernel::gemm(m, n, k, alpha, a, b, c, ldc, &self.tiles),
+        }
+    }
+}
+
+/// Dense `rows x cols` buffer holding `x + y`
+fn add<T: Element>(rows: usize, cols: usize, x: Strided<'_, T>, y: Strided<'_, T>) -> Vec<T> {
+    let mut out = Vec::with_capacity(rows * cols);
+    for i in 0..rows {
+        for j in 0..cols {
+            out.push(x.at(i, j) + y.at(i, j));
+        }
+    }
+    out
+}
+
+/// Dense `rows x cols` buffer holding `x - y`
+fn sub<T: Element>(rows: usize, cols: usize, x: Strided<'_, T>, y: Strided<'_, T>) -> Vec<T> {
+    let mut out = Vec::with_capacity(rows * cols);
+    for i in 0..rows {
+        for j in 0..cols {
+            out.push(x.at(i, j) - y.at(i, j));
+        }
+    }
+    out
+}
+
+fn dense<T: Element>(data: &[T], cols: usize) -> Strided<'_, T> {
+    Strided::row_major(data, cols)
+}
@@ -0,0 +1,93 @@
+//! Matrix transposition
+//!
+//! Out-of-place copies walk the source in square tiles so that both the
+//! strided reads and the dense writes stay within a few cache lines. Square
+//! matrices are transposed in place by swapping mirrored tiles; rectangular
+//! ones by following the cycles of the index permutation, which needs one bit
+//! of bookkeeping per element instead of a second buffer.
+
+use crate::element::Element;
+use crate::view::MatrixView;
+
+/// Tile edge used by the blocked transposes
+pub const BLOCK: usize = 32;
+
+/// Copy `src` into the dense row-major `dst`, one `BLOCK x BLOCK` tile at a time
+pub fn copy_blocked<T: Element>(src: MatrixView<'_, T>, dst: &mut [T]) {
+    let (rows, cols) = (src.rows(), src.cols());
+    assert_eq!(dst.len(), rows * cols, "destination has the wrong length");
+    for ib in (0..rows).step_by(BLOCK) {
+        let i_end = (ib + BLOCK).min(rows);
+        for jb in (0..cols).step_by(BLOCK) {
+            let j_end = (jb + BLOCK).min(cols);
+            for i in ib..i_end {
+                for j in jb..j_end {
+                    dst[i * cols + j] = src.at(i, j);
+                }
+            }
+        }
+    }
+}
+
+/// Write the transpose of `src` into the dense row-major `dst`, which holds
+/// `src.cols() x src.rows()` elements
+pub fn transpose_into<T: Element>(src: MatrixView<'_, T>, dst: &mut [T]) {
+    copy_blocked(src.transpose(), dst);
+}
+
+/// Transpose the row-major `n x n` matrix in `data` in place
+pub fn transpose_square_in_place<T>(data: &mut [T], n: usize) {
+    assert_eq!(data.len(), n * n, "data is not n x n");
+    for ib in (0..n).step_by(BLOCK) {
+        let i_end = (ib + BLOCK).min(n);
+        for jb in (ib..n).step_by(BLOCK) {
+            let j_end = (jb + BLOCK).min(n);
+            for i in ib..i_end {
+                // Diagonal tiles only swap their upper triangle
+                let j_start = if ib == jb { i + 1 } else { jb };
+                for j in j_start..j_end {
+                    data.swap(i * n + j, j * n + i);
+                }
+            }
+        }
+    }
+}
+
+/// Transpose the row-major `rows x cols` matrix in `data` in place, leaving
+/// it as a row-major `cols x rows` matrix
+///
+/// Element `k` moves to `k * rows mod (len - 1)`; each cycle of that
+/// permutation is rotated once, with a bitset marking visited positions.
+pub fn transpose_in_place<T>(data: &mut [T], rows: usize, cols: usize) {
+    assert_eq!(data.len(), rows * cols, "data is not rows x cols");
+    if rows == cols {
+        transpose_square_in_place(data, rows);
+        return;
+    }
+    if rows <= 1 || cols <= 1 {
+        // A single row or column has the same layout as its transpose
+        return;
+    }
+
+    // The first and last elements never move
+    let last = data.len() - 1;
+    let target = |k: usize| ((k as u128 * rows as u128) % last as u128) as usize;
+    let mut visited = vec![0u64; data.len().div_ceil(64)];
+
+    for start in 1..last {
+        if visited[start / 64] & (1 << (start % 64)) != 0 {
+            continue;
+        }
+        let mut k = start;
+        loop {
+            visited[k / 64] |= 1 << (k % 64);
+            let next = target(k);
+            if next == start {
+                break;
+            }
+            // Carry the element from `start` along the cycle
+            data.swap(start, next);
+            k = next;
+        }
+    }
+}
@@ -0,0 +1,396 @@
+//! Borrowed, strided views of matrix data
+//!
+//! A view addresses element `(i, j)` at `data[i * row_stride + j * col_stride]`,
+//! so row/column chunks, arbitrary 2D blocks and transposes of a [`Matrix`]
+//! can be handed to the kernels, MPI and the file writers without copying.
+
+use crate::element::Element;
+use crate::error::{Error, Result};
+use crate::kernel::Strided;
+use crate::matrix::Matrix;
+use crate::transpose;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Read-only view of a `rows x cols` region
+#[derive(Debug, Clone, Copy)]
+pub struct MatrixView<'a, T = f64> {
+    data: &'a [T],
+    rows: usize,
+    cols: usize,
+    row_stride: usize,
+    col_stride: usize,
+}
+
+/// Mutable view of a `rows x cols` region
+#[derive(Debug)]
+pub struct MatrixViewMut<'a, T = f64> {
+    data: &'a mut [T],
+    rows: usize,
+    cols: usize,
+    row_stride: usize,
+    col_stride: usize,
+}
+
+/// Check that every element of a strided region lies inside `len`
+fn check_extent(
+    len: usize,
+    rows: usize,
+    cols: usize,
+    row_stride: usize,
+    col_stride: usize,
+) -> Result<()> {
+    if rows == 0 || cols == 0 {
+        return Ok(());
+    }
+    let last = (rows - 1) * row_stride + (cols - 1) * col_stride;
+    if last >= len {
+        return Err(Error::ViewExtent {
+            rows,
+            cols,
+            row_stride,
+            col_stride,
+            len,
+        });
+    }
+    Ok(())
+}
+
+fn check_block(
+    rows: usize,
+    cols: usize,
+    row: usize,
+    col: usize,
+    num_rows: usize,
+    num_cols: usize,
+) -> Result<()> {
+    if row + num_rows > rows || col + num_cols > cols {
+        return Err(Error::OutOfBounds {
+            start: (row, col),
+            size: (num_rows, num_cols),
+            dims: (rows, cols),
+        });
+    }
+    Ok(())
+}
+
+/// Offset of `(row, col)`, clamped so that empty blocks at the edge stay in bounds
+fn block_offset(len: usize, row: usize, col: usize, row_stride: usize, col_stride: usize) -> usize {
+    (row * row_stride + col * col_stride).min(len)
+}
+
+impl<'a, T: Element> MatrixView<'a, T> {
+    /// View `data` as a `rows x cols` matrix with the given strides
+    pub fn new(
+        data: &'a [T],
+        rows: usize,
+        cols: usize,
+        row_stride: usize,
+        col_stride: usize,
+    ) -> Result<Self> {
+        check_extent(data.len(), rows, cols, row_stride, col_stride)?;
+        Ok(MatrixView {
+            data,
+            rows,
+            cols,
+            row_stride,
+            col_stride,
+        })
+    }
+
+    /// View a dense row-major buffer
+    pub fn row_major(data: &'a [T], rows: usize, cols: usize) -> Result<Self> {
+        if data.len() != rows * cols {
+            return Err(Error::DataLength {
+                len: data.len(),
+                rows,
+                cols,
+            });
+        }
+        MatrixView::new(data, rows, cols, cols, 1)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row_stride(&self) -> usize {
+        self.row_stride
+    }
+
+    pub fn col_stride(&self) -> usize {
+        self.col_stride
+    }
+
+    /// Get a value at a specific position
+    pub fn get(&self, row: usize, col: usize) -> Result<T> {
+        check_block(self.rows, self.cols, row, col, 1, 1)?;
+        Ok(self.at(row, col))
+    }
+
+    #[inline(always)]
+    pub(crate) fn at(&self, row: usize, col: usize) -> T {
+        self.data[row * self.row_stride + col * self.col_stride]
+    }
+
+    /// View of the `num_rows x num_cols` block starting at `(row, col)`
+    pub fn block(
+        &self,
+        row: usize,
+        col: usize,
+        num_rows: usize,
+        num_cols: usize,
+    ) -> Result<MatrixView<'a, T>> {
+        check_block(self.rows, self.cols, row, col, num_rows, num_cols)?;
+        let offset = block_offset(self.data.len(), row, col, self.row_stride, self.col_stride);
+        Ok(MatrixView {
+            data: &self.data[offset..],
+            rows: num_rows,
+            cols: num_cols,
+            row_stride: self.row_stride,
+            col_stride: self.col_stride,
+        })
+    }
+
+    /// View of rows `[start_row, start_row + num_rows)`
+    pub fn row_chunk(&self, start_row: usize, num_rows: usize) -> Result<MatrixView<'a, T>> {
+        self.block(start_row, 0, num_rows, self.cols)
+    }
+
+    /// View of columns `[start_col, start_col + num_cols)`
+    pub fn col_chunk(&self, start_col: usize, num_cols: usize) -> Result<MatrixView<'a, T>> {
+        self.block(0, start_col, self.rows, num_cols)
+    }
+
+    /// Transposed view of the same data
+    pub fn transpose(&self) -> MatrixView<'a, T> {
+        MatrixView {
+            data: self.data,
+            rows: self.cols,
+            cols: self.rows,
+            row_stride: self.col_stride,
+            col_stride: self.row_stride,
+        }
+    }
+
+    /// Whether the view is dense row-major, i.e. can be used as one slice
+    pub fn is_contiguous(&self) -> bool {
+        self.rows == 0
+            || self.cols == 0
+            || (self.cols == 1 || self.col_stride == 1)
+                && (self.rows == 1 || self.row_stride == self.cols)
+    }
+
+    /// The viewed elements as one row-major slice, if the view is contiguous
+    pub fn as_slice(&self) -> Option<&'a [T]> {
+        if !self.is_contiguous() {
+            return None;
+        }
+        Some(&self.data[..self.rows * self.cols])
+    }
+
+    /// Row `row` as a slice, if its elements are adjacent
+    pub fn row(&self, row: usize) -> Option<&'a [T]> {
+        if row >= self.rows || (self.col_stride != 1 && self.cols > 1) {
+            return None;
+        }
+        let start = row * self.row_stride;
+        Some(&self.data[start..start + self.cols])
+    }
+
+    /// Copy the viewed elements into a new dense matrix
+    pub fn to_matrix(self) -> Matrix<T> {
+        if let Some(slice) = self.as_slice() {
+            return Matrix {
+                data: slice.to_vec(),
+                rows: self.rows,
+                cols: self.cols,
+            };
+        }
+        let mut data = vec![T::zero(); self.rows * self.cols];
+        transpose::copy_blocked(self, &mut data);
+        Matrix {
+            data,
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
+    /// Operand for the blocked kernel
+    pub(crate) fn strided(&self) -> Strided<'a, T> {
+        Strided {
+            data: self.data,
+            row_stride: self.row_stride,
+            col_stride: self.col_stride,
+        }
+    }
+
+    /// Save the viewed elements to a text file
+    /// Format: space-separated values, one row per line
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let write = || -> io::Result<()> {
+            let mut writer = BufWriter::new(File::create(path)?);
+            self.write_text(&mut writer)?;
+            writer.flush()
+        };
+        write().map_err(|e| Error::io(Some(path), e))
+    }
+
+    /// Write the viewed elements in the text format
+    pub fn write_text<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                if j > 0 {
+                    write!(writer, " ")?;
+                }
+                write!(writer, "{}", self.at(i, j))?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T: Element> From<&'a Matrix<T>> for MatrixView<'a, T> {
+    fn from(matrix: &'a Matrix<T>) -> Self {
+        matrix.view()
+    }
+}
+
+impl<'a, T: Element> MatrixViewMut<'a, T> {
+    /// Mutably view `data` as a `rows x cols` matrix with the given strides
+    pub fn new(
+        data: &'a mut [T],
+        rows: usize,
+        cols: usize,
+        row_stride: usize,
+        col_stride: usize,
+    ) -> Result<Self> {
+        check_extent(data.len(), rows, cols, row_stride, col_stride)?;
+        Ok(MatrixViewMut {
+            data,
+            rows,
+            cols,
+            row_stride,
+            col_stride,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row_stride(&self) -> usize {
+        self.row_stride
+    }
+
+    pub fn col_stride(&self) -> usize {
+        self.col_stride
+    }
+
+    /// Read-only view of the same region
+    pub fn as_view(&self) -> MatrixView<'_, T> {
+        MatrixView {
+            data: self.data,
+            rows: self.rows,
+            cols: self.cols,
+            row_stride: self.row_stride,
+            col_stride: self.col_stride,
+        }
+    }
+
+    /// Get a value at a specific position
+    pub fn get(&self, row: usize, col: usize) -> Result<T> {
+        self.as_view().get(row, col)
+    }
+
+    /// Set a value at a specific position
+    pub fn set(&mut self, row: usize, col: usize, value: T) -> Result<()> {
+        check_block(self.rows, self.cols, row, col, 1, 1)?;
+        *self.at_mut(row, col) = value;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub(crate) fn at_mut(&mut self, row: usize, col: usize) -> &mut T {
+        &mut self.data[row * self.row_stride + col * self.col_stride]
+    }
+
+    /// Mutable view of the `num_rows x num_cols` block starting at `(row, col)`
+    pub fn block_mut(
+        &mut self,
+        row: usize,
+        col: usize,
+        num_rows: usize,
+        num_cols: usize,
+    ) -> Result<MatrixViewMut<'_, T>> {
+        check_block(self.rows, self.cols, row, col, num_rows, num_cols)?;
+        let offset = block_offset(self.data.len(), row, col, self.row_stride, self.col_stride);
+        Ok(MatrixViewMut {
+            data: &mut self.data[offset..],
+            rows: num_rows,
+            cols: num_cols,
+            row_stride: self.row_stride,
+            col_stride: self.col_stride,
+        })
+    }
+
+    /// Multiply every element by `beta`; `beta = 0` clears the view without
+    /// reading it
+    pub fn scale(&mut self, beta: T) {
+        if beta == T::zero() {
+            self.fill(T::zero());
+        } else if beta != T::one() {
+            for i in 0..self.rows {
+                for j in 0..self.cols {
+                    *self.at_mut(i, j) *= beta;
+                }
+            }
+        }
+    }
+
+    /// Buffer and leading dimension for the kernels, if each row is
+    /// unit-stride and no two rows overlap
+    pub(crate) fn as_row_major_mut(&mut self) -> Option<(&mut [T], usize)> {
+        if (self.col_stride != 1 && self.cols > 1) || self.row_stride < self.cols.max(1) {
+            return None;
+        }
+        Some((&mut *self.data, self.row_stride))
+    }
+
+    /// Set every element to `value`
+    pub fn fill(&mut self, value: T) {
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                *self.at_mut(i, j) = value;
+            }
+        }
+    }
+
+    /// Copy `source` into this view; the shapes must match
+    pub fn copy_from(&mut self, source: &MatrixView<'_, T>) -> Result<()> {
+        if source.rows != self.rows || source.cols != self.cols {
+            return Err(Error::DimensionMismatch {
+                operation: "copy",
+                left: (self.rows, self.cols),
+                right: (source.rows, source.cols),
+            });
+        }
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                *self.at_mut(i, j) = source.at(i, j);
+            }
+        }
+        Ok(())
+    }
+}
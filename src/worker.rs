@@ -1,64 +1,214 @@
+use crate::accumulate::Accumulation;
+use crate::element::Element;
+use crate::error::{Error, Result};
+use crate::kernel::{Algorithm, GemmSpec};
 use crate::matrix::Matrix;
 use crate::mpi_utils::*;
+use crate::simd::SimdLevel;
 use mpi::traits::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::env;
+use std::thread;
+
+/// Environment variable that sets the number of compute threads per worker
+pub const THREADS_ENV_VAR: &str = "MATRIX_THREADS";
+
+/// Number of compute threads to use when none is given on the command line:
+/// `MATRIX_THREADS` if set to a positive integer, otherwise every available core
+pub fn default_thread_count() -> usize {
+    env::var(THREADS_ENV_VAR)
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|&threads| threads > 0)
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()))
+}
 
 pub struct Worker<C: Communicator> {
     rank: i32,
     world: C,
+    threads: usize,
+    algorithm: Algorithm,
 }
 
 impl<C: Communicator> Worker<C> {
     pub fn new(world: C) -> Self {
         let rank = world.rank();
-        Worker { rank, world }
+        Worker {
+            rank,
+            world,
+            threads: default_thread_count(),
+            algorithm: Algorithm::default(),
+        }
+    }
+
+    /// Set the number of threads used to compute the assigned row chunk
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Set the algorithm used to multiply the assigned chunk
+    pub fn with_algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
     }
 
     pub fn rank(&self) -> i32 {
         self.rank
     }
 
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
     /// Process work assigned by the coordinator
-    pub fn process_work(&self) -> Result<(), String> {
-        println!("[Worker {}] Waiting for work assignment...", self.rank);
+    pub fn process_work(&self) -> Result<()> {
+        self.process_work_as::<f64>()
+    }
 
-        let (row_start, row_end, col_start, col_end) =
-            receive_work_assignment(&self.world, 0)?;
+    /// Process work with element type `T`, matching the coordinator's
+    /// [`multiply_matrices_as`](crate::coordinator::Coordinator::multiply_matrices_as)
+    /// or [`gemm_as`](crate::coordinator::Coordinator::gemm_as)
+    ///
+    /// Keeps taking assignments for as long as the coordinator flags that
+    /// more follow, as [`power_as`](crate::coordinator::Coordinator::power_as)
+    /// does, holding on to each result in case the next round reuses it.
+    pub fn process_work_as<T: Element>(&self) -> Result<()> {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(self.threads)
+            .build()
+            .map_err(|source| Error::ThreadPool {
+                rank: self.rank,
+                source,
+            })?;
+        let mut previous: Option<Matrix<T>> = None;
 
-        println!(
-            "[Worker {}] Received assignment: rows [{}, {}), cols [{}, {})",
-            self.rank, row_start, row_end, col_start, col_end
-        );
+        loop {
+            println!("[Worker {}] Waiting for work assignment...", self.rank);
+
+            let assignment = receive_work_assignment(&self.world, 0)?;
+
+            println!(
+                "[Worker {}] Received assignment: rows [{}, {}), cols [{}, {})",
+                self.rank,
+                assignment.row_start,
+                assignment.row_end,
+                assignment.col_start,
+                assignment.col_end
+            );
+
+            if assignment.is_empty() {
+                if assignment.more {
+                    println!("[Worker {}] No work assigned this round", self.rank);
+                    continue;
+                }
+                println!("[Worker {}] No work assigned, exiting", self.rank);
+                return Ok(());
+            }
+
+            let result = self.compute(&assignment, previous.take(), &pool)?;
+
+            println!("[Worker {}] Sending result to coordinator...", self.rank);
+            send_result(&self.world, 0, &result)?;
 
-        if row_start >= row_end || col_start >= col_end {
-            println!("[Worker {}] No work assigned, exiting", self.rank);
-            return Ok(());
+            if !assignment.more {
+                println!("[Worker {}] Work complete!", self.rank);
+                return Ok(());
+            }
+            previous = Some(result);
         }
+    }
+
+    /// Receive the operands of `assignment` and compute its result block
+    fn compute<T: Element>(
+        &self,
+        assignment: &WorkAssignment,
+        previous: Option<Matrix<T>>,
+        pool: &ThreadPool,
+    ) -> Result<Matrix<T>> {
+        let [alpha, beta] = receive_scalars::<T, 2>(&self.world, 0)?;
+        let spec = GemmSpec::default()
+            .with_alpha(alpha)
+            .with_beta(beta)
+            .with_trans_a(assignment.trans_a)
+            .with_trans_b(assignment.trans_b)
+            .with_accumulation(assignment.accumulation)
+            .with_reproducible(assignment.reproducible);
 
-        println!("[Worker {}] Receiving row chunk from matrix A...", self.rank);
-        let row_chunk = receive_matrix(&self.world, 0)?;
+        // With a transposed A the chunk holds the matching columns of A
+        let row_chunk = if assignment.reuse_a {
+            println!(
+                "[Worker {}] Reusing previous result as chunk of A",
+                self.rank
+            );
+            previous.ok_or_else(|| Error::Mpi {
+                rank: self.rank,
+                message: "Asked to reuse a previous result, but there is none".to_string(),
+            })?
+        } else {
+            println!("[Worker {}] Receiving chunk of matrix A...", self.rank);
+            receive_matrix::<T>(&self.world, 0)?
+        };
         println!(
-            "[Worker {}] Received row chunk: {}x{}",
-            self.rank, row_chunk.rows, row_chunk.cols
+            "[Worker {}] Received chunk of A: {}x{}{}",
+            self.rank,
+            row_chunk.rows,
+            row_chunk.cols,
+            if spec.trans_a.is_transposed() {
+                " (transposed)"
+            } else {
+                ""
+            }
         );
 
         println!("[Worker {}] Receiving matrix B...", self.rank);
-        let matrix_b = receive_matrix(&self.world, 0)?;
+        let matrix_b = receive_matrix::<T>(&self.world, 0)?;
         println!(
-            "[Worker {}] Received matrix B: {}x{}",
-            self.rank, matrix_b.rows, matrix_b.cols
+            "[Worker {}] Received matrix B: {}x{}{}",
+            self.rank,
+            matrix_b.rows,
+            matrix_b.cols,
+            if spec.trans_b.is_transposed() {
+                " (transposed)"
+            } else {
+                ""
+            }
         );
 
-        println!("[Worker {}] Computing multiplication...", self.rank);
-        let result = Matrix::multiply_chunks(&row_chunk, &matrix_b)?;
+        let mut result = if assignment.accumulate {
+            println!("[Worker {}] Receiving block of matrix C...", self.rank);
+            receive_matrix::<T>(&self.world, 0)?
+        } else {
+            Matrix::new(
+                assignment.row_end - assignment.row_start,
+                assignment.col_end - assignment.col_start,
+            )
+        };
+
+        let kernel = match spec.accumulation {
+            Accumulation::Naive if !spec.reproducible => {
+                format!("{} kernel", SimdLevel::active())
+            }
+            mode if spec.reproducible => format!("reproducible {} accumulation", mode),
+            mode => format!("{} accumulation", mode),
+        };
+        println!(
+            "[Worker {}] Computing multiplication on {} threads ({})...",
+            self.rank, self.threads, kernel
+        );
+        Matrix::gemm_with_algorithm(
+            &spec,
+            &row_chunk,
+            &matrix_b,
+            &mut result.view_mut(),
+            self.algorithm,
+            Some(pool),
+        )?;
         println!(
             "[Worker {}] Computed result: {}x{}",
             self.rank, result.rows, result.cols
         );
 
-        println!("[Worker {}] Sending result to coordinator...", self.rank);
-        send_result(&self.world, 0, &result)?;
-        println!("[Worker {}] Work complete!", self.rank);
-
-        Ok(())
+        Ok(result)
     }
 }
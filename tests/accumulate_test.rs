@@ -0,0 +1,154 @@
+// Tests for the dot-product accumulation modes
+
+use distribiuted_matrix_multiplication::accumulate::Accumulation;
+use distribiuted_matrix_multiplication::element::Element;
+use distribiuted_matrix_multiplication::kernel::{GemmSpec, Transpose};
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use num_complex::Complex;
+use rayon::ThreadPoolBuilder;
+
+fn product<T: Element>(mode: Accumulation, a: &Matrix<T>, b: &Matrix<T>) -> Matrix<T> {
+    Matrix::gemm(&GemmSpec::default().with_accumulation(mode), a, b, None).unwrap()
+}
+
+fn sample(rows: usize, cols: usize, seed: usize) -> Matrix {
+    Matrix::from_vec(
+        (0..rows * cols)
+            .map(|i| ((i * 37 + seed * 11) % 23) as f64 / 7.0 - 1.5)
+            .collect(),
+        rows,
+        cols,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_parse_names_and_aliases() {
+    for mode in Accumulation::ALL {
+        assert_eq!(Accumulation::parse(mode.name()).unwrap(), mode);
+    }
+    assert_eq!(
+        Accumulation::parse("neumaier").unwrap(),
+        Accumulation::Compensated
+    );
+    assert_eq!(
+        Accumulation::parse("dd").unwrap(),
+        Accumulation::DoubleDouble
+    );
+    assert!(Accumulation::parse("exact").is_err());
+    assert_eq!(Accumulation::default(), Accumulation::Naive);
+}
+
+#[test]
+fn test_all_modes_agree_on_well_conditioned_input() {
+    let a = sample(19, 33, 1);
+    let b = sample(33, 13, 2);
+    let expected = a.multiply(&b).unwrap();
+    for mode in Accumulation::ALL {
+        let c = product(mode, &a, &b);
+        assert!((&c - &expected).norm_max() < 1e-12, "{}", mode);
+    }
+}
+
+#[test]
+fn test_cancellation_is_recovered() {
+    // 1e16 + 1 - 1e16: the 1 is lost by a running sum
+    let x = [1e16, 1.0, -1e16];
+    let ones = [1.0; 3];
+    assert_eq!(f64::dot(Accumulation::Naive, &x, &ones), 0.0);
+
+    let a: Matrix = Matrix::from_vec(x.to_vec(), 1, 3).unwrap();
+    let b: Matrix = Matrix::from_vec(ones.to_vec(), 3, 1).unwrap();
+    assert_eq!(product(Accumulation::Compensated, &a, &b).data, vec![1.0]);
+    assert_eq!(product(Accumulation::DoubleDouble, &a, &b).data, vec![1.0]);
+}
+
+#[test]
+fn test_double_double_keeps_product_errors() {
+    // x^2 - (1 + 2^-29) with x = 1 + 2^-30 is exactly 2^-60, but x^2
+    // rounds to 1 + 2^-29 in double precision
+    let x = 1.0 + 2f64.powi(-30);
+    let a: Matrix = Matrix::from_vec(vec![x, -1.0], 1, 2).unwrap();
+    let b: Matrix = Matrix::from_vec(vec![x, 1.0 + 2f64.powi(-29)], 2, 1).unwrap();
+    assert_eq!(product(Accumulation::Compensated, &a, &b).data, vec![0.0]);
+    assert_eq!(
+        product(Accumulation::DoubleDouble, &a, &b).data,
+        vec![2f64.powi(-60)]
+    );
+}
+
+#[test]
+fn test_long_single_precision_sums() {
+    let k = 1 << 20;
+    let a: Matrix<f32> = Matrix::from_vec(vec![0.1; k], 1, k).unwrap();
+    let b: Matrix<f32> = Matrix::from_vec(vec![1.0; k], k, 1).unwrap();
+    let exact = 0.1f32 as f64 * k as f64;
+    let error = |mode| (product(mode, &a, &b).data[0] as f64 - exact).abs() / exact;
+
+    let running = f32::dot(Accumulation::Naive, &a.data, &b.data) as f64;
+    assert!((running - exact).abs() / exact > 1e-4);
+    for mode in [
+        Accumulation::Pairwise,
+        Accumulation::Compensated,
+        Accumulation::DoubleDouble,
+    ] {
+        assert!(error(mode) < 1e-6, "{}: {}", mode, error(mode));
+    }
+}
+
+#[test]
+fn test_gemm_options_and_threads() {
+    let a = sample(17, 9, 3);
+    let b = sample(11, 17, 4);
+    let c = sample(9, 11, 5);
+    let spec = GemmSpec::default()
+        .with_alpha(-0.5)
+        .with_beta(2.0)
+        .with_trans_a(Transpose::Yes)
+        .with_trans_b(Transpose::Yes);
+    let expected = Matrix::gemm(&spec, &a, &b, Some(c.clone())).unwrap();
+
+    let pool = ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+    for mode in Accumulation::ALL {
+        let spec = spec.with_accumulation(mode);
+        let serial = Matrix::gemm(&spec, &a, &b, Some(c.clone())).unwrap();
+        assert!((&serial - &expected).norm_max() < 1e-12, "{}", mode);
+
+        let mut parallel = c.clone();
+        Matrix::gemm_with_algorithm(
+            &spec,
+            &a,
+            &b,
+            &mut parallel.view_mut(),
+            Default::default(),
+            Some(&pool),
+        )
+        .unwrap();
+        assert_eq!(parallel, serial, "{}", mode);
+    }
+}
+
+#[test]
+fn test_integer_and_complex_elements() {
+    let a: Matrix<i64> = Matrix::from_vec(vec![1, -2, 3, 4, 5, -6], 2, 3).unwrap();
+    let b: Matrix<i64> = Matrix::from_vec(vec![7, 8, 9, 10, 11, 12], 3, 2).unwrap();
+    let expected = a.multiply(&b).unwrap();
+    for mode in Accumulation::ALL {
+        assert_eq!(product(mode, &a, &b), expected);
+    }
+
+    let z = |re: f64, im: f64| Complex::new(re, im);
+    let a: Matrix<Complex<f64>> =
+        Matrix::from_vec(vec![z(1e16, 0.0), z(0.0, 1.0), z(-1e16, 0.0)], 1, 3).unwrap();
+    let b: Matrix<Complex<f64>> =
+        Matrix::from_vec(vec![z(1.0, 1.0), z(0.0, 1.0), z(1.0, 1.0)], 3, 1).unwrap();
+    // The i * i = -1 term is lost beside 1e16 unless compensated
+    assert_eq!(
+        Complex::dot(Accumulation::Naive, &a.data, &b.data),
+        z(0.0, 0.0)
+    );
+    assert_eq!(
+        product(Accumulation::Compensated, &a, &b).data,
+        vec![z(-1.0, 0.0)]
+    );
+}
@@ -0,0 +1,190 @@
+// Tests for the native binary matrix format
+
+use distribiuted_matrix_multiplication::binary::{is_binary_file, BinaryWriter, Header, MAGIC};
+use distribiuted_matrix_multiplication::element::{Dtype, Element};
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use num_complex::Complex;
+use std::fs;
+use tempfile::tempdir;
+
+fn round_trip<T: Element>(matrix: &Matrix<T>, checksum: bool) -> Matrix<T> {
+    let mut bytes = Vec::new();
+    matrix.view().write_binary(&mut bytes, checksum).unwrap();
+    let expected_len = Header::SIZE + matrix.data.len() * T::DTYPE.size() + 4 * checksum as usize;
+    assert_eq!(bytes.len(), expected_len);
+    Matrix::read_binary(&bytes[..]).unwrap()
+}
+
+#[test]
+fn test_round_trip_is_exact_for_every_dtype() {
+    let special = vec![
+        0.1,
+        -0.0,
+        f64::INFINITY,
+        f64::MIN_POSITIVE / 3.0,
+        f64::MAX,
+        1.0 / 3.0,
+    ];
+    let m: Matrix = Matrix::from_vec(special, 2, 3).unwrap();
+    let back = round_trip(&m, true);
+    assert!(back
+        .data
+        .iter()
+        .zip(&m.data)
+        .all(|(a, b)| a.to_bits() == b.to_bits()));
+
+    let nan: Matrix = Matrix::from_vec(vec![f64::NAN], 1, 1).unwrap();
+    assert!(round_trip(&nan, false).data[0].is_nan());
+
+    let f: Matrix<f32> = Matrix::from_vec(vec![0.1, -2.5e-38, 7.0], 3, 1).unwrap();
+    assert_eq!(round_trip(&f, false), f);
+    let i: Matrix<i32> = Matrix::from_vec(vec![i32::MIN, -1, 0, i32::MAX], 2, 2).unwrap();
+    assert_eq!(round_trip(&i, true), i);
+    let l: Matrix<i64> = Matrix::from_vec(vec![i64::MIN, 42], 1, 2).unwrap();
+    assert_eq!(round_trip(&l, false), l);
+    let c: Matrix<Complex<f32>> = Matrix::from_vec(
+        vec![Complex::new(1.5, -0.25), Complex::new(-3.0, 8.0)],
+        2,
+        1,
+    )
+    .unwrap();
+    assert_eq!(round_trip(&c, true), c);
+    let z: Matrix<Complex<f64>> = Matrix::from_vec(vec![Complex::new(0.1, 0.2)], 1, 1).unwrap();
+    assert_eq!(round_trip(&z, false), z);
+
+    let empty: Matrix = Matrix::new(0, 5);
+    assert_eq!(round_trip(&empty, true), empty);
+}
+
+#[test]
+fn test_header_layout() {
+    let header = Header::new(Dtype::C128, 3, 70000, true);
+    let bytes = header.encode();
+    assert_eq!(&bytes[..8], b"DMMATRIX");
+    assert_eq!(bytes[8], 1);
+    assert_eq!(bytes[9], cfg!(target_endian = "big") as u8);
+    assert_eq!((bytes[10], bytes[11]), (6, 1));
+    assert_eq!(Header::decode(&bytes).unwrap(), header);
+    assert_eq!(header.data_len().unwrap(), 3 * 70000 * 16);
+}
+
+#[test]
+fn test_reads_big_endian_files() {
+    let mut bytes = Vec::from(MAGIC);
+    bytes.extend([1, 1, 2, 0, 0, 0, 0, 0]);
+    bytes.extend(2u64.to_be_bytes());
+    bytes.extend(1u64.to_be_bytes());
+    bytes.extend(1.5f64.to_be_bytes());
+    bytes.extend((-0.75f64).to_be_bytes());
+    let m: Matrix = Matrix::read_binary(&bytes[..]).unwrap();
+    assert_eq!((m.rows, m.cols), (2, 1));
+    assert_eq!(m.data, vec![1.5, -0.75]);
+}
+
+#[test]
+fn test_checksum_detects_corruption() {
+    let zero: Matrix<i32> = Matrix::new(1, 1);
+    let mut bytes = Vec::new();
+    zero.view().write_binary(&mut bytes, true).unwrap();
+    // CRC-32 of four zero bytes
+    assert_eq!(bytes[Header::SIZE + 4..], 0x2144_DF1Cu32.to_ne_bytes());
+
+    let m: Matrix = Matrix::from_vec((0..12).map(f64::from).collect(), 3, 4).unwrap();
+    let mut bytes = Vec::new();
+    m.view().write_binary(&mut bytes, true).unwrap();
+    bytes[Header::SIZE + 17] ^= 0x10;
+    let err = Matrix::<f64>::read_binary(&bytes[..]).unwrap_err();
+    assert!(err.to_string().contains("Checksum mismatch"), "{}", err);
+}
+
+#[test]
+fn test_malformed_files_are_rejected() {
+    let m: Matrix = Matrix::from_vec(vec![1.0, 2.0], 1, 2).unwrap();
+    let mut bytes = Vec::new();
+    m.view().write_binary(&mut bytes, false).unwrap();
+
+    let is_format =
+        |bytes: &[u8]| matches!(Matrix::<f64>::read_binary(bytes), Err(Error::Format { .. }));
+    assert!(is_format(&bytes[..bytes.len() - 1]));
+    assert!(is_format(&bytes[..10]));
+    assert!(is_format(&[bytes.as_slice(), &[0]].concat()));
+
+    let mut bad = bytes.clone();
+    bad[0] = b'X';
+    assert!(is_format(&bad));
+    let mut bad = bytes.clone();
+    bad[8] = 2;
+    assert!(is_format(&bad));
+    let mut bad = bytes.clone();
+    bad[16..24].copy_from_slice(&[0xFF; 8]);
+    assert!(is_format(&bad));
+
+    // Element type must match the requested one
+    let err = Matrix::<f32>::read_binary(&bytes[..]).unwrap_err();
+    assert!(err.to_string().contains("f64"), "{}", err);
+}
+
+#[test]
+fn test_load_and_save_pick_the_format() {
+    let dir = tempdir().unwrap();
+    let m: Matrix = Matrix::from_vec(vec![0.1, 0.2, 0.3, 1e-300], 2, 2).unwrap();
+
+    let binary = dir.path().join("m.bin");
+    let text = dir.path().join("m.txt");
+    m.save(&binary).unwrap();
+    m.save(&text).unwrap();
+    assert!(is_binary_file(&binary).unwrap());
+    assert!(!is_binary_file(&text).unwrap());
+    assert_eq!(Header::read_from_file(&binary).unwrap().rows, 2);
+    assert!(Header::read_from_file(&binary).unwrap().checksum);
+
+    assert_eq!(Matrix::<f64>::load(&binary).unwrap(), m);
+    assert_eq!(Matrix::<f64>::load(&text).unwrap(), m);
+    assert!(fs::metadata(&binary).unwrap().len() < 100);
+
+    // Binary content is recognized whatever the file is called
+    let renamed = dir.path().join("m.txt2");
+    fs::copy(&binary, &renamed).unwrap();
+    assert_eq!(Matrix::<f64>::load(&renamed).unwrap(), m);
+
+    let err = Matrix::<f64>::load_binary(&text).unwrap_err();
+    assert!(err.to_string().contains("m.txt"), "{}", err);
+}
+
+#[test]
+fn test_saving_a_transposed_view() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("t.bin");
+    let m: Matrix<i64> = Matrix::from_vec((0..6).collect(), 2, 3).unwrap();
+    m.view().transpose().save_binary(&path, false).unwrap();
+    assert_eq!(Matrix::<i64>::load_binary(&path).unwrap(), m.transpose());
+}
+
+#[test]
+fn test_writing_rows_in_blocks() {
+    let m: Matrix<i32> = Matrix::from_vec((0..15).collect(), 5, 3).unwrap();
+    for checksum in [false, true] {
+        let mut whole = Vec::new();
+        m.view().write_binary(&mut whole, checksum).unwrap();
+
+        let mut writer = BinaryWriter::new(Vec::new(), 5, 3, checksum).unwrap();
+        writer.write_rows(m.row_chunk(0, 2).unwrap()).unwrap();
+        writer.write_rows(m.row_chunk(2, 0).unwrap()).unwrap();
+        writer.write_rows(m.row_chunk(2, 3).unwrap()).unwrap();
+        assert_eq!(writer.rows_written(), 5);
+        assert_eq!(writer.finish().unwrap(), whole);
+    }
+
+    let mut writer: BinaryWriter<_, i32> = BinaryWriter::new(Vec::new(), 2, 3, true).unwrap();
+    assert!(matches!(
+        writer.write_rows(m.block(0, 0, 1, 2).unwrap()),
+        Err(Error::DimensionMismatch { .. })
+    ));
+    assert!(matches!(
+        writer.write_rows(m.row_chunk(0, 3).unwrap()),
+        Err(Error::DimensionMismatch { .. })
+    ));
+    writer.write_rows(m.row_chunk(0, 1).unwrap()).unwrap();
+    assert!(matches!(writer.finish(), Err(Error::Format { .. })));
+}
@@ -0,0 +1,119 @@
+// Tests for the blocked Cholesky decomposition and its solves
+
+mod common;
+
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use num_complex::Complex;
+use common::assert_close;
+
+/// `B B^T + n I` for a deterministic B: a well-conditioned Gram matrix
+fn gram(n: usize) -> Matrix {
+    let b = Matrix::from_vec(
+        (0..n * n)
+            .map(|i| ((i * 7 + 3) % 11) as f64 - 5.0)
+            .collect(),
+        n,
+        n,
+    )
+    .unwrap();
+    let mut g = b.multiply(&b.transpose()).unwrap();
+    for i in 0..n {
+        g[(i, i)] += n as f64;
+    }
+    g
+}
+
+#[test]
+fn test_small_factor() {
+    let a = Matrix::from_vec(
+        vec![4.0, 12.0, -16.0, 12.0, 37.0, -43.0, -16.0, -43.0, 98.0],
+        3,
+        3,
+    )
+    .unwrap();
+    let chol = a.cholesky().unwrap();
+    let expected =
+        Matrix::from_vec(vec![2.0, 0.0, 0.0, 6.0, 1.0, 0.0, -8.0, 5.0, 3.0], 3, 3).unwrap();
+    assert_close(chol.l(), &expected, 1e-12);
+    assert!((chol.determinant() - 36.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_blocked_factor_reproduces_matrix() {
+    // Larger than one block so the trailing GEMM update runs
+    for n in [1, 63, 64, 65, 150] {
+        let a = gram(n);
+        let l = a.cholesky().unwrap().into_l();
+        for i in 0..n {
+            for j in i + 1..n {
+                assert_eq!(l[(i, j)], 0.0);
+            }
+        }
+        assert_close(&l.multiply(&l.transpose()).unwrap(), &a, 1e-8 * n as f64);
+    }
+}
+
+#[test]
+fn test_solve() {
+    let a = gram(70);
+    let x = Matrix::from_vec((0..140).map(|v| (v % 9) as f64 - 4.0).collect(), 70, 2).unwrap();
+    let b = a.multiply(&x).unwrap();
+    let chol = a.cholesky().unwrap();
+    assert_close(&chol.solve(&b).unwrap(), &x, 1e-9);
+
+    // The two triangular halves compose to the full solve
+    let y = chol.solve_lower(&b).unwrap();
+    assert_close(&chol.l().multiply(&y).unwrap(), &b, 1e-8);
+    assert_close(&chol.solve_upper(&y).unwrap(), &x, 1e-9);
+
+    assert!(matches!(
+        chol.solve(&Matrix::new(3, 1)),
+        Err(Error::DimensionMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_not_positive_definite() {
+    let indefinite = Matrix::from_vec(vec![1.0, 2.0, 2.0, 1.0], 2, 2).unwrap();
+    assert!(matches!(
+        indefinite.cholesky(),
+        Err(Error::NotPositiveDefinite { pivot: 1 })
+    ));
+
+    let mut a = gram(80);
+    a[(70, 70)] = -1.0;
+    assert!(matches!(
+        a.cholesky(),
+        Err(Error::NotPositiveDefinite { .. })
+    ));
+
+    let nan = Matrix::from_vec(vec![f64::NAN], 1, 1).unwrap();
+    assert!(nan.cholesky().is_err());
+
+    let rect: Matrix = Matrix::new(2, 3);
+    assert!(matches!(rect.cholesky(), Err(Error::NotSquare { .. })));
+}
+
+#[test]
+fn test_complex_hermitian() {
+    let c = |re, im| Complex::new(re, im);
+    // [[4, 2-2i], [2+2i, 6]] = L L^H with L = [[2, 0], [1+i, 2]]
+    let a = Matrix::from_vec(
+        vec![c(4.0, 0.0), c(2.0, -2.0), c(2.0, 2.0), c(6.0, 0.0)],
+        2,
+        2,
+    )
+    .unwrap();
+    let chol = a.cholesky().unwrap();
+    let l = chol.l();
+    assert!((l[(0, 0)] - c(2.0, 0.0)).norm() < 1e-12);
+    assert!((l[(1, 0)] - c(1.0, 1.0)).norm() < 1e-12);
+    assert!((l[(1, 1)] - c(2.0, 0.0)).norm() < 1e-12);
+
+    let x = Matrix::from_vec(vec![c(1.0, -1.0), c(0.5, 2.0)], 2, 1).unwrap();
+    let solved = chol.solve(&a.multiply(&x).unwrap()).unwrap();
+    for (s, e) in solved.data.iter().zip(&x.data) {
+        assert!((s - e).norm() < 1e-12);
+    }
+}
@@ -1,2 +1,32 @@
+// Helpers shared by the integration tests; each test binary uses only some
+#![allow(dead_code)]
+
 pub mod mpi_mock;
 
+use distribiuted_matrix_multiplication::matrix::Matrix;
+
+/// Deterministic small-integer matrix, so products are exact in any order
+pub fn test_matrix(rows: usize, cols: usize, seed: usize) -> Matrix {
+    let data = (0..rows * cols)
+        .map(|x| ((x * 7 + seed * 13) % 17) as f64 - 8.0)
+        .collect();
+    Matrix::from_vec(data, rows, cols).unwrap()
+}
+
+/// Assert that two matrices have the same shape and agree within `tol`
+pub fn assert_close(actual: &Matrix, expected: &Matrix, tol: f64) {
+    assert_eq!((actual.rows, actual.cols), (expected.rows, expected.cols));
+    for (a, e) in actual.data.iter().zip(&expected.data) {
+        assert!((a - e).abs() <= tol, "{} != {}", a, e);
+    }
+}
+
+/// Shorthand for a matrix from row-major data
+pub fn m(data: Vec<f64>, rows: usize, cols: usize) -> Matrix {
+    Matrix::from_vec(data, rows, cols).unwrap()
+}
+
+/// Matrix holding `0, 1, 2, ...` in row-major order
+pub fn sequential(rows: usize, cols: usize) -> Matrix {
+    Matrix::from_vec((0..rows * cols).map(|x| x as f64).collect(), rows, cols).unwrap()
+}
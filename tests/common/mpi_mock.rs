@@ -1,13 +1,23 @@
 
+use distribiuted_matrix_multiplication::binary::BinaryWriter;
+use distribiuted_matrix_multiplication::coordinator::{power_rounds, ChunkSource, PowerRound};
+use distribiuted_matrix_multiplication::kernel::GemmSpec;
 use distribiuted_matrix_multiplication::matrix::Matrix;
 use distribiuted_matrix_multiplication::mpi_utils::*;
+use distribiuted_matrix_multiplication::view::MatrixView;
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 
+/// Messages waiting to be received, by sender, receiver and tag
+type Mailboxes = HashMap<(i32, i32, i32), Vec<Vec<u8>>>;
+
 /// Message queue for simulating MPI communication in tests
 #[derive(Clone)]
 pub struct TestMessageQueue {
-    messages: Arc<Mutex<HashMap<(i32, i32, i32), Vec<Vec<u8>>>>>,
+    messages: Arc<Mutex<Mailboxes>>,
 }
 
 impl TestMessageQueue {
@@ -19,7 +29,7 @@ impl TestMessageQueue {
 
     /// Send data from one rank to another
     pub fn send<T: Copy>(&self, from: i32, to: i32, tag: i32, data: &[T]) {
-        let size = data.len() * std::mem::size_of::<T>();
+        let size = std::mem::size_of_val(data);
         let bytes: Vec<u8> = unsafe {
             std::slice::from_raw_parts(data.as_ptr() as *const u8, size)
         }
@@ -27,16 +37,20 @@ impl TestMessageQueue {
 
         let mut msgs = self.messages.lock().unwrap();
         msgs.entry((from, to, tag))
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(bytes);
     }
 
     /// Receive data from one rank to another
+    ///
+    /// Messages with the same tag between the same ranks arrive in the order
+    /// they were sent, as in MPI.
     pub fn receive<T: Copy>(&self, from: i32, to: i32, tag: i32, buf: &mut [T]) -> bool {
         let mut msgs = self.messages.lock().unwrap();
         if let Some(msg_queue) = msgs.get_mut(&(from, to, tag)) {
-            if let Some(msg_bytes) = msg_queue.pop() {
-                let expected_size = buf.len() * std::mem::size_of::<T>();
+            if !msg_queue.is_empty() {
+                let msg_bytes = msg_queue.remove(0);
+                let expected_size = std::mem::size_of_val(buf);
                 if msg_bytes.len() >= expected_size {
                     unsafe {
                         std::ptr::copy_nonoverlapping(
@@ -86,6 +100,15 @@ pub mod test_mpi {
         queue.send(from, to, TAG_MATRIX_DATA, &matrix.data);
     }
 
+    /// Send a view as a dense matrix, packing it first unless it is contiguous
+    pub fn send_view(queue: &TestMessageQueue, from: i32, to: i32, view: MatrixView<'_, f64>) {
+        send_matrix_dimensions(queue, from, to, view.rows(), view.cols());
+        match view.as_slice() {
+            Some(data) => queue.send(from, to, TAG_MATRIX_DATA, data),
+            None => queue.send(from, to, TAG_MATRIX_DATA, &view.to_matrix().data),
+        }
+    }
+
     pub fn receive_matrix(
         queue: &TestMessageQueue,
         from: i32,
@@ -104,50 +127,44 @@ pub mod test_mpi {
         queue: &TestMessageQueue,
         from: i32,
         to: i32,
-        row_start: usize,
-        row_end: usize,
-        col_start: usize,
-        col_end: usize,
+        assignment: &WorkAssignment,
     ) {
-        let assignment = [
-            row_start as i32,
-            row_end as i32,
-            col_start as i32,
-            col_end as i32,
-        ];
-        queue.send(from, to, TAG_WORK_ASSIGNMENT, &assignment);
+        queue.send(from, to, TAG_WORK_ASSIGNMENT, &assignment.encode());
     }
 
     pub fn receive_work_assignment(
         queue: &TestMessageQueue,
         from: i32,
         to: i32,
-    ) -> Option<(usize, usize, usize, usize)> {
-        let mut assignment = [0i32; 4];
-        if queue.receive(from, to, TAG_WORK_ASSIGNMENT, &mut assignment) {
-            Some((
-                assignment[0] as usize,
-                assignment[1] as usize,
-                assignment[2] as usize,
-                assignment[3] as usize,
-            ))
+    ) -> Option<WorkAssignment> {
+        let mut msg = [0i32; 11];
+        if queue.receive(from, to, TAG_WORK_ASSIGNMENT, &mut msg) {
+            WorkAssignment::decode(&msg, from).ok()
         } else {
             None
         }
     }
 
-    // Separate functions for row chunk and column chunk to avoid tag conflicts
+    /// Send GEMM's alpha and beta
+    pub fn send_scalars(queue: &TestMessageQueue, from: i32, to: i32, scalars: &[f64; 2]) {
+        queue.send(from, to, TAG_SCALARS, scalars);
+    }
+
+    pub fn receive_scalars(queue: &TestMessageQueue, from: i32, to: i32) -> Option<[f64; 2]> {
+        let mut scalars = [0.0f64; 2];
+        queue
+            .receive(from, to, TAG_SCALARS, &mut scalars)
+            .then_some(scalars)
+    }
+
+    // Row chunks of A and column chunks of B travel as whole matrices, in the
+    // order the worker receives them
     pub fn send_row_chunk(queue: &TestMessageQueue, from: i32, to: i32, matrix: &Matrix) {
-        send_matrix_dimensions(queue, from, to, matrix.rows, matrix.cols);
-        queue.send(from, to, TAG_MATRIX_DATA, &matrix.data);
+        send_matrix(queue, from, to, matrix);
     }
 
     pub fn send_col_chunk(queue: &TestMessageQueue, from: i32, to: i32, matrix: &Matrix) {
-        // Use tag 10 for col chunk dimensions to avoid mixing with row chunk
-        let dims = [matrix.rows as i32, matrix.cols as i32];
-        queue.send(from, to, 10, &dims);
-        // Use TAG_RESULT_DATA for col chunk data
-        queue.send(from, to, TAG_RESULT_DATA, &matrix.data);
+        send_matrix(queue, from, to, matrix);
     }
 
     pub fn receive_row_chunk(
@@ -155,13 +172,7 @@ pub mod test_mpi {
         from: i32,
         to: i32,
     ) -> Option<Matrix> {
-        let (rows, cols) = receive_matrix_dimensions(queue, from, to)?;
-        let mut data = vec![0.0f64; rows * cols];
-        if queue.receive(from, to, TAG_MATRIX_DATA, &mut data) {
-            Some(Matrix { data, rows, cols })
-        } else {
-            None
-        }
+        receive_matrix(queue, from, to)
     }
 
     pub fn receive_col_chunk(
@@ -169,55 +180,252 @@ pub mod test_mpi {
         from: i32,
         to: i32,
     ) -> Option<Matrix> {
-        // Receive dimensions with tag 10
-        let mut dims = [0i32; 2];
-        if !queue.receive(from, to, 10, &mut dims) {
-            return None;
+        receive_matrix(queue, from, to)
+    }
+}
+
+/// Worker state kept from one assignment to the next
+pub struct SimulatedWorker {
+    pub rank: i32,
+    /// Result of the previous round, in case the next one reuses it
+    previous: Option<Matrix>,
+}
+
+impl SimulatedWorker {
+    pub fn new(rank: i32) -> Self {
+        SimulatedWorker {
+            rank,
+            previous: None,
         }
-        let rows = dims[0] as usize;
-        let cols = dims[1] as usize;
-        let mut data = vec![0.0f64; rows * cols];
-        // Receive data with TAG_RESULT_DATA
-        if queue.receive(from, to, TAG_RESULT_DATA, &mut data) {
-            Some(Matrix { data, rows, cols })
+    }
+
+    /// Handle one assignment the way `Worker::process_work_as` does
+    ///
+    /// Returns the assignment and the result sent back for it, if any.
+    pub fn step(
+        &mut self,
+        queue: &TestMessageQueue,
+        coordinator_rank: i32,
+    ) -> Result<(WorkAssignment, Option<Matrix>), String> {
+        let assignment = test_mpi::receive_work_assignment(queue, coordinator_rank, self.rank)
+            .ok_or("Failed to receive work assignment")?;
+        if assignment.is_empty() {
+            return Ok((assignment, None));
+        }
+
+        let [alpha, beta] = test_mpi::receive_scalars(queue, coordinator_rank, self.rank)
+            .ok_or("Failed to receive scalars")?;
+        let spec = GemmSpec::default()
+            .with_alpha(alpha)
+            .with_beta(beta)
+            .with_trans_a(assignment.trans_a)
+            .with_trans_b(assignment.trans_b)
+            .with_accumulation(assignment.accumulation)
+            .with_reproducible(assignment.reproducible);
+
+        // Receive row chunk from A, unless the previous result stands in for it
+        let row_chunk = if assignment.reuse_a {
+            self.previous
+                .take()
+                .ok_or("Asked to reuse a previous result, but there is none")?
+        } else {
+            test_mpi::receive_row_chunk(queue, coordinator_rank, self.rank)
+                .ok_or("Failed to receive row chunk")?
+        };
+
+        // Receive column chunk from B
+        let col_chunk = test_mpi::receive_col_chunk(queue, coordinator_rank, self.rank)
+            .ok_or("Failed to receive column chunk")?;
+
+        let matrix_c = if assignment.accumulate {
+            let c = test_mpi::receive_matrix(queue, coordinator_rank, self.rank)
+                .ok_or("Failed to receive block of C")?;
+            Some(c)
         } else {
             None
+        };
+
+        // Compute result
+        let result =
+            Matrix::gemm(&spec, &row_chunk, &col_chunk, matrix_c).map_err(|e| e.to_string())?;
+
+        // Send result back
+        test_mpi::send_matrix(queue, self.rank, coordinator_rank, &result);
+
+        if assignment.more {
+            self.previous = Some(result.clone());
         }
+        Ok((assignment, Some(result)))
     }
 }
 
-/// Simulate a worker processing work
+/// Simulate a worker processing work until the coordinator sends no more
+///
+/// Returns the last result computed.
 pub fn simulate_worker(
     queue: &TestMessageQueue,
     worker_rank: i32,
     coordinator_rank: i32,
 ) -> Result<Matrix, String> {
-    // Receive work assignment
-    let (row_start, row_end, col_start, col_end) = test_mpi::receive_work_assignment(
-        queue,
-        coordinator_rank,
-        worker_rank,
-    )
-    .ok_or("Failed to receive work assignment")?;
-
-    if row_start >= row_end || col_start >= col_end {
-        return Err("No work assigned".to_string());
+    let mut worker = SimulatedWorker::new(worker_rank);
+    let mut last = None;
+    loop {
+        let (assignment, result) = worker.step(queue, coordinator_rank)?;
+        last = result.or(last);
+        if !assignment.more {
+            return last.ok_or_else(|| "No work assigned".to_string());
+        }
     }
+}
 
-    // Receive row chunk from A
-    let row_chunk = test_mpi::receive_row_chunk(queue, coordinator_rank, worker_rank)
-        .ok_or("Failed to receive row chunk")?;
+/// Simulate `Coordinator::power` with `worker_count` workers, stepping the
+/// coordinator and the workers through the rounds in lockstep
+///
+/// Like the coordinator, uses only the accumulation mode and reproducible
+/// flag of `spec`. Returns `A^k` and how many rounds each worker computed.
+pub fn simulate_power(
+    queue: &TestMessageQueue,
+    spec: &GemmSpec,
+    matrix: &Matrix,
+    k: u32,
+    worker_count: usize,
+) -> Result<(Matrix, Vec<usize>), String> {
+    let n = matrix.rows;
+    let rounds = power_rounds(k);
+    let mut workers: Vec<_> = (1..=worker_count as i32)
+        .map(SimulatedWorker::new)
+        .collect();
+    let mut computed = vec![0; worker_count];
+    let mut result: Option<Matrix> = None;
 
-    // Receive column chunk from B
-    let col_chunk = test_mpi::receive_col_chunk(queue, coordinator_rank, worker_rank)
-        .ok_or("Failed to receive column chunk")?;
+    for (i, round) in rounds.iter().enumerate() {
+        let more = i + 1 < rounds.len();
+        let right = match round {
+            PowerRound::Square => result.as_ref().unwrap_or(matrix).clone(),
+            PowerRound::MultiplyByA => matrix.clone(),
+        };
 
-    // Compute result
-    let result = Matrix::multiply_chunks(&row_chunk, &col_chunk)?;
+        // Split the rows across the workers as the coordinator does
+        let rows_per_worker = n.div_ceil(worker_count);
+        for worker in &workers {
+            let row_start = (worker.rank as usize - 1) * rows_per_worker;
+            if row_start >= n {
+                let assignment = WorkAssignment {
+                    more,
+                    ..WorkAssignment::default()
+                };
+                test_mpi::send_work_assignment(queue, 0, worker.rank, &assignment);
+                continue;
+            }
+            let row_end = (row_start + rows_per_worker).min(n);
+            let assignment = WorkAssignment {
+                reuse_a: result.is_some(),
+                more,
+                accumulation: spec.accumulation,
+                reproducible: spec.reproducible,
+                ..WorkAssignment::new(row_start, row_end, 0, n)
+            };
+            test_mpi::send_work_assignment(queue, 0, worker.rank, &assignment);
+            test_mpi::send_scalars(queue, 0, worker.rank, &[1.0, 0.0]);
+            if result.is_none() {
+                let chunk = matrix
+                    .get_row_chunk(row_start, row_end - row_start)
+                    .map_err(|e| e.to_string())?;
+                test_mpi::send_row_chunk(queue, 0, worker.rank, &chunk);
+            }
+            test_mpi::send_col_chunk(queue, 0, worker.rank, &right);
+        }
 
-    // Send result back
-    test_mpi::send_matrix(queue, worker_rank, coordinator_rank, &result);
+        let mut next = Matrix::new(n, n);
+        for (worker, count) in workers.iter_mut().zip(&mut computed) {
+            let (assignment, block) = worker.step(queue, 0)?;
+            if block.is_none() {
+                continue;
+            }
+            *count += 1;
+            let block = test_mpi::receive_matrix(queue, worker.rank, 0)
+                .ok_or("Failed to receive result")?;
+            next.block_mut(assignment.row_start, 0, block.rows, block.cols)
+                .and_then(|mut dst| dst.copy_from(&block.view()))
+                .map_err(|e| e.to_string())?;
+        }
+        result = Some(next);
+    }
 
-    Ok(result)
+    Ok((result.unwrap_or_else(|| matrix.clone()), computed))
 }
 
+/// Simulate `Coordinator::gemm_as` with `worker_count` workers on operands
+/// opened as the coordinator opens them, streaming the result into a binary
+/// file at `output` the way it does for a `.bin` output
+pub fn simulate_gemm(
+    queue: &TestMessageQueue,
+    spec: &GemmSpec,
+    matrix_a: &dyn ChunkSource<f64>,
+    matrix_b: &dyn ChunkSource<f64>,
+    worker_count: usize,
+    output: &Path,
+) -> Result<(), String> {
+    let (a_rows, a_cols) = matrix_a.dims();
+    let rows = if spec.trans_a.is_transposed() {
+        a_cols
+    } else {
+        a_rows
+    };
+    let (b_rows, b_cols) = matrix_b.dims();
+    let cols = if spec.trans_b.is_transposed() {
+        b_rows
+    } else {
+        b_cols
+    };
+    let file = File::create(output).map_err(|e| e.to_string())?;
+    let mut writer: BinaryWriter<_> =
+        BinaryWriter::new(BufWriter::new(file), rows, cols, true).map_err(|e| e.to_string())?;
+
+    let rows_per_worker = rows.div_ceil(worker_count);
+    let mut workers: Vec<_> = (1..=worker_count as i32)
+        .map(SimulatedWorker::new)
+        .collect();
+    for worker in &workers {
+        let row_start = (worker.rank as usize - 1) * rows_per_worker;
+        if row_start >= rows {
+            test_mpi::send_work_assignment(queue, 0, worker.rank, &WorkAssignment::default());
+            continue;
+        }
+        let row_end = (row_start + rows_per_worker).min(rows);
+        let assignment = WorkAssignment {
+            trans_a: spec.trans_a,
+            trans_b: spec.trans_b,
+            ..WorkAssignment::new(row_start, row_end, 0, cols)
+        };
+        test_mpi::send_work_assignment(queue, 0, worker.rank, &assignment);
+        test_mpi::send_scalars(queue, 0, worker.rank, &[spec.alpha, spec.beta]);
+        let send = &mut |chunk: MatrixView<'_, f64>| {
+            test_mpi::send_view(queue, 0, worker.rank, chunk);
+            Ok(())
+        };
+        matrix_a
+            .with_chunk(
+                spec.trans_a.is_transposed(),
+                row_start,
+                row_end - row_start,
+                send,
+            )
+            .map_err(|e| e.to_string())?;
+        matrix_b
+            .with_chunk(false, 0, b_rows, send)
+            .map_err(|e| e.to_string())?;
+    }
+
+    // Results go to the file in rank order, which is row order
+    for worker in &mut workers {
+        if worker.step(queue, 0)?.1.is_none() {
+            continue;
+        }
+        let block =
+            test_mpi::receive_matrix(queue, worker.rank, 0).ok_or("Failed to receive result")?;
+        writer.write_rows(block.view()).map_err(|e| e.to_string())?;
+    }
+    let mut file = writer.finish().map_err(|e| e.to_string())?;
+    file.flush().map_err(|e| e.to_string())
+}
@@ -0,0 +1,106 @@
+// Tests for tolerance-aware matrix comparison
+
+mod common;
+
+use distribiuted_matrix_multiplication::element::Approx;
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use num_complex::Complex;
+use common::m;
+
+#[test]
+fn test_identical_matrices_compare_clean() {
+    let a = m(vec![1.0, -2.5, 0.0, 1e300, f64::INFINITY, 3.0], 2, 3);
+    let report = a.compare(&a, 0.0, 0.0).unwrap();
+    assert!(report.passed());
+    assert_eq!(report.elements, 6);
+    assert_eq!(report.max_abs_error, 0.0);
+    assert_eq!(report.max_ulp_distance, 0);
+    assert_eq!(report.max_abs_position, None);
+    assert!(a.approx_eq(&a, 0.0, 0.0));
+}
+
+#[test]
+fn test_report_finds_worst_positions() {
+    let expected = m(vec![1.0, 100.0, 1e-3, 5.0], 2, 2);
+    let actual = m(vec![1.0, 100.5, 2e-3, 5.0], 2, 2);
+    let report = actual.compare(&expected, 1e-6, 1e-6).unwrap();
+    assert_eq!(report.mismatches, 2);
+    assert!((report.max_abs_error - 0.5).abs() < 1e-12);
+    assert_eq!(report.max_abs_position, Some((0, 1)));
+    assert!((report.max_rel_error - 0.5).abs() < 1e-12);
+    assert_eq!(report.max_rel_position, Some((1, 0)));
+    assert!(report.max_ulp_distance > 1 << 40);
+
+    let text = report.to_string();
+    assert!(text.starts_with("2 of 4 elements outside tolerance"));
+    assert!(text.contains("at (0, 1)"));
+}
+
+#[test]
+fn test_absolute_and_relative_tolerances() {
+    let expected = m(vec![0.0, 1000.0], 1, 2);
+    let actual = m(vec![1e-9, 1000.001], 1, 2);
+    // The absolute tolerance covers the value near zero, the relative one the large value
+    assert!(actual.approx_eq(&expected, 1e-8, 1e-5));
+    assert!(!actual.approx_eq(&expected, 1e-8, 1e-8));
+    assert!(!actual.approx_eq(&expected, 1e-10, 1e-5));
+}
+
+#[test]
+fn test_ulp_distance() {
+    assert_eq!(1.0f64.ulp_distance(1.0 + f64::EPSILON), 1);
+    assert_eq!(0.0f64.ulp_distance(-0.0), 0);
+    assert_eq!(f64::from_bits(1).ulp_distance(-f64::from_bits(1)), 2);
+    assert_eq!(1.0f32.ulp_distance(1.0f32.next_up()), 1);
+    assert_eq!(f64::NAN.ulp_distance(1.0), u64::MAX);
+    assert_eq!(7i32.ulp_distance(-3), 10);
+    assert_eq!(i64::MIN.ulp_distance(i64::MAX), u64::MAX);
+
+    let z = Complex::new(1.0f64, 2.0);
+    assert_eq!(z.ulp_distance(Complex::new(1.0, 2.0f64.next_up())), 1);
+}
+
+#[test]
+fn test_nan_and_infinity_never_agree() {
+    let expected = m(vec![1.0, f64::INFINITY], 1, 2);
+    let with_nan = m(vec![f64::NAN, f64::INFINITY], 1, 2);
+    let report = with_nan.compare(&expected, 1.0, 1.0).unwrap();
+    assert_eq!(report.mismatches, 1);
+    assert_eq!(report.max_abs_error, f64::INFINITY);
+    assert_eq!(report.max_abs_position, Some((0, 0)));
+
+    let finite = m(vec![1.0, f64::MAX], 1, 2);
+    assert!(!finite.approx_eq(&expected, 1.0, 1.0));
+}
+
+#[test]
+fn test_integer_and_complex_matrices() {
+    let a: Matrix<i64> = Matrix::from_vec(vec![1, 2, 3, 4], 2, 2).unwrap();
+    let b: Matrix<i64> = Matrix::from_vec(vec![1, 2, 3, 6], 2, 2).unwrap();
+    let report = a.compare(&b, 0.0, 0.0).unwrap();
+    assert_eq!((report.mismatches, report.max_ulp_distance), (1, 2));
+    assert!(a.approx_eq(&b, 2.0, 0.0));
+
+    let c: Matrix<Complex<f64>> =
+        Matrix::from_vec(vec![Complex::new(3.0, 4.0), Complex::new(0.0, 0.0)], 1, 2).unwrap();
+    let d: Matrix<Complex<f64>> =
+        Matrix::from_vec(vec![Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)], 1, 2).unwrap();
+    let report = c.compare(&d, 0.0, 0.0).unwrap();
+    assert_eq!(report.max_abs_error, 5.0);
+    assert_eq!(report.max_rel_error, 1.0);
+}
+
+#[test]
+fn test_shape_mismatch() {
+    let a: Matrix = Matrix::new(2, 3);
+    let b: Matrix = Matrix::new(3, 2);
+    assert!(matches!(
+        a.compare(&b, 0.0, 0.0),
+        Err(Error::DimensionMismatch {
+            operation: "compare",
+            ..
+        })
+    ));
+    assert!(!a.approx_eq(&b, 1.0, 1.0));
+}
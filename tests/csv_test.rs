@@ -0,0 +1,267 @@
+// Tests for delimited (CSV/TSV) files
+
+use distribiuted_matrix_multiplication::csv::{self, CsvOptions, CsvOverrides, Table};
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use num_complex::Complex;
+use std::fs;
+use tempfile::tempdir;
+
+fn read(text: &str, options: &CsvOptions) -> Result<Table, Error> {
+    Table::read(text.as_bytes(), options)
+}
+
+fn write(table: &Table, options: &CsvOptions) -> String {
+    let mut bytes = Vec::new();
+    table.write(&mut bytes, options).unwrap();
+    String::from_utf8(bytes).unwrap()
+}
+
+#[test]
+fn test_header_row_labels_comments_and_blank_lines() {
+    let text = "# exported from a spreadsheet\n\
+                ,alpha, beta ,\"gamma, delta\"\n\
+                \n\
+                row 1, 1.5, -2, 3e2\n\
+                \x20 # indented comment\n\
+                \"row, 2\",4,5,6\r\n";
+    let options = CsvOptions::csv().with_header(true).with_row_labels(true);
+    let table = read(text, &options).unwrap();
+    assert_eq!(
+        table.matrix,
+        Matrix::from_vec(vec![1.5, -2.0, 300.0, 4.0, 5.0, 6.0], 2, 3).unwrap()
+    );
+    assert_eq!(
+        table.column_names.as_deref(),
+        Some(&["alpha".to_string(), "beta".into(), "gamma, delta".into()][..])
+    );
+    assert_eq!(
+        table.row_labels.as_deref(),
+        Some(&["row 1".to_string(), "row, 2".into()][..])
+    );
+
+    // Without the options the same file does not parse
+    assert!(matches!(
+        read(text, &CsvOptions::csv()),
+        Err(Error::Parse {
+            line: Some(2),
+            column: Some(1),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_quoted_fields_hold_quotes_and_line_breaks() {
+    let text = "\"a \"\"quoted\"\"\nname\",x\nlabel,\" 7 \"\n";
+    let options = CsvOptions::csv().with_header(true).with_row_labels(true);
+    let table = read(text, &options).unwrap();
+    assert_eq!(table.column_names.unwrap(), vec!["x"]);
+    assert_eq!(table.matrix.data, vec![7.0]);
+
+    let labels = read(
+        "\"multi\nline\",1\n",
+        &CsvOptions::csv().with_row_labels(true),
+    )
+    .unwrap();
+    assert_eq!(labels.row_labels.unwrap(), vec!["multi\nline"]);
+}
+
+#[test]
+fn test_errors_report_line_and_column() {
+    let options = CsvOptions::csv();
+    let cases = [
+        ("1,2\n3,x\n", 2, Some(3)),
+        ("1,2\n3,2,1\n", 2, Some(5)),
+        ("1,2,3\n4,5\n", 2, None),
+        ("1,\"2\" 3\n", 1, Some(7)),
+        ("1,2\"3\n", 1, Some(4)),
+        ("1,2\n\"3\n4\n", 2, Some(1)),
+    ];
+    for (text, line, column) in cases {
+        match read(text, &options) {
+            Err(Error::Parse {
+                line: l, column: c, ..
+            })
+            | Err(Error::Format {
+                line: l, column: c, ..
+            }) => assert_eq!((l, c), (Some(line), column), "{:?}", text),
+            other => panic!("{:?} gave {:?}", text, other),
+        }
+    }
+    let message = read("1,2\n3,x\n", &options).unwrap_err().to_string();
+    assert!(message.contains("on line 2, column 3"), "{}", message);
+
+    assert!(matches!(
+        read("# nothing here\n\n", &options),
+        Err(Error::Format { line: None, .. })
+    ));
+}
+
+#[test]
+fn test_tsv_and_custom_characters() {
+    let tsv = "1\t2\n3\t 4 \n";
+    let m: Matrix = Matrix::read_csv(tsv.as_bytes(), &CsvOptions::tsv()).unwrap();
+    assert_eq!(m, Matrix::from_vec(vec![1.0, 2.0, 3.0, 4.0], 2, 2).unwrap());
+
+    let options = CsvOptions::csv()
+        .with_delimiter(';')
+        .with_quote('\'')
+        .with_comment(Some('%'));
+    let text = "% comment\n'1';2\n";
+    let m: Matrix<i32> = Matrix::read_csv(text.as_bytes(), &options).unwrap();
+    assert_eq!(m.data, vec![1, 2]);
+
+    // Without a comment character a leading '#' is data
+    let no_comments = CsvOptions::csv().with_comment(None);
+    assert!(matches!(
+        Matrix::<f64>::read_csv("#1\n".as_bytes(), &no_comments),
+        Err(Error::Parse { .. })
+    ));
+    for bad in [
+        CsvOptions::csv().with_delimiter('"'),
+        CsvOptions::csv().with_delimiter('\n'),
+        CsvOptions::csv().with_comment(Some(',')),
+    ] {
+        assert!(matches!(
+            Matrix::<f64>::read_csv("1\n".as_bytes(), &bad),
+            Err(Error::InvalidArgument { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_writer_round_trips_names_labels_and_values() {
+    let options = CsvOptions::csv().with_header(true).with_row_labels(true);
+    let table = Table {
+        matrix: Matrix::from_vec(vec![0.1, -2.0, 2.5, 4.0], 2, 2).unwrap(),
+        column_names: Some(vec!["plain".into(), "has, comma \"and quotes\"".into()]),
+        row_labels: Some(vec!["#not a comment".into(), " padded ".into()]),
+    };
+    let text = write(&table, &options);
+    assert_eq!(
+        text,
+        ",plain,\"has, comma \"\"and quotes\"\"\"\n\
+         \"#not a comment\",0.1,-2\n\
+         \" padded \",2.5,4\n"
+    );
+    assert_eq!(read(&text, &options).unwrap(), table);
+
+    // Missing names and labels default to indices
+    let bare = Table::new(table.matrix.clone());
+    assert_eq!(
+        write(&bare, &options.clone().with_delimiter('\t')),
+        "\t0\t1\n0\t0.1\t-2\n1\t2.5\t4\n"
+    );
+    assert_eq!(write(&bare, &CsvOptions::csv()), "0.1,-2\n2.5,4\n");
+
+    let wrong = Table {
+        row_labels: Some(vec!["only one".into()]),
+        ..bare
+    };
+    let mut sink = Vec::new();
+    assert!(matches!(
+        wrong.write(&mut sink, &options),
+        Err(Error::InvalidArgument { .. })
+    ));
+}
+
+#[test]
+fn test_files_by_extension_and_element_types() {
+    let dir = tempdir().unwrap();
+    let z: Matrix<Complex<f64>> =
+        Matrix::from_vec(vec![Complex::new(1.0, -2.0), Complex::new(0.5, 0.0)], 1, 2).unwrap();
+    let csv_path = dir.path().join("z.csv");
+    z.save(&csv_path).unwrap();
+    assert!(fs::read_to_string(&csv_path).unwrap().contains(','));
+    assert_eq!(Matrix::<Complex<f64>>::load(&csv_path).unwrap(), z);
+
+    let m: Matrix<i64> = Matrix::from_vec(vec![1, 2, 3, 4, 5, 6], 3, 2).unwrap();
+    let tsv_path = dir.path().join("m.tsv");
+    m.save(&tsv_path).unwrap();
+    assert_eq!(fs::read_to_string(&tsv_path).unwrap(), "1\t2\n3\t4\n5\t6\n");
+    assert_eq!(Matrix::<i64>::load(&tsv_path).unwrap(), m);
+
+    fs::write(&csv_path, "1,2\n3,oops\n").unwrap();
+    match Matrix::<f64>::load(&csv_path) {
+        Err(Error::Parse {
+            path: Some(p),
+            line: Some(2),
+            column: Some(3),
+            value,
+            ..
+        }) => {
+            assert_eq!(p, csv_path);
+            assert_eq!(value, "oops");
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_overrides_apply_to_every_delimited_file() {
+    let dir = tempdir().unwrap();
+    let csv_path = dir.path().join("export.csv");
+    let tsv_path = dir.path().join("export.tsv");
+    fs::write(&csv_path, "id,x,y\na,1,2\nb,3,4\n").unwrap();
+    fs::write(&tsv_path, "id\tx\ty\na\t1\t2\nb\t3\t4\n").unwrap();
+    let expected = Matrix::from_vec(vec![1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+
+    // A spreadsheet export does not load without being told its layout
+    assert!(matches!(
+        Matrix::<f64>::load(&csv_path),
+        Err(Error::Parse { .. })
+    ));
+    let overrides = CsvOverrides {
+        header: true,
+        row_labels: true,
+        ..CsvOverrides::default()
+    };
+    assert_eq!(Matrix::load_with(&csv_path, &overrides).unwrap(), expected);
+    assert_eq!(Matrix::load_with(&tsv_path, &overrides).unwrap(), expected);
+
+    // An explicit delimiter wins over the extension
+    let semicolons = dir.path().join("semicolons.csv");
+    fs::write(&semicolons, "1;2\n3;4\n").unwrap();
+    let overrides = CsvOverrides {
+        delimiter: Some(';'),
+        ..CsvOverrides::default()
+    };
+    assert_eq!(overrides.options_for(&tsv_path).delimiter, ';');
+    assert_eq!(
+        Matrix::load_with(&semicolons, &overrides).unwrap(),
+        expected
+    );
+    assert_eq!(
+        CsvOverrides::default().options_for(&tsv_path),
+        CsvOptions::tsv()
+    );
+}
+
+#[test]
+fn test_tab_files_and_overrides_when_saving() {
+    let dir = tempdir().unwrap();
+    let m = Matrix::from_vec(vec![1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+    assert!(csv::is_delimited_extension("m.tab"));
+    assert!(!csv::is_delimited_extension("m.txt"));
+
+    // .tab is TSV for both loading and saving, so the overrides apply to it
+    let tab = dir.path().join("m.tab");
+    let overrides = CsvOverrides {
+        header: true,
+        row_labels: true,
+        ..CsvOverrides::default()
+    };
+    m.save_with(&tab, &overrides).unwrap();
+    assert!(fs::read_to_string(&tab).unwrap().starts_with('\t'));
+    assert_eq!(Matrix::load_with(&tab, &overrides).unwrap(), m);
+
+    let semicolons = dir.path().join("m.csv");
+    let overrides = CsvOverrides {
+        delimiter: Some(';'),
+        ..CsvOverrides::default()
+    };
+    m.save_with(&semicolons, &overrides).unwrap();
+    assert_eq!(fs::read_to_string(&semicolons).unwrap(), "1;2\n3;4\n");
+    assert_eq!(Matrix::load_with(&semicolons, &overrides).unwrap(), m);
+}
@@ -0,0 +1,105 @@
+// Tests for the Jacobi symmetric eigensolver and power iteration
+
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+
+fn symmetric(n: usize) -> Matrix {
+    let b = Matrix::from_vec(
+        (0..n * n)
+            .map(|i| ((i * 31 + 7) % 17) as f64 - 8.0)
+            .collect(),
+        n,
+        n,
+    )
+    .unwrap();
+    &b + &b.transpose()
+}
+
+#[test]
+fn test_known_eigenvalues() {
+    let a: Matrix = Matrix::from_vec(vec![2.0, 1.0, 1.0, 2.0], 2, 2).unwrap();
+    let eigen = a.symmetric_eigen().unwrap();
+    assert!((eigen.values[0] - 1.0).abs() < 1e-14);
+    assert!((eigen.values[1] - 3.0).abs() < 1e-14);
+    let v = &eigen.vectors;
+    let s = 0.5f64.sqrt();
+    assert!((v[(0, 1)].abs() - s).abs() < 1e-14);
+    assert!((v[(0, 1)] - v[(1, 1)]).abs() < 1e-14);
+}
+
+#[test]
+fn test_decomposition_reconstructs_matrix() {
+    for n in [1, 3, 10, 25] {
+        let a = symmetric(n);
+        let eigen = a.symmetric_eigen().unwrap();
+        let v = &eigen.vectors;
+        assert_eq!(eigen.values.len(), n);
+        assert!(eigen.values.windows(2).all(|w| w[0] <= w[1]));
+
+        let vtv = v.transpose().multiply(v).unwrap();
+        let identity: Matrix = Matrix::identity(n);
+        assert!((&vtv - &identity).norm_max() < 1e-12);
+
+        let mut d = Matrix::new(n, n);
+        for (i, &value) in eigen.values.iter().enumerate() {
+            d[(i, i)] = value;
+        }
+        let rebuilt = v.multiply(&d).unwrap().multiply(&v.transpose()).unwrap();
+        assert!((&rebuilt - &a).norm_max() < 1e-10 * a.norm_max());
+    }
+}
+
+#[test]
+fn test_only_lower_triangle_is_read() {
+    let mut a = symmetric(6);
+    let expected = a.symmetric_eigen().unwrap().values;
+    for i in 0..6 {
+        for j in i + 1..6 {
+            a[(i, j)] = 1e6;
+        }
+    }
+    let values = a.symmetric_eigen().unwrap().values;
+    for (v, e) in values.iter().zip(&expected) {
+        assert!((v - e).abs() < 1e-10);
+    }
+}
+
+#[test]
+fn test_power_iteration_finds_dominant_pair() {
+    let a = symmetric(12);
+    let eigen = a.symmetric_eigen().unwrap();
+    let dominant = *eigen
+        .values
+        .iter()
+        .max_by(|x, y| x.abs().partial_cmp(&y.abs()).unwrap())
+        .unwrap();
+
+    let (value, x) = a.power_iteration(10_000, 1e-10).unwrap();
+    assert!((value - dominant).abs() < 1e-8 * dominant.abs());
+    assert!((x.norm_frobenius() - 1.0).abs() < 1e-12);
+    let residual = &a.multiply(&x).unwrap() - &(&x * value);
+    assert!(residual.norm_frobenius() < 1e-8 * value.abs());
+}
+
+#[test]
+fn test_power_iteration_gives_up() {
+    // Rotation by 90 degrees: eigenvalues +-i have equal magnitude
+    let a = Matrix::from_vec(vec![0.0, -1.0, 1.0, 0.0], 2, 2).unwrap();
+    assert!(matches!(
+        a.power_iteration(100, 1e-12),
+        Err(Error::NoConvergence {
+            iterations: 100,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_non_square_is_rejected() {
+    let a: Matrix = Matrix::new(2, 3);
+    assert!(matches!(a.symmetric_eigen(), Err(Error::NotSquare { .. })));
+    assert!(matches!(
+        a.power_iteration(10, 1e-6),
+        Err(Error::NotSquare { .. })
+    ));
+}
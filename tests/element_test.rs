@@ -0,0 +1,127 @@
+// Tests for non-f64 element types
+
+use distribiuted_matrix_multiplication::element::{Dtype, Element};
+use distribiuted_matrix_multiplication::kernel::{MR, NR};
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use distribiuted_matrix_multiplication::simd::{self, SimdLevel};
+use num_complex::Complex;
+use tempfile::NamedTempFile;
+
+fn naive_multiply<T: Element>(a: &Matrix<T>, b: &Matrix<T>) -> Matrix<T> {
+    let mut result = Matrix::new(a.rows, b.cols);
+    for i in 0..a.rows {
+        for j in 0..b.cols {
+            let mut sum = T::zero();
+            for k in 0..a.cols {
+                sum += a.data[i * a.cols + k] * b.data[k * b.cols + j];
+            }
+            result.data[i * b.cols + j] = sum;
+        }
+    }
+    result
+}
+
+#[test]
+fn test_integer_multiply_is_exact() {
+    let a_data: Vec<i64> = (0..23 * 17).map(|x| (x % 11) - 5).collect();
+    let b_data: Vec<i64> = (0..17 * 29).map(|x| (x % 7) * 1_000_000_007).collect();
+    let a = Matrix::from_vec(a_data, 23, 17).unwrap();
+    let b = Matrix::from_vec(b_data, 17, 29).unwrap();
+
+    let result = a.multiply(&b).unwrap();
+    assert_eq!(result.data, naive_multiply(&a, &b).data);
+
+    let strassen = a.multiply_strassen(&b, 2).unwrap();
+    assert_eq!(strassen.data, result.data);
+}
+
+#[test]
+fn test_i32_multiply() {
+    let a = Matrix::from_vec(vec![1i32, 2, 3, 4], 2, 2).unwrap();
+    let b = Matrix::from_vec(vec![5i32, 6, 7, 8], 2, 2).unwrap();
+    assert_eq!(a.multiply(&b).unwrap().data, vec![19, 22, 43, 50]);
+}
+
+#[test]
+fn test_f32_multiply_matches_naive() {
+    let a_data: Vec<f32> = (0..40 * 33).map(|x| (x % 13) as f32 - 6.0).collect();
+    let b_data: Vec<f32> = (0..33 * 21).map(|x| (x % 9) as f32 * 0.5).collect();
+    let a = Matrix::from_vec(a_data, 40, 33).unwrap();
+    let b = Matrix::from_vec(b_data, 33, 21).unwrap();
+
+    assert_eq!(a.multiply(&b).unwrap().data, naive_multiply(&a, &b).data);
+}
+
+#[test]
+fn test_f32_simd_levels_match_scalar() {
+    let kb = 37;
+    let a: Vec<f32> = (0..kb * MR).map(|x| (x % 7) as f32 * 0.25).collect();
+    let b: Vec<f32> = (0..kb * NR).map(|x| (x % 5) as f32 - 2.0).collect();
+    let mut expected = vec![0.0f32; MR * NR];
+    simd::scalar_microkernel(kb, &a, &b, &mut expected, NR, MR, NR);
+
+    for level in [SimdLevel::Sse2, SimdLevel::Avx2, SimdLevel::Avx512] {
+        let mut c = vec![0.0f32; MR * NR];
+        simd::microkernel::<f32>(level)(kb, &a, &b, &mut c, NR, MR, NR);
+        assert_eq!(c, expected, "{} differs from scalar", level);
+    }
+}
+
+#[test]
+fn test_complex_multiply() {
+    let i = Complex::new(0.0, 1.0);
+    let one = Complex::new(1.0, 0.0);
+    // [i 1] * [i]   = [i*i + 1*1] = [0]
+    //           [1]
+    let a = Matrix::from_vec(vec![i, one], 1, 2).unwrap();
+    let b = Matrix::from_vec(vec![i, one], 2, 1).unwrap();
+    let c = a.multiply(&b).unwrap();
+    assert_eq!(c.data, vec![Complex::new(0.0, 0.0)]);
+
+    let a_data: Vec<Complex<f64>> = (0..9 * 11)
+        .map(|x| Complex::new((x % 5) as f64, (x % 3) as f64 - 1.0))
+        .collect();
+    let b_data: Vec<Complex<f64>> = (0..11 * 6)
+        .map(|x| Complex::new((x % 4) as f64 - 2.0, (x % 7) as f64))
+        .collect();
+    let a = Matrix::from_vec(a_data, 9, 11).unwrap();
+    let b = Matrix::from_vec(b_data, 11, 6).unwrap();
+    assert_eq!(a.multiply(&b).unwrap().data, naive_multiply(&a, &b).data);
+}
+
+#[test]
+fn test_text_roundtrip_for_other_types() {
+    let ints = Matrix::from_vec(vec![-3i64, 4, 9_000_000_000, 0], 2, 2).unwrap();
+    let file = NamedTempFile::new().unwrap();
+    ints.save_to_file(file.path()).unwrap();
+    let loaded = Matrix::<i64>::load_from_file(file.path()).unwrap();
+    assert_eq!(loaded.data, ints.data);
+
+    let complex = Matrix::from_vec(
+        vec![Complex::new(1.5f32, -2.0), Complex::new(0.0, 3.25)],
+        1,
+        2,
+    )
+    .unwrap();
+    let file = NamedTempFile::new().unwrap();
+    complex.save_to_file(file.path()).unwrap();
+    let loaded = Matrix::<Complex<f32>>::load_from_file(file.path()).unwrap();
+    assert_eq!(loaded.data, complex.data);
+}
+
+#[test]
+fn test_integer_loader_rejects_floats() {
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), "1 2.5\n").unwrap();
+    assert!(Matrix::<i32>::load_from_file(file.path()).is_err());
+}
+
+#[test]
+fn test_dtype_names() {
+    for dtype in Dtype::ALL {
+        assert_eq!(Dtype::parse(dtype.name()).unwrap(), dtype);
+    }
+    assert_eq!(<f32 as Element>::DTYPE, Dtype::F32);
+    assert_eq!(<Complex<f64> as Element>::DTYPE, Dtype::C128);
+    assert!(Dtype::parse("f16").is_err());
+}
@@ -0,0 +1,101 @@
+// Tests for the element-wise combinators and the Hadamard and Kronecker products
+
+mod common;
+
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use num_complex::Complex;
+use common::m;
+
+#[test]
+fn test_map_keeps_shape_and_may_change_type() {
+    let a = m(vec![1.5, -2.0, 3.25, 0.0, 4.0, -0.5], 2, 3);
+    assert_eq!(
+        a.map(|x| x * 2.0),
+        m(vec![3.0, -4.0, 6.5, 0.0, 8.0, -1.0], 2, 3)
+    );
+
+    let rounded: Matrix<i64> = a.map(|x| x.round() as i64);
+    assert_eq!((rounded.rows, rounded.cols), (2, 3));
+    assert_eq!(rounded.data, vec![2, -2, 3, 0, 4, -1]);
+
+    let lifted: Matrix<Complex<f64>> = a.map(|x| Complex::new(x, 1.0));
+    assert_eq!(lifted[(1, 2)], Complex::new(-0.5, 1.0));
+}
+
+#[test]
+fn test_zip_map_and_apply() {
+    let a = m(vec![1.0, 2.0, 3.0, 4.0], 2, 2);
+    let b = m(vec![4.0, 3.0, 2.0, 1.0], 2, 2);
+    assert_eq!(
+        a.zip_map(&b, f64::max).unwrap(),
+        m(vec![4.0, 3.0, 3.0, 4.0], 2, 2)
+    );
+
+    let mut c = a.clone();
+    c.apply(|x| x * x - 1.0);
+    assert_eq!(c, m(vec![0.0, 3.0, 8.0, 15.0], 2, 2));
+}
+
+#[test]
+fn test_hadamard_product() {
+    let a = m(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3);
+    let b = m(vec![2.0, 0.0, -1.0, 0.5, 1.0, 3.0], 2, 3);
+    assert_eq!(
+        a.hadamard(&b).unwrap(),
+        m(vec![2.0, 0.0, -3.0, 2.0, 5.0, 18.0], 2, 3)
+    );
+    assert_eq!(a.hadamard(&b).unwrap(), b.hadamard(&a).unwrap());
+}
+
+#[test]
+fn test_shape_mismatch_is_an_error() {
+    let a: Matrix = Matrix::new(2, 3);
+    let b: Matrix = Matrix::new(3, 2);
+    assert!(matches!(
+        a.hadamard(&b),
+        Err(Error::DimensionMismatch {
+            left: (2, 3),
+            right: (3, 2),
+            ..
+        })
+    ));
+    assert!(a.zip_map(&b, |x, y| x + y).is_err());
+}
+
+#[test]
+fn test_kronecker_product() {
+    let a = m(vec![1.0, 2.0, 3.0, 4.0], 2, 2);
+    let b = m(vec![0.0, 5.0, 6.0, 7.0, 8.0, 9.0], 2, 3);
+    let expected = m(
+        vec![
+            0.0, 5.0, 6.0, 0.0, 10.0, 12.0, //
+            7.0, 8.0, 9.0, 14.0, 16.0, 18.0, //
+            0.0, 15.0, 18.0, 0.0, 20.0, 24.0, //
+            21.0, 24.0, 27.0, 28.0, 32.0, 36.0,
+        ],
+        4,
+        6,
+    );
+    assert_eq!(a.kronecker(&b), expected);
+}
+
+#[test]
+fn test_kronecker_identities() {
+    let a = m(vec![1.0, -2.0, 0.5, 3.0, 4.0, -1.0], 3, 2);
+    let one = m(vec![1.0], 1, 1);
+    assert_eq!(a.kronecker(&one), a);
+    assert_eq!(one.kronecker(&a), a);
+
+    // (A kron B)(C kron D) = AC kron BD
+    let b = m(vec![2.0, 1.0, 0.0, -1.0], 2, 2);
+    let c = m(vec![1.0, 0.0, 2.0, -3.0, 1.0, 1.0], 2, 3);
+    let d = m(vec![0.5, 1.0, 1.0, 2.0], 2, 2);
+    let left = a.kronecker(&b).multiply(&c.kronecker(&d)).unwrap();
+    let right = a.multiply(&c).unwrap().kronecker(&b.multiply(&d).unwrap());
+    assert!((&left - &right).norm_max() < 1e-12);
+
+    let empty: Matrix = Matrix::new(0, 2);
+    let k = empty.kronecker(&a);
+    assert_eq!((k.rows, k.cols), (0, 4));
+}
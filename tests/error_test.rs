@@ -0,0 +1,126 @@
+// Tests for the typed error values returned across the crate
+
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::kernel::Algorithm;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use distribiuted_matrix_multiplication::view::MatrixView;
+use std::error::Error as _;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+fn write_temp(contents: &str) -> NamedTempFile {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "{}", contents).unwrap();
+    file
+}
+
+#[test]
+fn test_missing_file_is_io_error_with_path_and_source() {
+    let err = Matrix::<f64>::load_from_file("/nonexistent/matrix.txt").unwrap_err();
+    match &err {
+        Error::Io { path, source } => {
+            assert_eq!(path.as_deref(), Some("/nonexistent/matrix.txt".as_ref()));
+            assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+        }
+        other => panic!("expected Io, got {:?}", other),
+    }
+    assert!(err.source().is_some());
+    assert!(err.to_string().contains("/nonexistent/matrix.txt"));
+}
+
+#[test]
+fn test_bad_value_is_parse_error_with_line_and_value() {
+    let file = write_temp("1.0 2.0\n3.0 abc\n");
+    let err = Matrix::<f64>::load_from_file(file.path()).unwrap_err();
+    match &err {
+        Error::Parse {
+            path, line, value, ..
+        } => {
+            assert_eq!(path.as_deref(), Some(file.path()));
+            assert_eq!(*line, Some(2));
+            assert_eq!(value, "abc");
+        }
+        other => panic!("expected Parse, got {:?}", other),
+    }
+    assert!(err.to_string().contains("on line 2"));
+}
+
+#[test]
+fn test_ragged_rows_and_empty_file_are_format_errors() {
+    let file = write_temp("1.0 2.0\n3.0\n");
+    match Matrix::<f64>::load_from_file(file.path()).unwrap_err() {
+        Error::Format { line, .. } => assert_eq!(line, Some(2)),
+        other => panic!("expected Format, got {:?}", other),
+    }
+
+    let empty = write_temp("\n\n");
+    assert!(matches!(
+        Matrix::<f64>::load_from_file(empty.path()),
+        Err(Error::Format { line: None, .. })
+    ));
+}
+
+#[test]
+fn test_multiply_mismatch_reports_both_shapes() {
+    let a: Matrix = Matrix::new(2, 3);
+    let b: Matrix = Matrix::new(2, 3);
+    match a.multiply(&b).unwrap_err() {
+        Error::DimensionMismatch {
+            operation,
+            left,
+            right,
+        } => {
+            assert_eq!(operation, "multiply");
+            assert_eq!(left, (2, 3));
+            assert_eq!(right, (2, 3));
+        }
+        other => panic!("expected DimensionMismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_shape_errors_from_constructors_and_accessors() {
+    assert!(matches!(
+        Matrix::from_vec(vec![1.0f64; 5], 2, 3),
+        Err(Error::DataLength {
+            len: 5,
+            rows: 2,
+            cols: 3
+        })
+    ));
+
+    let m: Matrix = Matrix::new(3, 4);
+    assert!(matches!(
+        m.get(3, 0),
+        Err(Error::OutOfBounds { dims: (3, 4), .. })
+    ));
+    match m.row_chunk(2, 2).unwrap_err() {
+        Error::OutOfBounds { start, size, dims } => {
+            assert_eq!(start, (2, 0));
+            assert_eq!(size, (2, 4));
+            assert_eq!(dims, (3, 4));
+        }
+        other => panic!("expected OutOfBounds, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_view_past_buffer_is_view_extent_error() {
+    let data = [0.0f64; 6];
+    assert!(matches!(
+        MatrixView::new(&data, 2, 3, 4, 1),
+        Err(Error::ViewExtent {
+            row_stride: 4,
+            len: 6,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_unknown_option_is_invalid_argument() {
+    let err = Algorithm::parse("fastest", 64).unwrap_err();
+    assert!(matches!(err, Error::InvalidArgument { .. }));
+    assert!(err.to_string().contains("fastest"));
+    assert!(err.source().is_none());
+}
@@ -0,0 +1,149 @@
+// Tests for GEMM semantics: C = alpha * op(A) * op(B) + beta * C
+
+mod common;
+
+use distribiuted_matrix_multiplication::kernel::{Algorithm, GemmSpec, Transpose};
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use distribiuted_matrix_multiplication::view::MatrixViewMut;
+use common::test_matrix;
+
+fn transposed(m: &Matrix) -> Matrix {
+    m.view().transpose().to_matrix()
+}
+
+/// alpha * A * B + beta * C computed element by element
+fn reference(alpha: f64, a: &Matrix, b: &Matrix, beta: f64, c: &Matrix) -> Matrix {
+    let mut result = Matrix::new(a.rows, b.cols);
+    for i in 0..a.rows {
+        for j in 0..b.cols {
+            let mut sum = 0.0;
+            for k in 0..a.cols {
+                sum += a.data[i * a.cols + k] * b.data[k * b.cols + j];
+            }
+            result.data[i * b.cols + j] = alpha * sum + beta * c.data[i * c.cols + j];
+        }
+    }
+    result
+}
+
+#[test]
+fn test_gemm_all_transpose_combinations() {
+    let (m, k, n) = (13, 9, 11);
+    let a = test_matrix(m, k, 1);
+    let b = test_matrix(k, n, 2);
+    let c = test_matrix(m, n, 3);
+    let expected = reference(2.0, &a, &b, -0.5, &c);
+
+    for trans_a in [Transpose::No, Transpose::Yes] {
+        for trans_b in [Transpose::No, Transpose::Yes] {
+            let stored_a = if trans_a.is_transposed() {
+                transposed(&a)
+            } else {
+                a.clone()
+            };
+            let stored_b = if trans_b.is_transposed() {
+                transposed(&b)
+            } else {
+                b.clone()
+            };
+            let spec = GemmSpec::default()
+                .with_alpha(2.0)
+                .with_beta(-0.5)
+                .with_trans_a(trans_a)
+                .with_trans_b(trans_b);
+
+            let result = Matrix::gemm(&spec, &stored_a, &stored_b, Some(c.clone())).unwrap();
+            assert_eq!(result.data, expected.data, "{:?} {:?}", trans_a, trans_b);
+        }
+    }
+}
+
+#[test]
+fn test_gemm_without_c_is_scaled_product() {
+    let a = test_matrix(5, 6, 4);
+    let b = test_matrix(6, 7, 5);
+    let spec = GemmSpec::default().with_alpha(3.0).with_beta(10.0);
+
+    let result = Matrix::gemm(&spec, &a, &b, None).unwrap();
+    let expected = reference(3.0, &a, &b, 0.0, &Matrix::new(5, 7));
+    assert_eq!(result.data, expected.data);
+}
+
+#[test]
+fn test_beta_zero_ignores_nan_in_c() {
+    let a = test_matrix(4, 3, 6);
+    let b = test_matrix(3, 4, 7);
+    let c = Matrix::from_vec(vec![f64::NAN; 16], 4, 4).unwrap();
+
+    let result = Matrix::gemm(&GemmSpec::default(), &a, &b, Some(c)).unwrap();
+    assert_eq!(result.data, a.multiply(&b).unwrap().data);
+}
+
+#[test]
+fn test_gemm_into_strided_block() {
+    let a = test_matrix(6, 5, 8);
+    let b = test_matrix(5, 4, 9);
+    let spec = GemmSpec::default().with_alpha(-1.0).with_beta(2.0);
+
+    // C^T stored in a wider buffer: C is 6x4 with column-major layout
+    let mut buffer = vec![1.0; 4 * 8];
+    let mut c = MatrixViewMut::new(&mut buffer, 6, 4, 1, 8).unwrap();
+    Matrix::gemm_into(&spec, &a, &b, &mut c).unwrap();
+    let got = c.as_view().to_matrix();
+
+    let expected = reference(
+        -1.0,
+        &a,
+        &b,
+        2.0,
+        &Matrix::from_vec(vec![1.0; 24], 6, 4).unwrap(),
+    );
+    assert_eq!(got.data, expected.data);
+    // Padding columns of the buffer are untouched
+    assert_eq!(buffer[6], 1.0);
+    assert_eq!(buffer[7], 1.0);
+}
+
+#[test]
+fn test_strassen_gemm_matches_blocked() {
+    let a = test_matrix(37, 41, 10);
+    let b = test_matrix(37, 29, 11);
+    let spec = GemmSpec::default()
+        .with_alpha(0.5)
+        .with_beta(3.0)
+        .with_trans_a(Transpose::Yes);
+
+    let mut blocked = test_matrix(41, 29, 12);
+    let mut strassen = blocked.clone();
+    Matrix::gemm_into(&spec, &a, &b, &mut blocked.view_mut()).unwrap();
+    Matrix::gemm_with_algorithm(
+        &spec,
+        &a,
+        &b,
+        &mut strassen.view_mut(),
+        Algorithm::Strassen { crossover: 8 },
+        None,
+    )
+    .unwrap();
+    assert_eq!(strassen.data, blocked.data);
+}
+
+#[test]
+fn test_gemm_dimension_errors() {
+    let a = test_matrix(3, 4, 0);
+    let b = test_matrix(3, 5, 0);
+    assert!(Matrix::gemm(&GemmSpec::default(), &a, &b, None).is_err());
+
+    let spec = GemmSpec::default().with_trans_a(Transpose::Yes);
+    assert!(Matrix::gemm(&spec, &a, &b, None).is_ok());
+    assert!(Matrix::gemm(&spec, &a, &b, Some(Matrix::new(5, 4))).is_err());
+}
+
+#[test]
+fn test_transpose_flags() {
+    assert_eq!(Transpose::parse("n").unwrap(), Transpose::No);
+    assert_eq!(Transpose::parse("T").unwrap(), Transpose::Yes);
+    assert!(Transpose::parse("c").is_err());
+    assert_eq!(Transpose::from_flag(true), Transpose::Yes);
+    assert_eq!(GemmSpec::<i32>::default().alpha, 1);
+}
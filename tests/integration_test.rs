@@ -133,7 +133,7 @@ fn test_matrix_file_format_parsing() {
     let content = "1.5  2.5   3.5\n4.0 5.0 6.0\n7.0\t8.0\t9.0\n";
     fs::write(&file_path, content).unwrap();
 
-    let matrix = Matrix::load_from_file(&file_path).unwrap();
+    let matrix: Matrix = Matrix::load_from_file(&file_path).unwrap();
     assert_eq!(matrix.rows, 3);
     assert_eq!(matrix.cols, 3);
     assert!((matrix.get(0, 0).unwrap() - 1.5).abs() < 0.001);
@@ -169,15 +169,15 @@ fn test_error_handling() {
 
     // Test non-existent file
     let non_existent = temp_dir.path().join("nonexistent.txt");
-    assert!(Matrix::load_from_file(&non_existent).is_err());
+    assert!(Matrix::<f64>::load_from_file(&non_existent).is_err());
 
     // Test invalid format
     let invalid_file = temp_dir.path().join("invalid.txt");
     fs::write(&invalid_file, "not a number 2.0\n").unwrap();
-    assert!(Matrix::load_from_file(&invalid_file).is_err());
+    assert!(Matrix::<f64>::load_from_file(&invalid_file).is_err());
 
     // Test incompatible multiplication
-    let a = Matrix::new(2, 3);
+    let a: Matrix = Matrix::new(2, 3);
     let b = Matrix::new(4, 2);
     assert!(a.multiply(&b).is_err());
 }
@@ -0,0 +1,109 @@
+// Tests for the blocked GEMM kernel
+
+mod common;
+
+use distribiuted_matrix_multiplication::kernel::TileConfig;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use common::test_matrix;
+
+fn naive_multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut result = Matrix::new(a.rows, b.cols);
+    for i in 0..a.rows {
+        for j in 0..b.cols {
+            let mut sum = 0.0;
+            for k in 0..a.cols {
+                sum += a.data[i * a.cols + k] * b.data[k * b.cols + j];
+            }
+            result.data[i * b.cols + j] = sum;
+        }
+    }
+    result
+}
+
+#[test]
+fn test_blocked_matches_naive_on_ragged_sizes() {
+    // Sizes chosen so that none of the dimensions are multiples of MR, NR or the tiles
+    let a = test_matrix(37, 29, 1);
+    let b = test_matrix(29, 43, 2);
+    let tiles = TileConfig::new(8, 5, 16).unwrap();
+
+    let expected = naive_multiply(&a, &b);
+    let result = a.multiply_with_tiles(&b, &tiles).unwrap();
+
+    assert_eq!(result.rows, 37);
+    assert_eq!(result.cols, 43);
+    assert_eq!(result.data, expected.data);
+}
+
+#[test]
+fn test_blocked_matches_naive_with_default_tiles() {
+    let a = test_matrix(130, 300, 3);
+    let b = test_matrix(300, 70, 4);
+
+    let expected = naive_multiply(&a, &b);
+    let result = Matrix::multiply_chunks(&a, &b).unwrap();
+
+    assert_eq!(result.data, expected.data);
+}
+
+#[test]
+fn test_blocked_vector_shapes() {
+    let row = test_matrix(1, 50, 5);
+    let col = test_matrix(50, 1, 6);
+
+    let inner = row.multiply(&col).unwrap();
+    assert_eq!(inner.data, naive_multiply(&row, &col).data);
+
+    let outer = col.multiply(&row).unwrap();
+    assert_eq!(outer.data, naive_multiply(&col, &row).data);
+}
+
+#[test]
+fn test_blocked_empty_inner_dimension() {
+    let a: Matrix = Matrix::new(3, 0);
+    let b = Matrix::new(0, 4);
+    let result = a.multiply(&b).unwrap();
+    assert_eq!(result.rows, 3);
+    assert_eq!(result.cols, 4);
+    assert!(result.data.iter().all(|&x| x == 0.0));
+}
+
+#[test]
+fn test_tile_config_rejects_zero() {
+    assert!(TileConfig::new(0, 8, 8).is_err());
+    assert!(TileConfig::new(8, 0, 8).is_err());
+    assert!(TileConfig::new(8, 8, 0).is_err());
+}
+
+#[test]
+fn test_parallel_matches_serial() {
+    let a = test_matrix(61, 45, 7);
+    let b = test_matrix(45, 33, 8);
+    let expected = naive_multiply(&a, &b);
+
+    for threads in [1, 3, 4, 8] {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        let result = Matrix::multiply_chunks_parallel(&a, &b, &pool).unwrap();
+        assert_eq!(
+            result.data, expected.data,
+            "mismatch with {} threads",
+            threads
+        );
+    }
+}
+
+#[test]
+fn test_parallel_fewer_rows_than_threads() {
+    let a = test_matrix(2, 10, 9);
+    let b = test_matrix(10, 5, 10);
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(8)
+        .build()
+        .unwrap();
+
+    let result = Matrix::multiply_chunks_parallel(&a, &b, &pool).unwrap();
+    assert_eq!(result.data, naive_multiply(&a, &b).data);
+}
@@ -0,0 +1,121 @@
+// Tests for LU decomposition, solves, determinants and inverses
+
+mod common;
+
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use num_complex::Complex;
+use common::assert_close;
+
+fn sample() -> Matrix {
+    Matrix::from_vec(
+        vec![
+            2.0, 1.0, 1.0, 0.0, 4.0, 3.0, 3.0, 1.0, 8.0, 7.0, 9.0, 5.0, 6.0, 7.0, 9.0, 8.0,
+        ],
+        4,
+        4,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_factors_reproduce_permuted_matrix() {
+    let a = sample();
+    let lu = a.lu().unwrap();
+    let (l, u) = (lu.l(), lu.u());
+    for i in 0..4 {
+        assert_eq!(l.get(i, i).unwrap(), 1.0);
+        for j in i + 1..4 {
+            assert_eq!(l.get(i, j).unwrap(), 0.0);
+            assert_eq!(u.get(j, i).unwrap(), 0.0);
+        }
+    }
+
+    let mut pa = Matrix::new(4, 4);
+    for (i, &src) in lu.permutation().iter().enumerate() {
+        for j in 0..4 {
+            pa.set(i, j, a.get(src, j).unwrap()).unwrap();
+        }
+    }
+    assert_close(&l.multiply(&u).unwrap(), &pa, 1e-12);
+    // Partial pivoting keeps every multiplier at most 1 in magnitude
+    assert!(l.data.iter().all(|x| x.abs() <= 1.0));
+}
+
+#[test]
+fn test_determinant() {
+    assert!((sample().lu().unwrap().determinant() - 8.0).abs() < 1e-10);
+
+    // A single row swap flips the sign
+    let swap = Matrix::from_vec(vec![0.0, 1.0, 1.0, 0.0], 2, 2).unwrap();
+    assert_eq!(swap.lu().unwrap().determinant(), -1.0);
+
+    let identity: Matrix = Matrix::identity(5);
+    assert_eq!(identity.lu().unwrap().determinant(), 1.0);
+}
+
+#[test]
+fn test_solve_multiple_right_hand_sides() {
+    let a = sample();
+    let x = Matrix::from_vec((0..8).map(|v| v as f64 - 3.0).collect(), 4, 2).unwrap();
+    let b = a.multiply(&x).unwrap();
+    let solved = a.lu().unwrap().solve(&b).unwrap();
+    assert_close(&solved, &x, 1e-10);
+
+    let wrong = Matrix::new(3, 1);
+    assert!(matches!(
+        a.lu().unwrap().solve(&wrong),
+        Err(Error::DimensionMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_inverse() {
+    let a = sample();
+    let inv = a.lu().unwrap().inverse().unwrap();
+    assert_close(&a.multiply(&inv).unwrap(), &Matrix::identity(4), 1e-12);
+    assert_close(&inv.multiply(&a).unwrap(), &Matrix::identity(4), 1e-12);
+}
+
+#[test]
+fn test_singular_matrix() {
+    let a = Matrix::from_vec(vec![1.0, 2.0, 3.0, 2.0, 4.0, 6.0, 1.0, 0.0, 1.0], 3, 3).unwrap();
+    let lu = a.lu().unwrap();
+    assert!(lu.is_singular());
+    assert_eq!(lu.determinant(), 0.0);
+    assert!(matches!(lu.inverse(), Err(Error::Singular { .. })));
+    let err = lu.solve(&Matrix::new(3, 1)).unwrap_err();
+    assert!(err.to_string().contains("singular"));
+
+    let zero: Matrix = Matrix::new(2, 2);
+    assert!(zero.lu().unwrap().is_singular());
+}
+
+#[test]
+fn test_non_square_is_rejected() {
+    let a: Matrix = Matrix::new(2, 3);
+    assert!(matches!(
+        a.lu(),
+        Err(Error::NotSquare {
+            rows: 2,
+            cols: 3,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_complex_and_f32() {
+    let i = Complex::new(0.0, 1.0);
+    let one = Complex::new(1.0, 0.0);
+    let a = Matrix::from_vec(vec![one, i, i, one], 2, 2).unwrap();
+    let lu = a.lu().unwrap();
+    // det = 1 - i^2 = 2
+    assert!((lu.determinant() - Complex::new(2.0, 0.0)).norm() < 1e-12);
+    let product = a.multiply(&lu.inverse().unwrap()).unwrap();
+    assert!((product.get(0, 0).unwrap() - one).norm() < 1e-12);
+    assert!(product.get(0, 1).unwrap().norm() < 1e-12);
+
+    let a = Matrix::from_vec(vec![4.0f32, 3.0, 6.0, 3.0], 2, 2).unwrap();
+    assert!((a.lu().unwrap().determinant() + 6.0).abs() < 1e-5);
+}
@@ -0,0 +1,199 @@
+// Tests for memory-mapped binary matrix files
+
+use distribiuted_matrix_multiplication::binary::Header;
+use distribiuted_matrix_multiplication::coordinator::ChunkSource;
+use distribiuted_matrix_multiplication::element::Dtype;
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::mapped::MappedMatrix;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use num_complex::Complex;
+use std::fs;
+use tempfile::tempdir;
+
+fn sample(rows: usize, cols: usize) -> Matrix {
+    let data = (0..rows * cols).map(|i| i as f64 * 0.5 - 3.0).collect();
+    Matrix::from_vec(data, rows, cols).unwrap()
+}
+
+#[test]
+fn test_chunks_match_the_loaded_matrix() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("a.bin");
+    let m = sample(7, 5);
+    m.save_binary(&path, true).unwrap();
+
+    let mapped: MappedMatrix = MappedMatrix::open(&path).unwrap();
+    assert_eq!((mapped.rows(), mapped.cols()), (7, 5));
+    assert!(mapped.header().checksum);
+    assert_eq!(mapped.path(), path);
+    mapped.verify_checksum().unwrap();
+
+    assert_eq!(mapped.to_matrix(), m);
+    assert_eq!(
+        mapped.row_chunk(2, 3).unwrap(),
+        m.row_chunk(2, 3).unwrap().to_matrix()
+    );
+    assert_eq!(
+        mapped.col_chunk(1, 4).unwrap(),
+        m.col_chunk(1, 4).unwrap().to_matrix()
+    );
+    assert_eq!(
+        mapped.block(3, 2, 2, 2).unwrap(),
+        m.block(3, 2, 2, 2).unwrap().to_matrix()
+    );
+    assert_eq!(mapped.get(6, 4).unwrap(), m.get(6, 4).unwrap());
+    assert_eq!(mapped.row_chunk(7, 0).unwrap().rows, 0);
+}
+
+#[test]
+fn test_other_element_types_and_byte_order() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("z.bin");
+    let z: Matrix<Complex<f32>> = Matrix::from_vec(
+        vec![
+            Complex::new(1.0, -1.0),
+            Complex::new(2.5, 0.0),
+            Complex::new(0.0, 3.0),
+        ],
+        3,
+        1,
+    )
+    .unwrap();
+    z.save_binary(&path, false).unwrap();
+    let mapped: MappedMatrix<Complex<f32>> = MappedMatrix::open(&path).unwrap();
+    assert_eq!(mapped.row_chunk(1, 2).unwrap().data, z.data[1..]);
+
+    // A file written on a machine of the other byte order
+    let mut header = Header::new(Dtype::I64, 2, 2, false);
+    header.big_endian = !header.big_endian;
+    let mut bytes = header.encode().to_vec();
+    for value in [1i64, -2, 3, i64::MAX] {
+        bytes.extend_from_slice(&if header.big_endian {
+            value.to_be_bytes()
+        } else {
+            value.to_le_bytes()
+        });
+    }
+    let swapped = dir.path().join("swapped.bin");
+    fs::write(&swapped, bytes).unwrap();
+    let mapped: MappedMatrix<i64> = MappedMatrix::open(&swapped).unwrap();
+    assert_eq!(mapped.col_chunk(1, 1).unwrap().data, vec![-2, i64::MAX]);
+    assert_eq!(mapped.to_matrix(), Matrix::<i64>::load(&swapped).unwrap());
+}
+
+#[test]
+fn test_native_files_are_borrowed_in_place() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("a.bin");
+    let m = sample(6, 4);
+    m.save_binary(&path, false).unwrap();
+    let mapped: MappedMatrix = MappedMatrix::open(&path).unwrap();
+    let view = mapped.view().unwrap();
+    assert_eq!(view.to_matrix(), m);
+
+    // Row chunks point into the map; column chunks are strided views of it
+    let data = view.as_slice().unwrap().as_ptr_range();
+    let mut borrowed = Vec::new();
+    for by_columns in [false, true] {
+        mapped
+            .with_chunk(by_columns, 1, 2, &mut |chunk| {
+                borrowed.push(chunk.as_slice().map(|s| data.contains(&s.as_ptr())));
+                Ok(())
+            })
+            .unwrap();
+    }
+    assert_eq!(borrowed, vec![Some(true), None]);
+
+    // The other byte order has to be decoded
+    let mut header = Header::new(Dtype::F64, 1, 2, false);
+    header.big_endian = !header.big_endian;
+    let mut bytes = header.encode().to_vec();
+    for value in [1.5f64, -2.0] {
+        bytes.extend_from_slice(&if header.big_endian {
+            value.to_be_bytes()
+        } else {
+            value.to_le_bytes()
+        });
+    }
+    let swapped = dir.path().join("swapped.bin");
+    fs::write(&swapped, bytes).unwrap();
+    let mapped: MappedMatrix = MappedMatrix::open(&swapped).unwrap();
+    assert!(mapped.view().is_none());
+    mapped
+        .with_chunk(false, 0, 1, &mut |chunk| {
+            assert_eq!(chunk.to_matrix().data, vec![1.5, -2.0]);
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn test_malformed_files_are_rejected_on_open() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("m.bin");
+    sample(3, 3).save_binary(&path, true).unwrap();
+    let bytes = fs::read(&path).unwrap();
+
+    let cases = [
+        bytes[..bytes.len() - 1].to_vec(),
+        [&bytes[..], b"x"].concat(),
+        bytes[..10].to_vec(),
+        b"1 2\n3 4\n".to_vec(),
+    ];
+    for case in cases {
+        fs::write(&path, &case).unwrap();
+        match MappedMatrix::<f64>::open(&path) {
+            Err(Error::Format { path: Some(p), .. }) => assert_eq!(p, path),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    fs::write(&path, &bytes).unwrap();
+    assert!(matches!(
+        MappedMatrix::<f32>::open(&path),
+        Err(Error::Format { .. })
+    ));
+    assert!(matches!(
+        MappedMatrix::<f64>::open(dir.path().join("missing.bin")),
+        Err(Error::Io { .. })
+    ));
+}
+
+#[test]
+fn test_corruption_is_found_by_the_checksum() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("m.bin");
+    sample(4, 4).save_binary(&path, true).unwrap();
+    let mut bytes = fs::read(&path).unwrap();
+    bytes[Header::SIZE + 20] ^= 0x01;
+    fs::write(&path, &bytes).unwrap();
+
+    // Opening does not read the data, verifying does
+    let mapped: MappedMatrix = MappedMatrix::open(&path).unwrap();
+    match mapped.verify_checksum() {
+        Err(Error::Format { path: Some(p), .. }) => assert_eq!(p, path),
+        other => panic!("unexpected {:?}", other),
+    }
+
+    let unchecked = dir.path().join("unchecked.bin");
+    sample(4, 4).save_binary(&unchecked, false).unwrap();
+    let mapped: MappedMatrix = MappedMatrix::open(&unchecked).unwrap();
+    assert!(mapped.verify_checksum().is_ok());
+}
+
+#[test]
+fn test_out_of_bounds_blocks() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("m.bin");
+    sample(3, 2).save_binary(&path, false).unwrap();
+    let mapped: MappedMatrix = MappedMatrix::open(&path).unwrap();
+    assert!(matches!(
+        mapped.row_chunk(2, 2),
+        Err(Error::OutOfBounds { dims: (3, 2), .. })
+    ));
+    assert!(matches!(
+        mapped.block(0, 1, 1, 2),
+        Err(Error::OutOfBounds { .. })
+    ));
+    assert!(mapped.get(3, 0).is_err());
+}
@@ -0,0 +1,249 @@
+// Tests for Matrix Market (.mtx) reading and writing
+
+use distribiuted_matrix_multiplication::element::Element;
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use distribiuted_matrix_multiplication::matrix_market::{
+    Layout, MarketElement, MarketField, Symmetry,
+};
+use num_complex::Complex;
+use std::fs;
+use tempfile::tempdir;
+
+fn read<T: Element>(text: &str) -> Result<Matrix<T>, Error> {
+    Matrix::read_matrix_market(text.as_bytes())
+}
+
+fn write<T: Element>(matrix: &Matrix<T>, layout: Layout, symmetry: Symmetry) -> String {
+    let mut bytes = Vec::new();
+    matrix
+        .write_matrix_market(&mut bytes, layout, symmetry)
+        .unwrap();
+    String::from_utf8(bytes).unwrap()
+}
+
+#[test]
+fn test_array_is_column_major() {
+    let text = "%%MatrixMarket matrix array real general\n\
+                % a comment\n\
+                \n\
+                2 3\n1\n4\n2\n5\n3\n6\n";
+    let m: Matrix = read(text).unwrap();
+    assert_eq!(
+        m,
+        Matrix::from_vec(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3).unwrap()
+    );
+
+    let written = write(&m, Layout::Array, Symmetry::General);
+    assert!(written.starts_with("%%MatrixMarket matrix array real general\n2 3\n1\n4\n"));
+    assert_eq!(read::<f64>(&written).unwrap(), m);
+}
+
+#[test]
+fn test_coordinate_sums_duplicates_and_leaves_zeros() {
+    let text = "%%MatrixMarket matrix coordinate integer general\n\
+                3 2 4\n1 1 5\n3 2 -7\n1 1 2\n2 1 1\n";
+    let m: Matrix<i64> = read(text).unwrap();
+    assert_eq!(m, Matrix::from_vec(vec![7, 0, 1, 0, 0, -7], 3, 2).unwrap());
+
+    let written = write(&m, Layout::Coordinate, Symmetry::General);
+    assert_eq!(
+        written,
+        "%%MatrixMarket matrix coordinate integer general\n3 2 3\n1 1 7\n2 1 1\n3 2 -7\n"
+    );
+    assert_eq!(read::<i64>(&written).unwrap(), m);
+}
+
+#[test]
+fn test_symmetric_storage_expands_on_load() {
+    let coordinate = "%%MatrixMarket matrix coordinate real symmetric\n\
+                      3 3 4\n1 1 4\n2 1 1\n3 2 -2\n3 3 9\n";
+    let array = "%%MatrixMarket matrix array real symmetric\n3 3\n4\n1\n0\n0\n-2\n9\n";
+    let expected: Matrix =
+        Matrix::from_vec(vec![4.0, 1.0, 0.0, 1.0, 0.0, -2.0, 0.0, -2.0, 9.0], 3, 3).unwrap();
+    assert_eq!(read::<f64>(coordinate).unwrap(), expected);
+    assert_eq!(read::<f64>(array).unwrap(), expected);
+
+    for layout in [Layout::Array, Layout::Coordinate] {
+        let written = write(&expected, layout, Symmetry::Symmetric);
+        assert_eq!(read::<f64>(&written).unwrap(), expected);
+    }
+    assert_eq!(
+        write(&expected, Layout::Coordinate, Symmetry::Symmetric)
+            .lines()
+            .nth(1),
+        Some("3 3 4")
+    );
+}
+
+#[test]
+fn test_skew_symmetric_negates_the_mirror() {
+    let text = "%%MatrixMarket matrix coordinate real skew-symmetric\n2 2 1\n2 1 3.5\n";
+    let m: Matrix = read(text).unwrap();
+    assert_eq!(
+        m,
+        Matrix::from_vec(vec![0.0, -3.5, 3.5, 0.0], 2, 2).unwrap()
+    );
+
+    let written = write(&m, Layout::Array, Symmetry::SkewSymmetric);
+    assert_eq!(
+        written,
+        "%%MatrixMarket matrix array real skew-symmetric\n2 2\n3.5\n"
+    );
+    assert_eq!(read::<f64>(&written).unwrap(), m);
+
+    let diagonal = "%%MatrixMarket matrix coordinate real skew-symmetric\n2 2 1\n1 1 3\n";
+    assert!(matches!(
+        read::<f64>(diagonal),
+        Err(Error::Format { line: Some(3), .. })
+    ));
+}
+
+#[test]
+fn test_pattern_and_complex_fields() {
+    let pattern = "%%MatrixMarket matrix coordinate pattern symmetric\n2 2 2\n1 1\n2 1\n";
+    let m: Matrix<f32> = read(pattern).unwrap();
+    assert_eq!(m, Matrix::from_vec(vec![1.0, 1.0, 1.0, 0.0], 2, 2).unwrap());
+
+    let hermitian = "%%MatrixMarket matrix coordinate complex hermitian\n\
+                     2 2 2\n1 1 2 0\n2 1 1 -0.5\n";
+    let z: Matrix<Complex<f64>> = read(hermitian).unwrap();
+    assert_eq!(z.data[1], Complex::new(1.0, 0.5));
+    assert_eq!(z.data[2], Complex::new(1.0, -0.5));
+    let written = write(&z, Layout::Coordinate, Symmetry::Hermitian);
+    assert!(written.starts_with("%%MatrixMarket matrix coordinate complex hermitian\n"));
+    assert_eq!(read::<Complex<f64>>(&written).unwrap(), z);
+
+    // Real files load into complex matrices, but not the other way round
+    let real = "%%MatrixMarket matrix array real general\n1 1\n2.5\n";
+    let c: Matrix<Complex<f32>> = read(real).unwrap();
+    assert_eq!(c.data, vec![Complex::new(2.5, 0.0)]);
+    assert!(read::<f64>(hermitian).is_err());
+    assert_eq!(<Complex<f32>>::FIELD, MarketField::Complex);
+}
+
+#[test]
+fn test_malformed_files_are_rejected() {
+    let cases = [
+        ("", None),
+        ("%%MatrixMarket matrix array real\n1 1\n1\n", Some(1)),
+        ("%%MatrixMarket vector array real general\n1\n1\n", Some(1)),
+        (
+            "%%MatrixMarket matrix array pattern general\n1 1\n",
+            Some(1),
+        ),
+        (
+            "%%MatrixMarket matrix array real general\n1 1 1\n1\n",
+            Some(2),
+        ),
+        ("%%MatrixMarket matrix array real symmetric\n2 3\n", Some(2)),
+        (
+            "%%MatrixMarket matrix array real general\n1 2\n1\n2\n3\n",
+            Some(5),
+        ),
+        ("%%MatrixMarket matrix array real general\n2 1\n1\n", None),
+        (
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n3 1 1\n",
+            Some(3),
+        ),
+        (
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n1 1\n",
+            Some(3),
+        ),
+        (
+            "%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1\n",
+            None,
+        ), // Sizes too large to allocate
+        (
+            "%%MatrixMarket matrix coordinate real general\n4294967296 4294967296 0\n",
+            Some(2),
+        ),
+        (
+            "%%MatrixMarket matrix array real general\n% big\n1152921504606846976 9\n",
+            Some(3),
+        ),
+    ];
+    for (text, line) in cases {
+        match read::<f64>(text) {
+            Err(Error::Format { line: found, .. }) => assert_eq!(found, line, "{:?}", text),
+            other => panic!("{:?} gave {:?}", text, other),
+        }
+    }
+    let bad = "%%MatrixMarket matrix array integer general\n1 1\n1.5\n";
+    assert!(matches!(
+        read::<i32>(bad),
+        Err(Error::Parse { line: Some(3), .. })
+    ));
+}
+
+#[test]
+fn test_save_checks_symmetry_and_reports_path() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("m.mtx");
+    let m: Matrix = Matrix::from_vec(vec![1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+
+    assert!(matches!(
+        m.save_matrix_market(&path, Layout::Array, Symmetry::Symmetric),
+        Err(Error::InvalidArgument { .. })
+    ));
+    assert!(!path.exists());
+    let wide: Matrix = Matrix::new(1, 2);
+    assert!(matches!(
+        wide.save_matrix_market(&path, Layout::Array, Symmetry::Symmetric),
+        Err(Error::NotSquare { .. })
+    ));
+
+    m.save_matrix_market(&path, Layout::Coordinate, Symmetry::General)
+        .unwrap();
+    assert_eq!(Matrix::<f64>::load_matrix_market(&path).unwrap(), m);
+
+    fs::write(&path, "%%MatrixMarket matrix array real general\n1 1\nx\n").unwrap();
+    match Matrix::<f64>::load_matrix_market(&path) {
+        Err(Error::Parse {
+            path: Some(p),
+            line: Some(3),
+            ..
+        }) => assert_eq!(p, path),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_load_and_save_by_banner_and_extension() {
+    let dir = tempdir().unwrap();
+    let z: Matrix<Complex<f64>> = Matrix::from_vec(
+        vec![
+            Complex::new(1.0, -2.0),
+            Complex::new(0.0, 0.5),
+            Complex::new(3.0, 0.0),
+        ],
+        3,
+        1,
+    )
+    .unwrap();
+    let path = dir.path().join("z.mtx");
+    z.save(&path).unwrap();
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .starts_with("%%MatrixMarket matrix array complex general\n"));
+    assert_eq!(Matrix::<Complex<f64>>::load(&path).unwrap(), z);
+
+    // A SuiteSparse download is recognized by its banner whatever its name
+    let renamed = dir.path().join("bcsstk01.txt");
+    fs::write(
+        &renamed,
+        "%%matrixmarket MATRIX coordinate real symmetric\n2 2 2\n1 1 4\n2 1 -1\n",
+    )
+    .unwrap();
+    assert_eq!(
+        Matrix::<f64>::load(&renamed).unwrap(),
+        Matrix::from_vec(vec![4.0, -1.0, -1.0, 0.0], 2, 2).unwrap()
+    );
+
+    // A .mtx file without a banner gets the Matrix Market error
+    fs::write(&path, "1 2\n3 4\n").unwrap();
+    assert!(matches!(
+        Matrix::<f64>::load(&path),
+        Err(Error::Format { line: Some(1), .. })
+    ));
+}
@@ -6,7 +6,7 @@ use tempfile::NamedTempFile;
 
 #[test]
 fn test_matrix_creation() {
-    let m = Matrix::new(3, 4);
+    let m: Matrix = Matrix::new(3, 4);
     assert_eq!(m.rows, 3);
     assert_eq!(m.cols, 4);
     assert_eq!(m.data.len(), 12);
@@ -28,7 +28,7 @@ fn test_load_and_save() {
     writeln!(file, "4.0 5.0 6.0").unwrap();
     file.flush().unwrap();
 
-    let m = Matrix::load_from_file(file.path()).unwrap();
+    let m: Matrix = Matrix::load_from_file(file.path()).unwrap();
     assert_eq!(m.rows, 2);
     assert_eq!(m.cols, 3);
     assert_eq!(m.get(0, 0).unwrap(), 1.0);
@@ -140,7 +140,7 @@ fn test_multiply_chunks() {
 #[test]
 fn test_load_empty_file() {
     let file = NamedTempFile::new().unwrap();
-    assert!(Matrix::load_from_file(file.path()).is_err());
+    assert!(Matrix::<f64>::load_from_file(file.path()).is_err());
 }
 
 #[test]
@@ -150,18 +150,18 @@ fn test_load_inconsistent_columns() {
     writeln!(file, "4.0 5.0").unwrap(); // Different number of columns
     file.flush().unwrap();
     
-    assert!(Matrix::load_from_file(file.path()).is_err());
+    assert!(Matrix::<f64>::load_from_file(file.path()).is_err());
 }
 
 #[test]
 fn test_load_with_empty_lines() {
     let mut file = NamedTempFile::new().unwrap();
     writeln!(file, "1.0 2.0").unwrap();
-    writeln!(file, "").unwrap(); // Empty line
+    writeln!(file).unwrap(); // Empty line
     writeln!(file, "3.0 4.0").unwrap();
     file.flush().unwrap();
     
-    let m = Matrix::load_from_file(file.path()).unwrap();
+    let m: Matrix = Matrix::load_from_file(file.path()).unwrap();
     assert_eq!(m.rows, 2);
     assert_eq!(m.cols, 2);
 }
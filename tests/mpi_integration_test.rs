@@ -1,7 +1,19 @@
 mod common;
 
+use distribiuted_matrix_multiplication::accumulate::Accumulation;
+use distribiuted_matrix_multiplication::binary::Header;
+use distribiuted_matrix_multiplication::coordinator::{open_operand, power_rounds, PowerRound};
+use distribiuted_matrix_multiplication::csv::CsvOverrides;
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::kernel::{GemmSpec, Transpose};
 use distribiuted_matrix_multiplication::matrix::Matrix;
-use common::mpi_mock::{simulate_worker, test_mpi, TestMessageQueue};
+use distribiuted_matrix_multiplication::mpi_utils::WorkAssignment;
+use std::fs;
+use tempfile::tempdir;
+use common::mpi_mock::{
+    simulate_gemm, simulate_power, simulate_worker, test_mpi, TestMessageQueue,
+};
+use common::test_matrix;
 
 #[test]
 fn test_coordinator_worker_communication() {
@@ -13,7 +25,13 @@ fn test_coordinator_worker_communication() {
     let coordinator_rank = 0;
     let worker_rank = 1;
 
-    test_mpi::send_work_assignment(&queue, coordinator_rank, worker_rank, 0, 2, 0, 2);
+    test_mpi::send_work_assignment(
+        &queue,
+        coordinator_rank,
+        worker_rank,
+        &WorkAssignment::new(0, 2, 0, 2),
+    );
+    test_mpi::send_scalars(&queue, coordinator_rank, worker_rank, &[1.0, 0.0]);
 
     let row_chunk = matrix_a.get_row_chunk(0, 2).unwrap();
     test_mpi::send_row_chunk(&queue, coordinator_rank, worker_rank, &row_chunk);
@@ -55,13 +73,15 @@ fn test_distributed_multiplication_with_multiple_workers() {
     // Worker 1: rows [0, 2), cols [0, 2)
     // Worker 2: rows [2, 4), cols [2, 4)
 
-    test_mpi::send_work_assignment(&queue, coordinator_rank, 1, 0, 2, 0, 2);
+    test_mpi::send_work_assignment(&queue, coordinator_rank, 1, &WorkAssignment::new(0, 2, 0, 2));
+    test_mpi::send_scalars(&queue, coordinator_rank, 1, &[1.0, 0.0]);
     let row_chunk_1 = matrix_a.get_row_chunk(0, 2).unwrap();
     test_mpi::send_row_chunk(&queue, coordinator_rank, 1, &row_chunk_1);
     let col_chunk_1 = matrix_b.get_col_chunk(0, 2).unwrap();
     test_mpi::send_col_chunk(&queue, coordinator_rank, 1, &col_chunk_1);
 
-    test_mpi::send_work_assignment(&queue, coordinator_rank, 2, 2, 4, 2, 4);
+    test_mpi::send_work_assignment(&queue, coordinator_rank, 2, &WorkAssignment::new(2, 4, 2, 4));
+    test_mpi::send_scalars(&queue, coordinator_rank, 2, &[1.0, 0.0]);
     let row_chunk_2 = matrix_a.get_row_chunk(2, 2).unwrap();
     test_mpi::send_row_chunk(&queue, coordinator_rank, 2, &row_chunk_2);
     let col_chunk_2 = matrix_b.get_col_chunk(2, 2).unwrap();
@@ -103,7 +123,12 @@ fn test_worker_with_no_work() {
     let coordinator_rank = 0;
     let worker_rank = 1;
 
-    test_mpi::send_work_assignment(&queue, coordinator_rank, worker_rank, 5, 5, 5, 5);
+    test_mpi::send_work_assignment(
+        &queue,
+        coordinator_rank,
+        worker_rank,
+        &WorkAssignment::new(5, 5, 5, 5),
+    );
 
     let result = simulate_worker(&queue, worker_rank, coordinator_rank);
     assert!(result.is_err());
@@ -132,3 +157,121 @@ fn test_matrix_communication_roundtrip() {
     assert_eq!(original.data, received.data);
 }
 
+
+#[test]
+fn test_power_rounds_with_more_workers_than_rows() {
+    let a = Matrix::from_vec(vec![1.0, 2.0, 0.0, -1.0, 1.0, 3.0, 2.0, 0.0, 1.0], 3, 3).unwrap();
+
+    // Odd exponents end on a multiplication by A; ranks 4 and 5 have no rows
+    for k in [3, 5, 7] {
+        let queue = TestMessageQueue::new();
+        let (result, computed) = simulate_power(&queue, &GemmSpec::default(), &a, k, 5).unwrap();
+        assert_eq!(result, a.pow(k).unwrap(), "k = {}", k);
+        let rounds = power_rounds(k).len();
+        assert_eq!(computed, vec![rounds, rounds, rounds, 0, 0], "k = {}", k);
+    }
+    assert_eq!(
+        power_rounds(5),
+        vec![
+            PowerRound::Square,
+            PowerRound::Square,
+            PowerRound::MultiplyByA
+        ]
+    );
+}
+
+#[test]
+fn test_work_assignment_encoding() {
+    let assignment = WorkAssignment {
+        trans_a: Transpose::Yes,
+        reuse_a: true,
+        more: true,
+        accumulation: Accumulation::Compensated,
+        reproducible: true,
+        ..WorkAssignment::new(2, 5, 0, 7)
+    };
+    let mut msg = assignment.encode();
+    assert_eq!(WorkAssignment::decode(&msg, 0).unwrap(), assignment);
+
+    // An unknown accumulation mode is an error, not a silent fallback
+    for bad in [Accumulation::ALL.len() as i32, -1] {
+        msg[9] = bad;
+        assert!(matches!(
+            WorkAssignment::decode(&msg, 3),
+            Err(Error::Mpi { rank: 3, .. })
+        ));
+    }
+}
+
+#[test]
+fn test_reproducible_power_is_the_same_for_any_worker_count() {
+    let data = (0..36)
+        .map(|i| ((i * 37 % 23) as f64 - 11.0) / 7.0)
+        .collect();
+    let a = Matrix::from_vec(data, 6, 6).unwrap();
+    let spec = GemmSpec::default()
+        .with_accumulation(Accumulation::Pairwise)
+        .with_reproducible(true);
+
+    let powers: Vec<Matrix> = [1, 2, 4, 7]
+        .into_iter()
+        .map(|workers| {
+            let queue = TestMessageQueue::new();
+            simulate_power(&queue, &spec, &a, 11, workers).unwrap().0
+        })
+        .collect();
+    for power in &powers[1..] {
+        let same_bits = power
+            .data
+            .iter()
+            .zip(&powers[0].data)
+            .all(|(x, y)| x.to_bits() == y.to_bits());
+        assert!(same_bits);
+    }
+}
+
+#[test]
+fn test_gemm_streams_mapped_operands_into_a_binary_file() {
+    let dir = tempdir().unwrap();
+    let a = test_matrix(7, 5, 1);
+    let b = test_matrix(5, 4, 2);
+    let a_path = dir.path().join("a.bin");
+    let at_path = dir.path().join("at.bin");
+    let b_path = dir.path().join("b.bin");
+    let output = dir.path().join("c.bin");
+    a.save_binary(&a_path, true).unwrap();
+    a.transpose().save_binary(&at_path, true).unwrap();
+    b.save_binary(&b_path, false).unwrap();
+
+    let csv = CsvOverrides::default();
+    let expected = Matrix::gemm(&GemmSpec::default().with_alpha(2.0), &a, &b, None).unwrap();
+    // A stored transposed is sent by columns
+    for (path, trans_a) in [(&a_path, Transpose::No), (&at_path, Transpose::Yes)] {
+        for workers in [1, 3, 9] {
+            let matrix_a = open_operand::<f64>(path, &csv, true).unwrap();
+            let matrix_b = open_operand::<f64>(&b_path, &csv, true).unwrap();
+            let spec = GemmSpec::default().with_alpha(2.0).with_trans_a(trans_a);
+            let queue = TestMessageQueue::new();
+            simulate_gemm(
+                &queue,
+                &spec,
+                matrix_a.as_ref(),
+                matrix_b.as_ref(),
+                workers,
+                &output,
+            )
+            .unwrap();
+            assert_eq!(Matrix::<f64>::load_binary(&output).unwrap(), expected);
+        }
+    }
+
+    // The checksum is only read up front when asked for
+    let mut bytes = fs::read(&a_path).unwrap();
+    bytes[Header::SIZE + 3] ^= 0x40;
+    fs::write(&a_path, bytes).unwrap();
+    assert!(open_operand::<f64>(&a_path, &csv, false).is_ok());
+    assert!(matches!(
+        open_operand::<f64>(&a_path, &csv, true),
+        Err(Error::Format { .. })
+    ));
+}
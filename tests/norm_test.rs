@@ -0,0 +1,95 @@
+// Tests for matrix norms and the condition number estimate
+
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use num_complex::Complex;
+
+fn sample() -> Matrix {
+    Matrix::from_vec(vec![1.0, -2.0, 3.0, -4.0, 5.0, -6.0], 2, 3).unwrap()
+}
+
+#[test]
+fn test_norms_of_small_matrix() {
+    let m = sample();
+    assert!((m.norm_frobenius() - 91.0f64.sqrt()).abs() < 1e-12);
+    assert_eq!(m.norm_one(), 9.0);
+    assert_eq!(m.norm_inf(), 15.0);
+    assert_eq!(m.norm_max(), 6.0);
+
+    // Transposing swaps the 1- and infinity norms
+    let t = m.transpose();
+    assert_eq!(t.norm_one(), 15.0);
+    assert_eq!(t.norm_inf(), 9.0);
+}
+
+#[test]
+fn test_norms_of_empty_and_zero_matrices() {
+    let empty: Matrix = Matrix::new(0, 3);
+    assert_eq!(empty.norm_frobenius(), 0.0);
+    assert_eq!(empty.norm_one(), 0.0);
+    assert_eq!(empty.norm_inf(), 0.0);
+    let zero: Matrix = Matrix::new(3, 3);
+    assert_eq!(zero.norm_frobenius(), 0.0);
+    assert_eq!(zero.norm_max(), 0.0);
+}
+
+#[test]
+fn test_frobenius_does_not_overflow() {
+    let m: Matrix = Matrix::from_vec(vec![1e200, 1e200, -1e200, 1e200], 2, 2).unwrap();
+    assert!((m.norm_frobenius() / 2e200 - 1.0).abs() < 1e-12);
+    let tiny: Matrix = Matrix::from_vec(vec![3e-200, 4e-200], 1, 2).unwrap();
+    assert!((tiny.norm_frobenius() / 5e-200 - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_complex_norms_use_modulus() {
+    let m: Matrix<Complex<f64>> =
+        Matrix::from_vec(vec![Complex::new(3.0, 4.0), Complex::new(0.0, -1.0)], 1, 2).unwrap();
+    assert_eq!(m.norm_max(), 5.0);
+    assert_eq!(m.norm_inf(), 6.0);
+    assert!((m.norm_frobenius() - 26.0f64.sqrt()).abs() < 1e-12);
+}
+
+#[test]
+fn test_condition_estimate_matches_exact_value() {
+    let identity: Matrix = Matrix::identity(4);
+    assert!((identity.condition_estimate().unwrap() - 1.0).abs() < 1e-12);
+
+    let diagonal: Matrix =
+        Matrix::from_vec(vec![100.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.01], 3, 3).unwrap();
+    assert!((diagonal.condition_estimate().unwrap() - 1e4).abs() < 1e-6);
+
+    // Exact condition number from the explicit inverse
+    let a = Matrix::from_vec(
+        vec![
+            4.0, -2.0, 1.0, 3.0, 6.0, -4.0, 2.0, 1.0, 8.0, 5.0, 0.5, -3.0, 1.0, 7.0, 2.0, 9.0,
+        ],
+        4,
+        4,
+    )
+    .unwrap();
+    let exact = a.norm_one() * a.lu().unwrap().inverse().unwrap().norm_one();
+    let estimate = a.condition_estimate().unwrap();
+    assert!(estimate <= exact * (1.0 + 1e-12));
+    assert!(estimate >= exact / 3.0);
+}
+
+#[test]
+fn test_condition_estimate_of_ill_conditioned_and_singular() {
+    // Hilbert matrices are notoriously ill-conditioned
+    let n = 8;
+    let hilbert = Matrix::from_vec(
+        (0..n * n)
+            .map(|k| 1.0 / ((k / n + k % n + 1) as f64))
+            .collect(),
+        n,
+        n,
+    )
+    .unwrap();
+    assert!(hilbert.condition_estimate().unwrap() > 1e9);
+
+    let singular: Matrix = Matrix::from_vec(vec![1.0, 2.0, 2.0, 4.0], 2, 2).unwrap();
+    assert!(singular.condition_estimate().unwrap().is_infinite());
+
+    let rect: Matrix = Matrix::new(2, 3);
+    assert!(rect.condition_estimate().is_err());
+}
@@ -0,0 +1,219 @@
+// Tests for NumPy .npy and .npz files
+
+use distribiuted_matrix_multiplication::element::Element;
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use distribiuted_matrix_multiplication::npy::{self, MAGIC};
+use num_complex::Complex;
+use std::fs;
+use tempfile::tempdir;
+
+fn round_trip<T: Element>(matrix: &Matrix<T>, fortran_order: bool) -> Matrix<T> {
+    let mut bytes = Vec::new();
+    matrix.view().write_npy(&mut bytes, fortran_order).unwrap();
+    let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    assert_eq!((10 + header_len) % 64, 0);
+    assert_eq!(bytes[9 + header_len], b'\n');
+    Matrix::read_npy(&bytes[..]).unwrap()
+}
+
+/// A version 1.0 file with the given header dict and data
+fn npy_file(dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&[1, 0]);
+    bytes.extend_from_slice(&(dict.len() as u16 + 1).to_le_bytes());
+    bytes.extend_from_slice(dict.as_bytes());
+    bytes.push(b'\n');
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+#[test]
+fn test_round_trip_every_dtype_in_both_orders() {
+    let m: Matrix = Matrix::from_vec(vec![0.1, -0.0, f64::MAX, 1.0 / 3.0, 5.0, 6.0], 2, 3).unwrap();
+    let f: Matrix<f32> = Matrix::from_vec(vec![0.1, -2.5e-38, 7.0], 3, 1).unwrap();
+    let i: Matrix<i32> = Matrix::from_vec(vec![i32::MIN, -1, 0, i32::MAX], 2, 2).unwrap();
+    let l: Matrix<i64> = Matrix::from_vec(vec![i64::MIN, 42], 1, 2).unwrap();
+    let c: Matrix<Complex<f32>> = Matrix::from_vec(
+        vec![Complex::new(1.5, -0.25), Complex::new(-3.0, 8.0)],
+        2,
+        1,
+    )
+    .unwrap();
+    let z: Matrix<Complex<f64>> = Matrix::from_vec(vec![Complex::new(0.1, 0.2)], 1, 1).unwrap();
+    let empty: Matrix = Matrix::new(0, 4);
+    for fortran_order in [false, true] {
+        assert_eq!(round_trip(&m, fortran_order), m);
+        assert_eq!(round_trip(&f, fortran_order), f);
+        assert_eq!(round_trip(&i, fortran_order), i);
+        assert_eq!(round_trip(&l, fortran_order), l);
+        assert_eq!(round_trip(&c, fortran_order), c);
+        assert_eq!(round_trip(&z, fortran_order), z);
+        assert_eq!(round_trip(&empty, fortran_order), empty);
+    }
+}
+
+#[test]
+fn test_header_matches_numpy() {
+    let m: Matrix = Matrix::new(2, 3);
+    let mut bytes = Vec::new();
+    m.view().write_npy(&mut bytes, false).unwrap();
+    // What numpy.save writes for numpy.zeros((2, 3)) on a little-endian machine
+    let dict = "{'descr': '<f8', 'fortran_order': False, 'shape': (2, 3), }";
+    assert_eq!(&bytes[..10], b"\x93NUMPY\x01\x00\x76\x00");
+    assert!(bytes[10..].starts_with(dict.as_bytes()));
+    assert_eq!(bytes.len(), 128 + 6 * 8);
+
+    let mut fortran = Vec::new();
+    let m: Matrix<i32> = Matrix::from_vec(vec![1, 2, 3, 4, 5, 6], 2, 3).unwrap();
+    m.view().write_npy(&mut fortran, true).unwrap();
+    let data: Vec<i32> = fortran[128..]
+        .chunks_exact(4)
+        .map(|b| i32::from_ne_bytes(b.try_into().unwrap()))
+        .collect();
+    assert_eq!(data, vec![1, 4, 2, 5, 3, 6]);
+}
+
+#[test]
+fn test_reads_headers_written_by_other_versions() {
+    // Big-endian data, double quotes, no trailing comma, 1-D shape
+    let data: Vec<u8> = [7i32, -1, 300]
+        .iter()
+        .flat_map(|v| v.to_be_bytes())
+        .collect();
+    let bytes = npy_file(
+        "{\"descr\": \">i4\", \"shape\": (3,), \"fortran_order\": True}",
+        &data,
+    );
+    let m: Matrix<i32> = Matrix::read_npy(&bytes[..]).unwrap();
+    assert_eq!(m, Matrix::from_vec(vec![7, -1, 300], 3, 1).unwrap());
+
+    // Python 2 long integers in the shape, Fortran order
+    let data: Vec<u8> = [1.0f32, 2.0, 3.0, 4.0]
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect();
+    let bytes = npy_file(
+        "{'descr': '<f4', 'fortran_order': True, 'shape': (2L, 2L), }",
+        &data,
+    );
+    let m: Matrix<f32> = Matrix::read_npy(&bytes[..]).unwrap();
+    assert_eq!(m, Matrix::from_vec(vec![1.0, 3.0, 2.0, 4.0], 2, 2).unwrap());
+
+    // Version 2.0 uses a four-byte header length
+    let dict = "{'descr': '<i8', 'fortran_order': False, 'shape': (1, 1), }\n";
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&[2, 0]);
+    bytes.extend_from_slice(&(dict.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(dict.as_bytes());
+    bytes.extend_from_slice(&9i64.to_le_bytes());
+    let m: Matrix<i64> = Matrix::read_npy(&bytes[..]).unwrap();
+    assert_eq!(m.data, vec![9]);
+}
+
+#[test]
+fn test_malformed_files_are_rejected() {
+    let dict = |descr: &str, shape: &str| {
+        format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+            descr, shape
+        )
+    };
+    let eight = [0u8; 8];
+    let cases = [
+        b"\x93NUMPX\x01\x00".to_vec(),
+        npy_file(&dict("<f4", "(1, 1)"), &eight[..4]),
+        npy_file(&dict("<u2", "(1, 1)"), &eight[..2]),
+        npy_file(&dict("<f8", "(1, 1, 1)"), &eight),
+        npy_file(&dict("<f8", "(2, 1)"), &eight),
+        npy_file(&dict("<f8", "(1, 1)"), &[0u8; 9]),
+        npy_file("{'descr': '<f8', 'shape': (1, 1), }", &eight),
+        npy_file(
+            "{'descr': '<f8', 'fortran_order': False, 'shape': (1, 1)",
+            &eight,
+        ),
+        npy_file(&format!("{}x", dict("<f8", "(1, 1)")), &eight),
+        // Non-ASCII type strings
+        npy_file(&dict("é8", "(1, 1)"), &eight),
+        npy_file(&dict("<é", "(1, 1)"), &eight),
+    ];
+    for bytes in cases {
+        match Matrix::<f64>::read_npy(&bytes[..]) {
+            Err(Error::Format { .. }) => {}
+            other => panic!("{:?} gave {:?}", String::from_utf8_lossy(&bytes), other),
+        }
+    }
+    let mut version = npy_file(&dict("<f8", "(1, 1)"), &eight);
+    version[6] = 4;
+    assert!(matches!(
+        Matrix::<f64>::read_npy(&version[..]),
+        Err(Error::Format { .. })
+    ));
+}
+
+#[test]
+fn test_npz_archives_hold_named_arrays() {
+    let dir = tempdir().unwrap();
+    let a: Matrix = Matrix::from_vec(vec![1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+    let b: Matrix = Matrix::from_vec(vec![5.0; 300], 20, 15).unwrap();
+    for compressed in [false, true] {
+        let path = dir.path().join(format!("pair_{}.npz", compressed));
+        npy::save_npz(&path, &[("a", &a), ("b", &b)], compressed).unwrap();
+        assert_eq!(npy::npz_names(&path).unwrap(), vec!["a", "b"]);
+        assert_eq!(Matrix::<f64>::load_npz(&path, "b").unwrap(), b);
+        assert_eq!(Matrix::<f64>::load_npz(&path, "a").unwrap(), a);
+        if compressed {
+            assert!(fs::metadata(&path).unwrap().len() < 300 * 8);
+        }
+
+        match Matrix::<f64>::load_npz(&path, "c") {
+            Err(Error::Format {
+                path: Some(p),
+                message,
+                ..
+            }) => {
+                assert_eq!(p, path);
+                assert!(message.contains("\"a\""), "{}", message);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            Matrix::<f64>::load(&path),
+            Err(Error::Format { .. })
+        ));
+        assert!(matches!(
+            Matrix::<f32>::load_npz(&path, "a"),
+            Err(Error::Format { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_load_and_save_pick_numpy_by_extension() {
+    let dir = tempdir().unwrap();
+    let m: Matrix<i64> = Matrix::from_vec(vec![1, -2, 3, -4, 5, -6], 3, 2).unwrap();
+
+    let npy_path = dir.path().join("m.npy");
+    m.save(&npy_path).unwrap();
+    assert_eq!(&fs::read(&npy_path).unwrap()[..6], &MAGIC);
+    assert!(npy::is_npy_file(&npy_path).unwrap());
+    assert_eq!(Matrix::<i64>::load(&npy_path).unwrap(), m);
+
+    let npz_path = dir.path().join("m.npz");
+    m.save(&npz_path).unwrap();
+    assert!(npy::is_npz_file(&npz_path).unwrap());
+    assert_eq!(npy::npz_names(&npz_path).unwrap(), vec![npy::DEFAULT_NAME]);
+    assert_eq!(Matrix::<i64>::load(&npz_path).unwrap(), m);
+
+    // Content decides on load, whatever the extension says
+    let renamed = dir.path().join("m.txt");
+    fs::rename(&npy_path, &renamed).unwrap();
+    assert_eq!(Matrix::<i64>::load(&renamed).unwrap(), m);
+
+    let garbage = dir.path().join("bad.npz");
+    fs::write(&garbage, b"PK\x03\x04 not really a zip").unwrap();
+    match Matrix::<i64>::load(&garbage) {
+        Err(Error::Format { path: Some(p), .. }) => assert_eq!(p, garbage),
+        other => panic!("unexpected {:?}", other),
+    }
+}
@@ -0,0 +1,94 @@
+// Tests for the std::ops implementations on Matrix
+
+mod common;
+
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use common::m;
+
+#[test]
+fn test_add_and_sub_all_operand_forms() {
+    let a = m(vec![1.0, 2.0, 3.0, 4.0], 2, 2);
+    let b = m(vec![10.0, 20.0, 30.0, 40.0], 2, 2);
+    let sum = m(vec![11.0, 22.0, 33.0, 44.0], 2, 2);
+    let diff = m(vec![-9.0, -18.0, -27.0, -36.0], 2, 2);
+
+    assert_eq!(&a + &b, sum);
+    assert_eq!(a.clone() + &b, sum);
+    assert_eq!(&a + b.clone(), sum);
+    assert_eq!(a.clone() + b.clone(), sum);
+
+    assert_eq!(&a - &b, diff);
+    assert_eq!(a.clone() - &b, diff);
+    assert_eq!(&a - b.clone(), diff);
+    assert_eq!(a.clone() - b.clone(), diff);
+
+    let mut c = a.clone();
+    c += &b;
+    c -= a;
+    assert_eq!(c, b);
+}
+
+#[test]
+fn test_matrix_product_operator() {
+    let a = m(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3);
+    let b = m(vec![7.0, 8.0, 9.0, 10.0, 11.0, 12.0], 3, 2);
+    let expected = m(vec![58.0, 64.0, 139.0, 154.0], 2, 2);
+
+    assert_eq!(&a * &b, expected);
+    assert_eq!(a.clone() * &b, expected);
+    assert_eq!(&a * b.clone(), expected);
+    assert_eq!(a * b, expected);
+}
+
+#[test]
+fn test_neg_and_scalar_multiplication() {
+    let a = m(vec![1.0, -2.0, 0.5, 4.0], 2, 2);
+    assert_eq!(-&a, m(vec![-1.0, 2.0, -0.5, -4.0], 2, 2));
+    assert_eq!(-a.clone(), &a * -1.0);
+    assert_eq!(2.0 * &a, m(vec![2.0, -4.0, 1.0, 8.0], 2, 2));
+    assert_eq!(a.clone() * 2.0, 2.0 * a.clone());
+
+    let mut b = a.clone();
+    b *= 0.0;
+    assert_eq!(b, Matrix::new(2, 2));
+
+    let ints = Matrix::from_vec(vec![1i64, 2, 3], 1, 3).unwrap();
+    assert_eq!((3 * ints).data, vec![3, 6, 9]);
+}
+
+#[test]
+fn test_index_and_index_mut() {
+    let mut a = m(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3);
+    assert_eq!(a[(0, 2)], 3.0);
+    assert_eq!(a[(1, 0)], 4.0);
+    a[(1, 2)] = -6.0;
+    assert_eq!(a.get(1, 2).unwrap(), -6.0);
+}
+
+#[test]
+#[should_panic(expected = "Index out of bounds")]
+fn test_index_checks_column_bound() {
+    let a = m(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3);
+    // Flat offset 3 exists, but column 3 does not
+    let _ = a[(0, 3)];
+}
+
+#[test]
+#[should_panic(expected = "different shapes")]
+fn test_add_shape_mismatch_panics() {
+    let _ = Matrix::<f64>::new(2, 3) + Matrix::new(3, 2);
+}
+
+#[test]
+#[should_panic(expected = "incompatible")]
+fn test_product_shape_mismatch_panics() {
+    let _ = Matrix::<f64>::new(2, 3) * Matrix::new(2, 3);
+}
+
+#[test]
+fn test_partial_eq_compares_shape() {
+    let row = m(vec![1.0, 2.0], 1, 2);
+    let col = m(vec![1.0, 2.0], 2, 1);
+    assert_ne!(row, col);
+    assert_eq!(row, row.clone());
+}
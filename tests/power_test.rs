@@ -0,0 +1,66 @@
+// Tests for matrix powers by repeated squaring
+
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+
+fn repeated_multiply<T: distribiuted_matrix_multiplication::element::Element>(
+    a: &Matrix<T>,
+    k: u32,
+) -> Matrix<T> {
+    let mut result = Matrix::identity(a.rows);
+    for _ in 0..k {
+        result = result.multiply(a).unwrap();
+    }
+    result
+}
+
+#[test]
+fn test_matches_repeated_multiplication() {
+    let a: Matrix<i64> = Matrix::from_vec(vec![1, 2, 0, -1, 1, 3, 2, 0, 1], 3, 3).unwrap();
+    for k in 0..=13 {
+        assert_eq!(a.pow(k).unwrap(), repeated_multiply(&a, k), "k = {}", k);
+    }
+}
+
+#[test]
+fn test_zero_and_first_power() {
+    let a: Matrix = Matrix::from_vec(vec![2.0, -1.0, 0.5, 3.0], 2, 2).unwrap();
+    assert_eq!(a.pow(0).unwrap(), Matrix::identity(2));
+    assert_eq!(a.pow(1).unwrap(), a);
+
+    let empty: Matrix = Matrix::new(0, 0);
+    assert_eq!(empty.pow(5).unwrap(), empty);
+}
+
+#[test]
+fn test_fibonacci_numbers() {
+    // [[1, 1], [1, 0]]^k holds F(k+1), F(k) and F(k-1)
+    let q: Matrix<i64> = Matrix::from_vec(vec![1, 1, 1, 0], 2, 2).unwrap();
+    let p = q.pow(90).unwrap();
+    assert_eq!(p[(0, 1)], 2_880_067_194_370_816_120);
+    assert_eq!(p[(0, 0)], p[(0, 1)] + p[(1, 1)]);
+}
+
+#[test]
+fn test_markov_chain_converges() {
+    // Rows of a stochastic matrix sum to one, and so do those of its powers
+    let p: Matrix = Matrix::from_vec(vec![0.9, 0.1, 0.5, 0.5], 2, 2).unwrap();
+    let limit = p.pow(200).unwrap();
+    for i in 0..2 {
+        assert!((limit[(i, 0)] - 5.0 / 6.0).abs() < 1e-12);
+        assert!((limit[(i, 1)] - 1.0 / 6.0).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_non_square_is_rejected() {
+    let a: Matrix = Matrix::new(2, 3);
+    assert!(matches!(
+        a.pow(2),
+        Err(Error::NotSquare {
+            rows: 2,
+            cols: 3,
+            ..
+        })
+    ));
+}
@@ -0,0 +1,143 @@
+// Tests for Householder QR and least-squares solves
+
+mod common;
+
+use distribiuted_matrix_multiplication::error::Error;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use num_complex::Complex;
+use common::assert_close;
+
+fn pseudo_random(rows: usize, cols: usize, seed: usize) -> Matrix {
+    let data = (0..rows * cols)
+        .map(|i| (((i + seed) * 7919 % 104729) as f64 / 104729.0) - 0.5)
+        .collect();
+    Matrix::from_vec(data, rows, cols).unwrap()
+}
+
+fn assert_orthonormal_columns(q: &Matrix) {
+    let gram = q.transpose().multiply(q).unwrap();
+    assert_close(&gram, &Matrix::identity(q.cols), 1e-12);
+}
+
+#[test]
+fn test_factors_reproduce_matrix() {
+    // Shapes below and above one panel, tall, wide and square
+    for &(m, n) in &[(5, 3), (3, 5), (40, 40), (100, 70), (50, 90)] {
+        let a = pseudo_random(m, n, m + n);
+        let qr = a.qr();
+        let (q, r) = (qr.q(), qr.r());
+        assert_eq!((q.rows, q.cols), (m, m.min(n)));
+        assert_eq!((r.rows, r.cols), (m.min(n), n));
+        for i in 0..r.rows {
+            for j in 0..i {
+                assert_eq!(r[(i, j)], 0.0);
+            }
+        }
+        assert_orthonormal_columns(&q);
+        assert_close(&q.multiply(&r).unwrap(), &a, 1e-12);
+    }
+}
+
+#[test]
+fn test_pivoted_factor_orders_diagonal() {
+    let a = pseudo_random(30, 20, 3);
+    let qr = a.qr_pivoted();
+    let perm = qr.permutation().unwrap().to_vec();
+    let r = qr.r();
+    for i in 1..20 {
+        assert!(r[(i, i)].abs() <= r[(i - 1, i - 1)].abs() + 1e-12);
+    }
+
+    let mut ap = Matrix::new(30, 20);
+    for (j, &src) in perm.iter().enumerate() {
+        for i in 0..30 {
+            ap[(i, j)] = a[(i, src)];
+        }
+    }
+    assert_close(&qr.q().multiply(&r).unwrap(), &ap, 1e-12);
+    assert!(a.qr().permutation().is_none());
+}
+
+#[test]
+fn test_apply_q_and_qh_round_trip() {
+    let a = pseudo_random(12, 5, 1);
+    let b = pseudo_random(12, 3, 2);
+    let qr = a.qr();
+    let qhb = qr.apply_qh(&b).unwrap();
+    assert_close(&qr.apply_q(&qhb).unwrap(), &b, 1e-12);
+    assert!(matches!(
+        qr.apply_qh(&Matrix::new(5, 1)),
+        Err(Error::DimensionMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_lstsq_overdetermined() {
+    // Fit y = 2 + 3x exactly, then with a residual orthogonal to the columns
+    let xs = [0.0, 1.0, 2.0, 3.0, 4.0];
+    let a = Matrix::from_vec(xs.iter().flat_map(|&x| [1.0, x]).collect(), 5, 2).unwrap();
+    let y = Matrix::from_vec(xs.iter().map(|&x| 2.0 + 3.0 * x).collect(), 5, 1).unwrap();
+    let expected = Matrix::from_vec(vec![2.0, 3.0], 2, 1).unwrap();
+    assert_close(&a.lstsq(&y).unwrap(), &expected, 1e-12);
+    assert_close(&a.qr().lstsq(&y).unwrap(), &expected, 1e-12);
+
+    // (1, -2, 0, 2, -1) is orthogonal to both columns
+    let noise = [1.0, -2.0, 0.0, 2.0, -1.0];
+    let noisy = Matrix::from_vec(
+        xs.iter()
+            .zip(noise)
+            .map(|(&x, e)| 2.0 + 3.0 * x + e)
+            .collect(),
+        5,
+        1,
+    )
+    .unwrap();
+    assert_close(&a.lstsq(&noisy).unwrap(), &expected, 1e-12);
+}
+
+#[test]
+fn test_rank_deficient() {
+    // Third column is the sum of the first two
+    let mut a = pseudo_random(8, 3, 5);
+    for i in 0..8 {
+        a[(i, 2)] = a[(i, 0)] + a[(i, 1)];
+    }
+    assert_eq!(a.qr_pivoted().rank(), 2);
+
+    let x = a.lstsq(&a.get_col_chunk(2, 1).unwrap()).unwrap();
+    let residual = &a.multiply(&x).unwrap() - &a.get_col_chunk(2, 1).unwrap();
+    assert!(residual.data.iter().all(|r| r.abs() < 1e-12));
+    assert_eq!(x.data.iter().filter(|v| **v == 0.0).count(), 1);
+
+    assert!(matches!(
+        a.qr().lstsq(&Matrix::new(8, 1)),
+        Err(Error::Singular { .. })
+    ));
+}
+
+#[test]
+fn test_complex() {
+    let c = |re: f64, im: f64| Complex::new(re, im);
+    let a = Matrix::from_vec(
+        vec![
+            c(1.0, 1.0),
+            c(2.0, 0.0),
+            c(0.0, -1.0),
+            c(1.0, 0.5),
+            c(3.0, 2.0),
+            c(-1.0, 0.0),
+        ],
+        3,
+        2,
+    )
+    .unwrap();
+    let qr = a.qr();
+    let product = qr.q().multiply(&qr.r()).unwrap();
+    for (p, e) in product.data.iter().zip(&a.data) {
+        assert!((p - e).norm() < 1e-12);
+    }
+    for i in 0..2 {
+        // Reflectors leave a real diagonal in R
+        assert!(qr.r()[(i, i)].im.abs() < 1e-12);
+    }
+}
@@ -0,0 +1,151 @@
+// Tests that reproducible products do not depend on how the work is split
+
+use distribiuted_matrix_multiplication::accumulate::Accumulation;
+use distribiuted_matrix_multiplication::kernel::{Algorithm, GemmSpec, Transpose};
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use rayon::ThreadPoolBuilder;
+
+fn sample(rows: usize, cols: usize, seed: u64) -> Matrix {
+    // Values spread over many magnitudes so that summation order matters
+    let mut state = seed;
+    let data = (0..rows * cols)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let mantissa = (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5;
+            mantissa * 10f64.powi((state % 13) as i32 - 6)
+        })
+        .collect();
+    Matrix::from_vec(data, rows, cols).unwrap()
+}
+
+fn reproducible() -> GemmSpec {
+    GemmSpec::default().with_reproducible(true)
+}
+
+/// Compute the product as `workers` row chunks, each on `threads` threads,
+/// the way the coordinator splits it
+fn split_product(
+    spec: &GemmSpec,
+    a: &Matrix,
+    b: &Matrix,
+    workers: usize,
+    threads: usize,
+    algorithm: Algorithm,
+) -> Matrix {
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .unwrap();
+    let rows_per_worker = a.rows.div_ceil(workers);
+    let mut data = Vec::new();
+    for start in (0..a.rows).step_by(rows_per_worker) {
+        let rows = rows_per_worker.min(a.rows - start);
+        let chunk = a.row_chunk(start, rows).unwrap();
+        let mut result = Matrix::new(rows, b.cols);
+        Matrix::gemm_with_algorithm(
+            spec,
+            chunk,
+            b,
+            &mut result.view_mut(),
+            algorithm,
+            Some(&pool),
+        )
+        .unwrap();
+        data.extend(result.data);
+    }
+    Matrix::from_vec(data, a.rows, b.cols).unwrap()
+}
+
+fn same_bits(x: &Matrix, y: &Matrix) -> bool {
+    x.data.len() == y.data.len()
+        && x.data
+            .iter()
+            .zip(&y.data)
+            .all(|(a, b)| a.to_bits() == b.to_bits())
+}
+
+#[test]
+fn test_identical_across_workers_and_threads() {
+    let a = sample(37, 301, 1);
+    let b = sample(301, 23, 2);
+    let expected = Matrix::gemm(&reproducible(), &a, &b, None).unwrap();
+    for workers in [1, 2, 3, 5, 8, 37] {
+        for threads in [1, 2, 4] {
+            let c = split_product(
+                &reproducible(),
+                &a,
+                &b,
+                workers,
+                threads,
+                Algorithm::Blocked,
+            );
+            assert!(
+                same_bits(&c, &expected),
+                "{} workers, {} threads",
+                workers,
+                threads
+            );
+        }
+    }
+}
+
+#[test]
+fn test_algorithm_does_not_matter() {
+    let a = sample(64, 64, 3);
+    let b = sample(64, 64, 4);
+    let expected = Matrix::gemm(&reproducible(), &a, &b, None).unwrap();
+    let strassen = Algorithm::Strassen { crossover: 8 };
+    for workers in [1, 3] {
+        let c = split_product(&reproducible(), &a, &b, workers, 2, strassen);
+        assert!(same_bits(&c, &expected));
+    }
+}
+
+#[test]
+fn test_every_accumulation_mode_is_reproducible() {
+    let a = sample(29, 257, 5);
+    let b = sample(257, 17, 6);
+    for mode in Accumulation::ALL {
+        let spec = reproducible().with_accumulation(mode);
+        let expected = Matrix::gemm(&spec, &a, &b, None).unwrap();
+        for (workers, threads) in [(2, 3), (4, 1), (29, 2)] {
+            let c = split_product(&spec, &a, &b, workers, threads, Algorithm::Blocked);
+            assert!(same_bits(&c, &expected), "{}", mode);
+        }
+    }
+}
+
+#[test]
+fn test_documented_evaluation_order() {
+    // Each entry is beta * c + alpha * (ascending running sum of products)
+    let a = sample(7, 45, 7);
+    let b = sample(45, 5, 8);
+    let c = sample(7, 5, 9);
+    let (alpha, beta) = (0.75, -1.25);
+    let spec = reproducible().with_alpha(alpha).with_beta(beta);
+    let result = Matrix::gemm(&spec, &a, &b, Some(c.clone())).unwrap();
+    for i in 0..7 {
+        for j in 0..5 {
+            let mut dot = 0.0;
+            for p in 0..45 {
+                dot += a[(i, p)] * b[(p, j)];
+            }
+            let expected = beta * c[(i, j)] + alpha * dot;
+            assert_eq!(result[(i, j)].to_bits(), expected.to_bits());
+        }
+    }
+}
+
+#[test]
+fn test_transposed_operands() {
+    let a = sample(120, 31, 10);
+    let b = sample(19, 120, 11);
+    let spec = reproducible()
+        .with_trans_a(Transpose::Yes)
+        .with_trans_b(Transpose::Yes);
+    let expected = Matrix::gemm(&reproducible(), &a.transpose(), &b.transpose(), None).unwrap();
+    let c = Matrix::gemm(&spec, &a, &b, None).unwrap();
+    assert!(same_bits(&c, &expected));
+}
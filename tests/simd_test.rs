@@ -0,0 +1,82 @@
+// Tests for the SIMD microkernels and runtime dispatch
+
+use distribiuted_matrix_multiplication::kernel::{MR, NR};
+use distribiuted_matrix_multiplication::simd::{self, SimdLevel};
+
+const LEVELS: [SimdLevel; 4] = [
+    SimdLevel::Scalar,
+    SimdLevel::Sse2,
+    SimdLevel::Avx2,
+    SimdLevel::Avx512,
+];
+
+fn panels(kb: usize) -> (Vec<f64>, Vec<f64>) {
+    let a = (0..kb * MR)
+        .map(|x| ((x * 31) % 19) as f64 * 0.37 - 3.1)
+        .collect();
+    let b = (0..kb * NR)
+        .map(|x| ((x * 17) % 23) as f64 * 0.11 + 0.5)
+        .collect();
+    (a, b)
+}
+
+#[test]
+fn test_every_level_matches_scalar_reference() {
+    let ldc = NR + 3;
+    for kb in [1, 2, 7, 64, 257] {
+        let (a, b) = panels(kb);
+        let mut expected = vec![1.0; MR * ldc];
+        simd::scalar_microkernel(kb, &a, &b, &mut expected, ldc, MR, NR);
+
+        for level in LEVELS {
+            let mut c = vec![1.0; MR * ldc];
+            simd::microkernel(level)(kb, &a, &b, &mut c, ldc, MR, NR);
+            for (x, y) in c.iter().zip(&expected) {
+                assert!(
+                    (x - y).abs() <= 1e-12 * y.abs().max(1.0),
+                    "{} differs from scalar at kb={}: {} vs {}",
+                    level,
+                    kb,
+                    x,
+                    y
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_partial_tile_leaves_rest_of_c_untouched() {
+    let ldc = NR;
+    let (a, b) = panels(5);
+    for level in LEVELS {
+        let mut c = vec![-7.0; MR * ldc];
+        simd::microkernel(level)(5, &a, &b, &mut c, ldc, 2, 3);
+        for i in 0..MR {
+            for j in 0..NR {
+                let touched = i < 2 && j < 3;
+                assert_eq!(
+                    c[i * ldc + j] != -7.0,
+                    touched,
+                    "{} at ({}, {})",
+                    level,
+                    i,
+                    j
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_active_level_is_supported() {
+    assert!(SimdLevel::active() <= SimdLevel::detect());
+}
+
+#[test]
+fn test_parse_level_names() {
+    assert_eq!(SimdLevel::parse("AVX2"), Some(SimdLevel::Avx2));
+    assert_eq!(SimdLevel::parse(" avx512 "), Some(SimdLevel::Avx512));
+    assert_eq!(SimdLevel::parse("scalar"), Some(SimdLevel::Scalar));
+    assert_eq!(SimdLevel::parse("neon"), None);
+}
@@ -0,0 +1,79 @@
+// Tests for Strassen-Winograd multiplication
+
+mod common;
+
+use distribiuted_matrix_multiplication::kernel::Algorithm;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use common::test_matrix;
+
+#[test]
+fn test_strassen_power_of_two() {
+    let a = test_matrix(64, 64, 1);
+    let b = test_matrix(64, 64, 2);
+    let expected = a.multiply(&b).unwrap();
+    let result = a.multiply_strassen(&b, 4).unwrap();
+    assert_eq!(result.data, expected.data);
+}
+
+#[test]
+fn test_strassen_odd_and_rectangular_shapes() {
+    // Every combination of odd/even dimensions exercises a different peel
+    for &(m, k, n) in &[
+        (33, 17, 21),
+        (40, 31, 26),
+        (27, 48, 35),
+        (50, 50, 13),
+        (9, 70, 9),
+    ] {
+        let a = test_matrix(m, k, m);
+        let b = test_matrix(k, n, n);
+        let expected = a.multiply(&b).unwrap();
+        let result = a.multiply_strassen(&b, 2).unwrap();
+        assert_eq!(result.rows, m);
+        assert_eq!(result.cols, n);
+        assert_eq!(result.data, expected.data, "mismatch for {}x{}x{}", m, k, n);
+    }
+}
+
+#[test]
+fn test_strassen_below_crossover_uses_blocked_kernel() {
+    let a = test_matrix(10, 12, 3);
+    let b = test_matrix(12, 14, 4);
+    let result = a.multiply_strassen(&b, 256).unwrap();
+    assert_eq!(result.data, a.multiply(&b).unwrap().data);
+}
+
+#[test]
+fn test_strassen_chunks_on_thread_pool() {
+    let a = test_matrix(45, 38, 5);
+    let b = test_matrix(38, 52, 6);
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(3)
+        .build()
+        .unwrap();
+    let result = Matrix::multiply_chunks_with_algorithm(
+        &a,
+        &b,
+        Algorithm::Strassen { crossover: 8 },
+        Some(&pool),
+    )
+    .unwrap();
+    assert_eq!(result.data, a.multiply(&b).unwrap().data);
+}
+
+#[test]
+fn test_strassen_incompatible_dimensions() {
+    let a = test_matrix(4, 3, 7);
+    let b = test_matrix(4, 3, 8);
+    assert!(a.multiply_strassen(&b, 2).is_err());
+}
+
+#[test]
+fn test_algorithm_parse() {
+    assert_eq!(Algorithm::parse("blocked", 64).unwrap(), Algorithm::Blocked);
+    assert_eq!(
+        Algorithm::parse("strassen", 64).unwrap(),
+        Algorithm::Strassen { crossover: 64 }
+    );
+    assert!(Algorithm::parse("winograd", 64).is_err());
+}
@@ -0,0 +1,78 @@
+// Tests for blocked and in-place transposition
+
+mod common;
+
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use distribiuted_matrix_multiplication::transpose;
+use common::sequential;
+
+fn naive_transpose(m: &Matrix) -> Matrix {
+    let mut t = Matrix::new(m.cols, m.rows);
+    for i in 0..m.rows {
+        for j in 0..m.cols {
+            t.set(j, i, m.get(i, j).unwrap()).unwrap();
+        }
+    }
+    t
+}
+
+#[test]
+fn test_transpose_small() {
+    let m = sequential(2, 3);
+    let t = m.transpose();
+    assert_eq!(t.rows, 3);
+    assert_eq!(t.cols, 2);
+    assert_eq!(t.data, vec![0.0, 3.0, 1.0, 4.0, 2.0, 5.0]);
+}
+
+#[test]
+fn test_transpose_across_tile_boundaries() {
+    // Sizes straddling the tile edge exercise the partial tiles
+    for &(rows, cols) in &[(33, 65), (64, 31), (1, 70), (70, 1), (0, 5)] {
+        let m = sequential(rows, cols);
+        assert_eq!(m.transpose(), naive_transpose(&m), "{}x{}", rows, cols);
+        assert_eq!(m.transpose().transpose(), m);
+    }
+}
+
+#[test]
+fn test_transpose_in_place_square() {
+    for n in [1, 2, 7, 32, 45] {
+        let m = sequential(n, n);
+        let mut t = m.clone();
+        t.transpose_in_place();
+        assert_eq!(t, naive_transpose(&m), "{}x{}", n, n);
+    }
+}
+
+#[test]
+fn test_transpose_in_place_rectangular() {
+    for &(rows, cols) in &[(2, 3), (3, 2), (5, 17), (40, 33), (1, 9), (9, 1)] {
+        let m = sequential(rows, cols);
+        let mut t = m.clone();
+        t.transpose_in_place();
+        assert_eq!(t.rows, cols);
+        assert_eq!(t.cols, rows);
+        assert_eq!(t, naive_transpose(&m), "{}x{}", rows, cols);
+    }
+}
+
+#[test]
+fn test_transpose_of_strided_view() {
+    let m = sequential(6, 8);
+    let block = m.block(1, 2, 4, 5).unwrap();
+    let mut dst = vec![0.0; 20];
+    transpose::transpose_into(block, &mut dst);
+    assert_eq!(dst, naive_transpose(&block.to_matrix()).data);
+    assert_eq!(block.transpose().to_matrix().data, dst);
+}
+
+#[test]
+fn test_get_col_chunk_matches_columns() {
+    let m = sequential(40, 50);
+    let chunk = m.get_col_chunk(3, 35).unwrap();
+    assert_eq!(chunk.rows, 40);
+    assert_eq!(chunk.cols, 35);
+    assert_eq!(chunk.get(39, 34).unwrap(), m.get(39, 37).unwrap());
+    assert!(m.get_col_chunk(40, 11).is_err());
+}
@@ -0,0 +1,124 @@
+// Tests for borrowed, strided matrix views
+
+mod common;
+
+use distribiuted_matrix_multiplication::accumulate::Accumulation;
+use distribiuted_matrix_multiplication::kernel::GemmSpec;
+use distribiuted_matrix_multiplication::matrix::Matrix;
+use distribiuted_matrix_multiplication::view::{MatrixView, MatrixViewMut};
+use tempfile::NamedTempFile;
+use common::sequential;
+
+#[test]
+fn test_block_view_addresses_original_data() {
+    let m = sequential(4, 5);
+    let block = m.block(1, 2, 2, 3).unwrap();
+    assert_eq!(block.rows(), 2);
+    assert_eq!(block.cols(), 3);
+    assert_eq!(block.get(0, 0).unwrap(), 7.0);
+    assert_eq!(block.get(1, 2).unwrap(), 14.0);
+    assert!(block.get(2, 0).is_err());
+    assert!(!block.is_contiguous());
+
+    let inner = block.block(1, 1, 1, 2).unwrap();
+    assert_eq!(inner.to_matrix().data, vec![13.0, 14.0]);
+    assert!(m.block(3, 0, 2, 1).is_err());
+}
+
+#[test]
+fn test_row_chunk_is_contiguous_and_col_chunk_is_not() {
+    let m = sequential(4, 3);
+    let rows = m.row_chunk(1, 2).unwrap();
+    assert_eq!(rows.as_slice().unwrap(), &[3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+    assert_eq!(rows.to_matrix().data, m.get_row_chunk(1, 2).unwrap().data);
+
+    let cols = m.col_chunk(1, 2).unwrap();
+    assert!(cols.as_slice().is_none());
+    assert_eq!(cols.row(2).unwrap(), &[7.0, 8.0]);
+    assert_eq!(cols.to_matrix().data, m.get_col_chunk(1, 2).unwrap().data);
+}
+
+#[test]
+fn test_transpose_and_custom_strides() {
+    let m = sequential(2, 3);
+    let t = m.view().transpose();
+    assert_eq!((t.rows(), t.cols()), (3, 2));
+    assert_eq!(t.to_matrix().data, vec![0.0, 3.0, 1.0, 4.0, 2.0, 5.0]);
+    assert!(t.row(0).is_none());
+
+    // Every other element of a buffer, viewed as 2x2
+    let data: Vec<f64> = (0..8).map(|x| x as f64).collect();
+    let v = MatrixView::new(&data, 2, 2, 4, 2).unwrap();
+    assert_eq!(v.to_matrix().data, vec![0.0, 2.0, 4.0, 6.0]);
+    assert!(MatrixView::new(&data, 2, 2, 4, 4).is_err());
+    assert!(MatrixView::row_major(&data, 3, 3).is_err());
+}
+
+#[test]
+fn test_multiply_views_matches_copies() {
+    let a = sequential(6, 7);
+    let b = sequential(7, 5);
+
+    let a_block = a.block(1, 2, 4, 3).unwrap();
+    let b_block = b.block(2, 1, 3, 4).unwrap();
+    let from_views = Matrix::multiply_chunks(a_block, b_block).unwrap();
+    let from_copies = a_block.to_matrix().multiply(&b_block.to_matrix()).unwrap();
+    assert_eq!(from_views.data, from_copies.data);
+
+    // A^T * A through a transposed view
+    let ata = Matrix::multiply_chunks(a.view().transpose(), &a).unwrap();
+    let expected = a.view().transpose().to_matrix().multiply(&a).unwrap();
+    assert_eq!(ata.data, expected.data);
+
+    assert!(Matrix::multiply_chunks(a_block, a_block).is_err());
+}
+
+#[test]
+fn test_mutable_block_writes_through() {
+    let mut m: Matrix = Matrix::new(3, 4);
+    let source = sequential(2, 2);
+    {
+        let mut block = m.block_mut(1, 1, 2, 2).unwrap();
+        block.copy_from(&source.view()).unwrap();
+        block.set(0, 0, -1.0).unwrap();
+        assert!(block.set(2, 0, 0.0).is_err());
+    }
+    assert_eq!(
+        m.data,
+        vec![0.0, 0.0, 0.0, 0.0, 0.0, -1.0, 1.0, 0.0, 0.0, 2.0, 3.0, 0.0]
+    );
+
+    let mut data = vec![0.0; 6];
+    let mut column = MatrixViewMut::new(&mut data, 3, 1, 2, 1).unwrap();
+    column.fill(9.0);
+    assert!(column.copy_from(&source.view()).is_err());
+    assert_eq!(data, vec![9.0, 0.0, 9.0, 0.0, 9.0, 0.0]);
+}
+
+#[test]
+fn test_gemm_into_a_view_with_zero_row_stride() {
+    let a = sequential(1, 2);
+    let b = sequential(2, 3);
+    for accumulation in [Accumulation::Naive, Accumulation::Pairwise] {
+        let mut data = vec![1.0; 3];
+        let mut c = MatrixViewMut::new(&mut data, 1, 3, 0, 1).unwrap();
+        let spec = GemmSpec::default()
+            .with_beta(1.0)
+            .with_accumulation(accumulation);
+        Matrix::gemm_into(&spec, &a, &b, &mut c).unwrap();
+        assert_eq!(data, vec![4.0, 5.0, 6.0]);
+    }
+}
+
+#[test]
+fn test_save_view_to_file() {
+    let m = sequential(3, 3);
+    let file = NamedTempFile::new().unwrap();
+    m.col_chunk(1, 2)
+        .unwrap()
+        .save_to_file(file.path())
+        .unwrap();
+
+    let loaded: Matrix = Matrix::load_from_file(file.path()).unwrap();
+    assert_eq!(loaded.data, vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0]);
+}